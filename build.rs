@@ -0,0 +1,29 @@
+use prost::Message as _;
+use protobuf::Message as _;
+
+const PROTO_ROOT: &str = "proto";
+const PROTOS: [&str; 1] = ["proto/sleeping_bag_locator/v1/api.proto"];
+
+/// Protos are parsed in Rust, so building doesn't depend on `protoc` being installed
+fn main() {
+    println!("cargo:rerun-if-changed={PROTO_ROOT}");
+
+    let descriptors = protobuf_parse::Parser::new()
+        .pure()
+        .include(PROTO_ROOT)
+        .inputs(PROTOS)
+        .file_descriptor_set()
+        .expect("Protos must be valid");
+    let descriptors = prost_types::FileDescriptorSet::decode(
+        descriptors
+            .write_to_bytes()
+            .expect("Descriptors must be serializable")
+            .as_slice(),
+    )
+    .expect("Descriptors must be the same in both protobuf implementations");
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds(descriptors)
+        .expect("Services must be generated");
+}
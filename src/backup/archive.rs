@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    dtos::{BackupItem, BackupTrashedItem, BackupUser},
+    errors::{ExportError, RestoreError},
+};
+use crate::dao::{
+    CreateUserValidationError,
+    Item,
+    ItemBuilderError,
+    ItemsDao,
+    ItemsSnapshot,
+    RestoreMode,
+    TrashedItem,
+    User,
+    UsersDao,
+    ValidationErrors,
+};
+
+const ARCHIVE_FORMAT: &str = "sleeping-bag-locator-backup";
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Items,
+    Trash,
+    Users,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SectionManifest {
+    name: Section,
+    records: usize,
+    sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    version: u32,
+    app_version: String,
+    created_at: NaiveDateTime,
+    sections: Vec<SectionManifest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "section", content = "data", rename_all = "lowercase")]
+enum Record {
+    Items(BackupItem),
+    Trash(BackupTrashedItem),
+    Users(BackupUser),
+}
+
+impl Record {
+    fn section(&self) -> Section {
+        match self {
+            Record::Items(_) => Section::Items,
+            Record::Trash(_) => Section::Trash,
+            Record::Users(_) => Section::Users,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SectionDigest {
+    records: usize,
+    hasher: Sha256,
+}
+
+impl SectionDigest {
+    fn update(&mut self, line: &str) {
+        self.records += 1;
+        self.hasher.update(line.as_bytes());
+        self.hasher.update(b"\n");
+    }
+
+    fn finalize(self, name: Section) -> SectionManifest {
+        SectionManifest {
+            name,
+            records: self.records,
+            sha256: format!("{:x}", self.hasher.finalize()),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct RestoreReport {
+    pub items: usize,
+    pub trash: usize,
+    pub users: usize,
+}
+
+pub async fn export(
+    items: &(dyn ItemsDao + Send + Sync),
+    users: &(dyn UsersDao + Send + Sync),
+) -> Result<String, ExportError> {
+    // Listing page by page would skip or repeat records, which are changed meanwhile
+    let ItemsSnapshot {
+        items: items_snapshot,
+        trash: trash_snapshot,
+    } = items.snapshot().await?;
+
+    let mut items_digest = SectionDigest::default();
+    let mut items_lines = Vec::new();
+    for entity in items_snapshot {
+        let line = serde_json::to_string(&Record::Items(entity.into()))?;
+        items_digest.update(&line);
+        items_lines.push(line);
+    }
+
+    let mut trash_digest = SectionDigest::default();
+    let mut trash_lines = Vec::new();
+    for entity in trash_snapshot {
+        let line = serde_json::to_string(&Record::Trash(entity.into()))?;
+        trash_digest.update(&line);
+        trash_lines.push(line);
+    }
+
+    let mut users_digest = SectionDigest::default();
+    let mut users_lines = Vec::new();
+    for entity in users.snapshot().await? {
+        let line = serde_json::to_string(&Record::Users(entity.into()))?;
+        users_digest.update(&line);
+        users_lines.push(line);
+    }
+
+    let manifest = Manifest {
+        format: ARCHIVE_FORMAT.to_owned(),
+        version: ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_owned(),
+        created_at: Utc::now().naive_utc(),
+        sections: vec![
+            items_digest.finalize(Section::Items),
+            trash_digest.finalize(Section::Trash),
+            users_digest.finalize(Section::Users),
+        ],
+    };
+
+    let mut archive = serde_json::to_string(&manifest)?;
+    archive.push('\n');
+    for line in items_lines.iter().chain(&trash_lines).chain(&users_lines) {
+        archive.push_str(line);
+        archive.push('\n');
+    }
+
+    Ok(archive)
+}
+
+/// Applies the archive either as a whole or not at all
+async fn apply(
+    items: &(dyn ItemsDao + Send + Sync),
+    users: &(dyn UsersDao + Send + Sync),
+    restored_items: ItemsSnapshot,
+    restored_users: Vec<User>,
+    mode: RestoreMode,
+) -> Result<(), RestoreError> {
+    let previous_items = items.snapshot().await?;
+    items.restore(restored_items, mode).await?;
+
+    if let Err(err) = users.restore(restored_users, mode).await {
+        items
+            .restore(previous_items, RestoreMode::Replace)
+            .await
+            .map_err(|x| RestoreError::Rollback {
+                internal: x.to_string(),
+            })?;
+
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+pub async fn restore(
+    items: &(dyn ItemsDao + Send + Sync),
+    users: &(dyn UsersDao + Send + Sync),
+    archive: &str,
+    mode: RestoreMode,
+) -> Result<RestoreReport, RestoreError> {
+    let mut lines = archive
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, manifest) = lines.next().ok_or(RestoreError::MissingManifest)?;
+    let manifest: Manifest =
+        serde_json::from_str(manifest).map_err(|x| RestoreError::InvalidManifest {
+            internal: x.to_string(),
+        })?;
+
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(RestoreError::UnknownFormat {
+            format: manifest.format,
+        });
+    }
+    if manifest.version != ARCHIVE_VERSION {
+        return Err(RestoreError::UnsupportedVersion {
+            version: manifest.version,
+        });
+    }
+
+    let mut digests: HashMap<Section, SectionDigest> = HashMap::new();
+    let mut restored_items: Vec<Item> = Vec::new();
+    let mut restored_trash: Vec<TrashedItem> = Vec::new();
+    let mut restored_users: Vec<User> = Vec::new();
+
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let record: Record =
+            serde_json::from_str(line).map_err(|x| RestoreError::InvalidRecord {
+                line: line_number,
+                internal: x.to_string(),
+            })?;
+
+        digests.entry(record.section()).or_default().update(line);
+
+        match record {
+            Record::Items(entity) => restored_items.push(entity.try_into().map_err(
+                |x: ValidationErrors<ItemBuilderError>| RestoreError::InvalidRecord {
+                    line: line_number,
+                    internal: x.to_string(),
+                },
+            )?),
+            Record::Trash(entity) => restored_trash.push(entity.try_into().map_err(
+                |x: ValidationErrors<ItemBuilderError>| RestoreError::InvalidRecord {
+                    line: line_number,
+                    internal: x.to_string(),
+                },
+            )?),
+            Record::Users(entity) => restored_users.push(entity.try_into().map_err(
+                |x: ValidationErrors<CreateUserValidationError>| RestoreError::InvalidRecord {
+                    line: line_number,
+                    internal: x.to_string(),
+                },
+            )?),
+        }
+    }
+
+    if let Some(section) = digests
+        .keys()
+        .find(|section| !manifest.sections.iter().any(|x| x.name == **section))
+    {
+        return Err(RestoreError::UnknownSection { section: *section });
+    }
+
+    for expected in &manifest.sections {
+        let actual = digests
+            .remove(&expected.name)
+            .unwrap_or_default()
+            .finalize(expected.name);
+
+        if actual.records != expected.records {
+            return Err(RestoreError::RecordsCountMismatch {
+                section: expected.name,
+                expected: expected.records,
+                actual: actual.records,
+            });
+        }
+        if actual.sha256 != expected.sha256 {
+            return Err(RestoreError::ChecksumMismatch {
+                section: expected.name,
+            });
+        }
+    }
+
+    let report = RestoreReport {
+        items: restored_items.len(),
+        trash: restored_trash.len(),
+        users: restored_users.len(),
+    };
+
+    apply(
+        items,
+        users,
+        ItemsSnapshot {
+            items: restored_items,
+            trash: restored_trash,
+        },
+        restored_users,
+        mode,
+    )
+    .await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::async_trait;
+    use fake::{Fake, Faker};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::dao::{
+        CreateItemParams,
+        CreateUserError,
+        CreateUserParams,
+        DeleteUserError,
+        GetUserError,
+        ItemsHashMapDao,
+        ListUsersError,
+        Pagination,
+        Precondition,
+        RestoreUsersError,
+        UpdateUserError,
+        UpdateUserParams,
+        UserAuthType,
+        UsersHashMapDao,
+        UsersHealthError,
+    };
+
+    /// Fails to restore users, the only call it's expected to get
+    struct FailingUsersDao;
+
+    #[async_trait]
+    impl UsersDao for FailingUsersDao {
+        async fn list(&self, _: Pagination) -> Result<Vec<User>, ListUsersError> {
+            unreachable!()
+        }
+
+        async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+            unreachable!()
+        }
+
+        async fn create(&self, _: CreateUserParams) -> Result<User, CreateUserError> {
+            unreachable!()
+        }
+
+        async fn get(&self, _: Uuid) -> Result<User, GetUserError> {
+            unreachable!()
+        }
+
+        async fn find_by_external_id(
+            &self,
+            _: UserAuthType,
+            _: &str,
+        ) -> Result<Option<User>, ListUsersError> {
+            unreachable!()
+        }
+
+        async fn update(
+            &self,
+            _: Uuid,
+            _: UpdateUserParams,
+            _: Precondition,
+        ) -> Result<User, UpdateUserError> {
+            unreachable!()
+        }
+
+        async fn delete(&self, _: Uuid, _: Precondition) -> Result<(), DeleteUserError> {
+            unreachable!()
+        }
+
+        async fn restore(&self, _: Vec<User>, _: RestoreMode) -> Result<(), RestoreUsersError> {
+            Err(RestoreUsersError::UnexpectedError)
+        }
+
+        async fn health(&self) -> Result<(), UsersHealthError> {
+            unreachable!()
+        }
+    }
+
+    const ITEMS_COUNT: usize = 101;
+    const TRASHED_COUNT: usize = 2;
+
+    async fn populated() -> (ItemsHashMapDao, UsersHashMapDao) {
+        let items = ItemsHashMapDao::new();
+        let users = UsersHashMapDao::new();
+
+        for index in 0..ITEMS_COUNT {
+            let entity = items.create(Faker.fake()).await.unwrap();
+            if index < TRASHED_COUNT {
+                items.delete(entity.id(), Precondition::None).await.unwrap();
+            }
+        }
+        for _ in 0..3 {
+            users.create(Faker.fake()).await.unwrap();
+        }
+
+        (items, users)
+    }
+
+    #[tokio::test]
+    async fn export_and_restore() {
+        let (items, users) = populated().await;
+        let archive = export(&items, &users).await.unwrap();
+        println!("{archive}");
+
+        assert_eq!(archive.lines().count(), 1 + ITEMS_COUNT + 3);
+
+        let target_items = ItemsHashMapDao::new();
+        let target_users = UsersHashMapDao::new();
+        let report = restore(&target_items, &target_users, &archive, RestoreMode::Merge)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report,
+            RestoreReport {
+                items: ITEMS_COUNT - TRASHED_COUNT,
+                trash: TRASHED_COUNT,
+                users: 3
+            }
+        );
+        assert_eq!(
+            export(&target_items, &target_users)
+                .await
+                .unwrap()
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>(),
+            archive.lines().skip(1).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_replace() {
+        let (items, users) = populated().await;
+        let archive = export(&items, &users).await.unwrap();
+
+        let target_items = ItemsHashMapDao::new();
+        let existing = target_items
+            .create(Faker.fake::<CreateItemParams>())
+            .await
+            .unwrap();
+        let trashed = target_items
+            .create(Faker.fake::<CreateItemParams>())
+            .await
+            .unwrap();
+        target_items
+            .delete(trashed.id(), Precondition::None)
+            .await
+            .unwrap();
+        let target_users = UsersHashMapDao::new();
+
+        restore(&target_items, &target_users, &archive, RestoreMode::Replace)
+            .await
+            .unwrap();
+
+        assert!(target_items.get(existing.id()).await.is_err());
+        assert_eq!(
+            target_items.snapshot().await.unwrap(),
+            items.snapshot().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_users_roll_back_items() {
+        let (items, users) = populated().await;
+        let archive = export(&items, &users).await.unwrap();
+
+        let target_items = ItemsHashMapDao::new();
+        let existing = target_items
+            .create(Faker.fake::<CreateItemParams>())
+            .await
+            .unwrap();
+
+        let result = restore(
+            &target_items,
+            &FailingUsersDao,
+            &archive,
+            RestoreMode::Replace,
+        )
+        .await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(RestoreError::UsersStorage {
+                internal: RestoreUsersError::UnexpectedError.to_string()
+            })
+        );
+        assert_eq!(
+            target_items.snapshot().await.unwrap(),
+            ItemsSnapshot {
+                items: vec![existing],
+                trash: Vec::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn tampered_archive() {
+        let (items, users) = populated().await;
+        let archive = export(&items, &users).await.unwrap();
+        let tampered = archive.replacen("\"location\":\"", "\"location\":\"x", 1);
+
+        let result = restore(
+            &ItemsHashMapDao::new(),
+            &UsersHashMapDao::new(),
+            &tampered,
+            RestoreMode::Merge,
+        )
+        .await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(RestoreError::ChecksumMismatch {
+                section: Section::Items
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_records() {
+        let (items, users) = populated().await;
+        let archive = export(&items, &users).await.unwrap();
+        let truncated = archive.lines().take(2).collect::<Vec<_>>().join("\n");
+
+        let result = restore(
+            &ItemsHashMapDao::new(),
+            &UsersHashMapDao::new(),
+            &truncated,
+            RestoreMode::Merge,
+        )
+        .await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(RestoreError::RecordsCountMismatch {
+                section: Section::Items,
+                expected: ITEMS_COUNT - TRASHED_COUNT,
+                actual: 1
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_format() {
+        let result = restore(
+            &ItemsHashMapDao::new(),
+            &UsersHashMapDao::new(),
+            "{\"format\":\"zip\",\"version\":1,\"app_version\":\"0.1.0\",\"created_at\":\"2018-03-20T09:12:28\",\"sections\":[]}",
+            RestoreMode::Merge,
+        )
+        .await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(RestoreError::UnknownFormat {
+                format: "zip".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_version() {
+        let result = restore(
+            &ItemsHashMapDao::new(),
+            &UsersHashMapDao::new(),
+            "{\"format\":\"sleeping-bag-locator-backup\",\"version\":2,\"app_version\":\"0.1.0\",\"created_at\":\"2018-03-20T09:12:28\",\"sections\":[]}",
+            RestoreMode::Merge,
+        )
+        .await;
+        println!("{result:#?}");
+
+        assert_eq!(result, Err(RestoreError::UnsupportedVersion { version: 2 }));
+    }
+}
@@ -0,0 +1,145 @@
+use reqwest::{header::AUTHORIZATION, Client, Response, Url};
+
+use super::{archive::RestoreReport, errors::AdminClientError};
+use crate::dao::RestoreMode;
+
+const EXPORT_PATH: &str = "v1/admin/backup";
+const RESTORE_PATH: &str = "v1/admin/restore";
+
+/// Client of admin endpoints, so that backups are taken from the server, which holds the data
+pub struct AdminClient {
+    client: Client,
+    server_url: Url,
+    token: String,
+}
+
+impl AdminClient {
+    pub fn new(server_url: Url, token: String) -> Self {
+        Self {
+            client: Client::new(),
+            server_url,
+            token,
+        }
+    }
+
+    fn url(&self, path: &str) -> Result<Url, AdminClientError> {
+        self.server_url
+            .join(path)
+            .map_err(|x| AdminClientError::Request {
+                internal: x.to_string(),
+            })
+    }
+
+    async fn check(response: Response) -> Result<Response, AdminClientError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        Err(AdminClientError::Rejected {
+            status: status.as_u16(),
+            details: response.text().await.unwrap_or_default(),
+        })
+    }
+
+    pub async fn export(&self) -> Result<String, AdminClientError> {
+        let response = self
+            .client
+            .get(self.url(EXPORT_PATH)?)
+            .header(AUTHORIZATION, format!("Bearer {}", self.token))
+            .send()
+            .await?;
+
+        Ok(Self::check(response).await?.text().await?)
+    }
+
+    pub async fn restore(
+        &self,
+        archive: String,
+        mode: RestoreMode,
+    ) -> Result<RestoreReport, AdminClientError> {
+        let mode = match mode {
+            RestoreMode::Merge => "merge",
+            RestoreMode::Replace => "replace",
+        };
+        let response = self
+            .client
+            .post(self.url(RESTORE_PATH)?)
+            .query(&[("mode", mode)])
+            .header(AUTHORIZATION, format!("Bearer {}", self.token))
+            .body(archive)
+            .send()
+            .await?;
+
+        Ok(Self::check(response).await?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use fake::{Fake, Faker};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::{
+        dao::UsersDao,
+        http::{AppState, V1Router, V1_PREFIX},
+    };
+
+    const TOKEN: &str = "secret";
+
+    async fn server(state: AppState) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let router = Router::new()
+            .nest(V1_PREFIX, V1Router::default().into())
+            .with_state(state);
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        Url::parse(&format!("http://{address}/")).unwrap()
+    }
+
+    fn state() -> AppState {
+        AppState {
+            admin_token: Some(TOKEN.to_owned()),
+            ..AppState::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn export_and_restore() {
+        let source = state();
+        for _ in 0..3 {
+            source.users.create(Faker.fake()).await.unwrap();
+        }
+        let source = AdminClient::new(server(source).await, TOKEN.to_owned());
+        let target = AdminClient::new(server(state()).await, TOKEN.to_owned());
+
+        let archive = source.export().await.unwrap();
+        println!("{archive}");
+        let report = target.restore(archive, RestoreMode::Replace).await.unwrap();
+
+        assert_eq!(
+            report,
+            RestoreReport {
+                items: 1,
+                trash: 0,
+                users: 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_token() {
+        let client = AdminClient::new(server(state()).await, "wrong".to_owned());
+
+        let result = client.export().await;
+        println!("{result:#?}");
+
+        assert!(matches!(
+            result,
+            Err(AdminClientError::Rejected { status: 401, .. })
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dao::{
+    CreateUserValidationError,
+    Item,
+    ItemBuilder,
+    ItemBuilderError,
+    TrashedItem,
+    User,
+    UserAuthType,
+    ValidationErrors,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct BackupItem {
+    id: Uuid,
+    name: String,
+    location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_id: Option<Uuid>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<Item> for BackupItem {
+    fn from(value: Item) -> Self {
+        BackupItem {
+            id: value.id(),
+            name: value.name().to_owned(),
+            location: value.location().to_owned(),
+            owner_id: value.owner_id(),
+            created_at: value.created_at(),
+            updated_at: value.updated_at(),
+        }
+    }
+}
+
+impl TryInto<Item> for BackupItem {
+    type Error = ValidationErrors<ItemBuilderError>;
+
+    fn try_into(self) -> Result<Item, Self::Error> {
+        ItemBuilder::new()
+            .id(self.id)
+            .name(self.name)
+            .location(self.location)
+            .owner_id(self.owner_id)
+            .created_at(self.created_at)
+            .update_at(self.updated_at)
+            .build()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct BackupTrashedItem {
+    #[serde(flatten)]
+    item: BackupItem,
+    deleted_at: NaiveDateTime,
+}
+
+impl From<TrashedItem> for BackupTrashedItem {
+    fn from(value: TrashedItem) -> Self {
+        BackupTrashedItem {
+            deleted_at: value.deleted_at(),
+            item: value.into_item().into(),
+        }
+    }
+}
+
+impl TryInto<TrashedItem> for BackupTrashedItem {
+    type Error = ValidationErrors<ItemBuilderError>;
+
+    fn try_into(self) -> Result<TrashedItem, Self::Error> {
+        Ok(TrashedItem::with_deleted_at(
+            self.item.try_into()?,
+            self.deleted_at,
+        ))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub enum BackupUserAuthType {
+    Github,
+}
+
+impl From<UserAuthType> for BackupUserAuthType {
+    fn from(value: UserAuthType) -> Self {
+        match value {
+            UserAuthType::Github => BackupUserAuthType::Github,
+        }
+    }
+}
+
+impl From<BackupUserAuthType> for UserAuthType {
+    fn from(value: BackupUserAuthType) -> Self {
+        match value {
+            BackupUserAuthType::Github => UserAuthType::Github,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct BackupUser {
+    id: Uuid,
+    name: String,
+    auth_type: BackupUserAuthType,
+    external_id: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<User> for BackupUser {
+    fn from(value: User) -> Self {
+        BackupUser {
+            id: value.id(),
+            name: value.name().to_owned(),
+            auth_type: value.auth_type().into(),
+            external_id: value.external_id().to_owned(),
+            created_at: value.created_at(),
+            updated_at: value.updated_at(),
+        }
+    }
+}
+
+impl TryInto<User> for BackupUser {
+    type Error = ValidationErrors<CreateUserValidationError>;
+
+    fn try_into(self) -> Result<User, Self::Error> {
+        User::new(
+            self.id,
+            self.name,
+            self.auth_type.into(),
+            self.external_id,
+            self.created_at,
+            self.updated_at,
+        )
+    }
+}
@@ -0,0 +1,169 @@
+use thiserror::Error;
+
+use super::archive::Section;
+use crate::dao::{
+    ErrorVariant,
+    ListItemsError,
+    ListUsersError,
+    RestoreItemsError,
+    RestoreUsersError,
+};
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ExportError {
+    #[error("Cannot list items. This error was a direct following of: {internal}")]
+    ItemsListing { internal: String },
+    #[error("Cannot list users. This error was a direct following of: {internal}")]
+    UsersListing { internal: String },
+    #[error("Cannot serialize record. This error was a direct following of: {internal}")]
+    Serialization { internal: String },
+}
+
+impl ErrorVariant for ExportError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::ItemsListing { .. } => "ExportError::ItemsListing",
+            Self::UsersListing { .. } => "ExportError::UsersListing",
+            Self::Serialization { .. } => "ExportError::Serialization",
+        }
+    }
+}
+
+impl From<ListItemsError> for ExportError {
+    fn from(value: ListItemsError) -> Self {
+        Self::ItemsListing {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<ListUsersError> for ExportError {
+    fn from(value: ListUsersError) -> Self {
+        Self::UsersListing {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serialization {
+            internal: value.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RestoreError {
+    #[error("Archive is empty, manifest is missing")]
+    MissingManifest,
+    #[error("Cannot parse manifest. This error was a direct following of: {internal}")]
+    InvalidManifest { internal: String },
+    #[error("Unknown archive format {format:?}")]
+    UnknownFormat { format: String },
+    #[error("Archive version {version} is not supported")]
+    UnsupportedVersion { version: u32 },
+    #[error(
+        "Cannot restore record at line {line}. This error was a direct following of: {internal}"
+    )]
+    InvalidRecord { line: usize, internal: String },
+    #[error("Section {section:?} is not described in manifest")]
+    UnknownSection { section: Section },
+    #[error("Section {section:?} has {actual} records, but manifest declares {expected}")]
+    RecordsCountMismatch {
+        section: Section,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Section {section:?} checksum diverges from one in manifest")]
+    ChecksumMismatch { section: Section },
+    #[error("Cannot store items. This error was a direct following of: {internal}")]
+    ItemsStorage { internal: String },
+    #[error("Cannot store users. This error was a direct following of: {internal}")]
+    UsersStorage { internal: String },
+    #[error("Cannot list items to roll back to. This error was a direct following of: {internal}")]
+    ItemsListing { internal: String },
+    #[error(
+        "Cannot roll back items after failed restore of users. This error was a direct following \
+         of: {internal}"
+    )]
+    Rollback { internal: String },
+}
+
+impl ErrorVariant for RestoreError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::MissingManifest => "RestoreError::MissingManifest",
+            Self::InvalidManifest { .. } => "RestoreError::InvalidManifest",
+            Self::UnknownFormat { .. } => "RestoreError::UnknownFormat",
+            Self::UnsupportedVersion { .. } => "RestoreError::UnsupportedVersion",
+            Self::InvalidRecord { .. } => "RestoreError::InvalidRecord",
+            Self::UnknownSection { .. } => "RestoreError::UnknownSection",
+            Self::RecordsCountMismatch { .. } => "RestoreError::RecordsCountMismatch",
+            Self::ChecksumMismatch { .. } => "RestoreError::ChecksumMismatch",
+            Self::ItemsStorage { .. } => "RestoreError::ItemsStorage",
+            Self::UsersStorage { .. } => "RestoreError::UsersStorage",
+            Self::ItemsListing { .. } => "RestoreError::ItemsListing",
+            Self::Rollback { .. } => "RestoreError::Rollback",
+        }
+    }
+}
+
+impl From<RestoreItemsError> for RestoreError {
+    fn from(value: RestoreItemsError) -> Self {
+        Self::ItemsStorage {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<ListItemsError> for RestoreError {
+    fn from(value: ListItemsError) -> Self {
+        Self::ItemsListing {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<RestoreUsersError> for RestoreError {
+    fn from(value: RestoreUsersError) -> Self {
+        Self::UsersStorage {
+            internal: value.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ScheduledBackupError {
+    #[error(transparent)]
+    Export(#[from] ExportError),
+    #[error(
+        "Cannot upload backup to object storage. This error was a direct following of: {internal}"
+    )]
+    Upload { internal: String },
+    #[error(
+        "Cannot list backups in object storage. This error was a direct following of: {internal}"
+    )]
+    Listing { internal: String },
+    #[error("Cannot delete expired backup. This error was a direct following of: {internal}")]
+    Pruning { internal: String },
+}
+
+#[derive(Error, Debug)]
+pub enum AdminClientError {
+    #[error("Cannot reach admin endpoints. This error was a direct following of: {internal}")]
+    Request { internal: String },
+    #[error("Server answered with status {status}: {details}")]
+    Rejected { status: u16, details: String },
+}
+
+impl From<reqwest::Error> for AdminClientError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request {
+            internal: value.to_string(),
+        }
+    }
+}
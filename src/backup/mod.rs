@@ -0,0 +1,10 @@
+pub use archive::{export, restore, RestoreReport};
+pub use client::AdminClient;
+pub use errors::{ExportError, RestoreError};
+pub use scheduler::{BackupScheduler, BackupStatus, BackupStatusHandle};
+
+mod archive;
+mod client;
+mod dtos;
+mod errors;
+mod scheduler;
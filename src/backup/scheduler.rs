@@ -0,0 +1,245 @@
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::{Arc, RwLock},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use cron::Schedule;
+use futures::TryStreamExt;
+use object_store::{path::Path, ObjectStore, PutPayload};
+use tracing::{error, info};
+
+use super::{archive::export, errors::ScheduledBackupError};
+use crate::dao::{ItemsDao, UsersDao};
+
+const OBJECT_PREFIX: &str = "backup-";
+const OBJECT_SUFFIX: &str = ".jsonl";
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct BackupStatus {
+    pub attempted_at: Option<NaiveDateTime>,
+    pub succeeded_at: Option<NaiveDateTime>,
+    pub object: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct BackupStatusHandle(Arc<RwLock<BackupStatus>>);
+
+impl BackupStatusHandle {
+    pub fn get(&self) -> BackupStatus {
+        self.0.read().unwrap().clone()
+    }
+
+    fn set(&self, status: BackupStatus) {
+        *self.0.write().unwrap() = status;
+    }
+}
+
+pub struct BackupScheduler {
+    items: Arc<dyn ItemsDao + Send + Sync>,
+    users: Arc<dyn UsersDao + Send + Sync>,
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    schedule: Schedule,
+    retention: NonZeroUsize,
+    status: BackupStatusHandle,
+}
+
+impl BackupScheduler {
+    pub fn new(
+        items: Arc<dyn ItemsDao + Send + Sync>,
+        users: Arc<dyn UsersDao + Send + Sync>,
+        store: Arc<dyn ObjectStore>,
+        prefix: Path,
+        schedule: Schedule,
+        retention: NonZeroUsize,
+    ) -> Self {
+        Self {
+            items,
+            users,
+            store,
+            prefix,
+            schedule,
+            retention,
+            status: BackupStatusHandle::default(),
+        }
+    }
+
+    pub fn status(&self) -> BackupStatusHandle {
+        self.status.clone()
+    }
+
+    /// Uploads backups on schedule until `shutdown` resolves. Backup in progress is finished
+    /// first, so no partial archive is left behind
+    pub async fn run(self, shutdown: impl Future<Output = ()>) {
+        tokio::pin!(shutdown);
+        while let Some(next) = self.schedule.upcoming(Utc).next() {
+            let delay = (next - Utc::now()).to_std().unwrap_or_default();
+            info!("Next scheduled backup at {next}");
+            tokio::select! {
+                () = tokio::time::sleep(delay) => {}
+                () = &mut shutdown => return,
+            }
+
+            // Failures are only reported through status, next run is still scheduled
+            let _ = self.backup().await;
+        }
+    }
+
+    pub async fn backup(&self) -> Result<Path, ScheduledBackupError> {
+        let started_at = Utc::now().naive_utc();
+        let result = self.upload_and_prune(started_at).await;
+
+        let previous = self.status.get();
+        let status = match &result {
+            Ok(location) => {
+                info!("Backup uploaded to {location}");
+                BackupStatus {
+                    attempted_at: Some(started_at),
+                    succeeded_at: Some(started_at),
+                    object: Some(location.to_string()),
+                    error: None,
+                }
+            }
+            Err(err) => {
+                error!("Scheduled backup failed: {err}");
+                BackupStatus {
+                    attempted_at: Some(started_at),
+                    error: Some(err.to_string()),
+                    ..previous
+                }
+            }
+        };
+        self.status.set(status);
+
+        result
+    }
+
+    async fn upload_and_prune(
+        &self,
+        started_at: NaiveDateTime,
+    ) -> Result<Path, ScheduledBackupError> {
+        let archive = export(&self.items, &self.users).await?;
+        let location = self.prefix.child(format!(
+            "{OBJECT_PREFIX}{}{OBJECT_SUFFIX}",
+            started_at.format("%Y%m%dT%H%M%S")
+        ));
+
+        self.store
+            .put(&location, PutPayload::from(archive))
+            .await
+            .map_err(|x| ScheduledBackupError::Upload {
+                internal: x.to_string(),
+            })?;
+
+        let mut backups: Vec<Path> = self
+            .store
+            .list(Some(&self.prefix))
+            .map_ok(|x| x.location)
+            .try_filter(|x| {
+                let is_backup = x.filename().is_some_and(|name| {
+                    name.starts_with(OBJECT_PREFIX) && name.ends_with(OBJECT_SUFFIX)
+                });
+                async move { is_backup }
+            })
+            .try_collect()
+            .await
+            .map_err(|x| ScheduledBackupError::Listing {
+                internal: x.to_string(),
+            })?;
+        // Timestamps in names are sortable, so the newest backups end up last
+        backups.sort();
+
+        let expired = backups.len().saturating_sub(self.retention.get());
+        for path in backups.drain(..expired) {
+            info!("Pruning expired backup {path}");
+            self.store
+                .delete(&path)
+                .await
+                .map_err(|x| ScheduledBackupError::Pruning {
+                    internal: x.to_string(),
+                })?;
+        }
+
+        Ok(location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::Duration;
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::dao::{ItemsHashMapDao, UsersHashMapDao};
+
+    fn scheduler(store: Arc<InMemory>, retention: usize) -> BackupScheduler {
+        BackupScheduler::new(
+            Arc::new(ItemsHashMapDao::new()),
+            Arc::new(UsersHashMapDao::new()),
+            store,
+            Path::from("backups"),
+            Schedule::from_str("0 0 3 * * *").unwrap(),
+            NonZeroUsize::new(retention).unwrap(),
+        )
+    }
+
+    async fn stored(store: &InMemory) -> Vec<Path> {
+        let mut result: Vec<Path> = store
+            .list(None)
+            .map_ok(|x| x.location)
+            .try_collect()
+            .await
+            .unwrap();
+        result.sort();
+
+        result
+    }
+
+    #[tokio::test]
+    async fn backup_uploaded() {
+        let store = Arc::new(InMemory::new());
+        let scheduler = scheduler(store.clone(), 3);
+
+        let location = scheduler.backup().await.unwrap();
+        println!("{location}");
+        let status = scheduler.status().get();
+        println!("{status:#?}");
+
+        assert_eq!(stored(&store).await, vec![location.clone()]);
+        assert_eq!(status.object, Some(location.to_string()));
+        assert_eq!(status.succeeded_at, status.attempted_at);
+        assert_eq!(status.error, None);
+    }
+
+    #[tokio::test]
+    async fn expired_pruned() {
+        let store = Arc::new(InMemory::new());
+        let now = Utc::now().naive_utc();
+        for days in 1..=3 {
+            let name = format!(
+                "{OBJECT_PREFIX}{}{OBJECT_SUFFIX}",
+                (now - Duration::days(days)).format("%Y%m%dT%H%M%S")
+            );
+            store
+                .put(&Path::from("backups").child(name), PutPayload::new())
+                .await
+                .unwrap();
+        }
+        let unrelated = Path::from("backups/notes.txt");
+        store.put(&unrelated, PutPayload::new()).await.unwrap();
+
+        let location = scheduler(store.clone(), 2).backup().await.unwrap();
+        let result = stored(&store).await;
+        println!("{result:#?}");
+
+        assert_eq!(result.len(), 3);
+        assert!(result.contains(&location));
+        assert!(result.contains(&unrelated));
+    }
+}
@@ -1,12 +1,25 @@
 #![allow(clippy::struct_field_names)]
-use std::net::{IpAddr, Ipv4Addr};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+    str::FromStr,
+};
 
-use clap::{value_parser, Args, Parser, ValueEnum};
+use axum::http::{HeaderName, Method};
+use chrono::{DateTime, Utc};
+use clap::{value_parser, ArgAction, Args, Parser, Subcommand, ValueEnum};
+use cron::Schedule;
+use reqwest::Url;
 use tracing::Level;
 
+use crate::cors::OriginPattern;
+
 #[derive(Parser, Debug)]
 #[clap(version, about)]
 pub struct Config {
+    #[command(subcommand)]
+    pub command: Option<Command>,
     #[command(flatten)]
     pub runtime: Runtime,
     #[command(flatten)]
@@ -19,6 +32,69 @@ pub struct Config {
     pub items: ItemsDao,
     #[command(flatten)]
     pub users: UsersDao,
+    #[command(flatten)]
+    pub dao_cache: DaoCache,
+    #[command(flatten)]
+    pub dao_instrumentation: DaoInstrumentation,
+    #[command(flatten)]
+    pub admin: Admin,
+    #[command(flatten)]
+    pub backup_schedule: BackupSchedule,
+    #[command(flatten)]
+    pub trash: Trash,
+    #[command(flatten)]
+    pub batch: Batch,
+    #[command(flatten)]
+    pub idempotency: Idempotency,
+    #[command(flatten)]
+    pub rate_limit: RateLimit,
+    #[command(flatten)]
+    pub versioning: Versioning,
+    #[command(flatten)]
+    pub events: Events,
+    #[command(flatten)]
+    pub webhooks: Webhooks,
+    #[command(flatten)]
+    pub grpc: Grpc,
+    #[command(flatten)]
+    pub cors: Cors,
+    #[command(flatten)]
+    pub shutdown: Shutdown,
+    #[command(flatten)]
+    pub tls: Tls,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Start HTTP server (default)
+    Serve,
+    /// Export all application data of a running server into a backup archive
+    Export {
+        /// Archive path, stdout is used if not set
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Server, which is called with admin token
+        #[arg(long, env, default_value = "http://localhost:8080")]
+        server_url: Url,
+    },
+    /// Restore application data of a running server from a backup archive
+    Restore {
+        /// Archive path, stdin is used if not set
+        #[arg(long)]
+        input: Option<PathBuf>,
+        #[arg(long, default_value_t, value_enum)]
+        mode: BackupRestoreMode,
+        /// Server, which is called with admin token
+        #[arg(long, env, default_value = "http://localhost:8080")]
+        server_url: Url,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum, Default, Debug)]
+pub enum BackupRestoreMode {
+    #[default]
+    Merge,
+    Replace,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -51,6 +127,10 @@ pub struct Authentication {
     pub oauth_client_id: String,
     #[arg(long, env, default_value = "")]
     pub oauth_client_secret: String,
+    /// Whether session cookie is sent over HTTPS only. Browsers drop such cookies,
+    /// if API is served over plain HTTP, e.g. locally without TLS
+    #[arg(long, env, default_value_t = true, action = ArgAction::Set)]
+    pub session_cookie_secure: bool,
 }
 
 #[derive(Clone, ValueEnum, Default, Debug)]
@@ -93,3 +173,224 @@ pub struct UsersDao {
     #[arg(long, env, default_value_t, value_enum)]
     pub users_dao_type: UsersDaoType,
 }
+
+#[derive(Args, Clone, Debug)]
+pub struct DaoCache {
+    #[arg(long, env, default_value_t = false)]
+    pub dao_cache_enabled: bool,
+    #[arg(long, env, default_value = "1024")]
+    pub dao_cache_capacity: NonZeroUsize,
+    #[arg(long, env, value_parser = value_parser!(u64).range(1..), default_value = "60")]
+    pub dao_cache_ttl_seconds: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DaoInstrumentation {
+    #[arg(long, env, default_value_t = false)]
+    pub dao_instrumentation_enabled: bool,
+    /// How often accumulated DAO call counters are logged
+    #[arg(long, env, default_value_t = 60)]
+    pub dao_instrumentation_log_interval_seconds: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Admin {
+    /// Bearer token for admin endpoints, which are disabled if not set
+    #[arg(long, env)]
+    pub admin_token: Option<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct BackupSchedule {
+    /// Cron expression with seconds, e.g. "0 0 3 * * *"; scheduled backups are disabled if not set
+    #[arg(long, env, value_parser = Schedule::from_str)]
+    pub backup_schedule: Option<Schedule>,
+    #[arg(long, env, default_value = "7")]
+    pub backup_retention_count: NonZeroUsize,
+    #[arg(long, env, default_value = "")]
+    pub backup_s3_endpoint: String,
+    /// Allows plain HTTP endpoints, which expose backups and credentials in transit
+    #[arg(long, env, default_value_t = false)]
+    pub backup_s3_allow_http: bool,
+    #[arg(long, env, default_value = "us-east-1")]
+    pub backup_s3_region: String,
+    #[arg(long, env, default_value = "")]
+    pub backup_s3_bucket: String,
+    #[arg(long, env, default_value = "backups")]
+    pub backup_s3_prefix: String,
+    #[arg(long, env, default_value = "")]
+    pub backup_s3_access_key_id: String,
+    #[arg(long, env, default_value = "")]
+    pub backup_s3_secret_access_key: String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Trash {
+    #[arg(long, env, value_parser = value_parser!(i64).range(1..=36500), default_value = "30")]
+    pub trash_retention_days: i64,
+    #[arg(long, env, value_parser = value_parser!(u64).range(1..), default_value = "3600")]
+    pub trash_purge_interval_seconds: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Batch {
+    /// Maximum number of operations in a single batch request
+    #[arg(long, env, default_value = "100")]
+    pub items_batch_max_size: NonZeroUsize,
+}
+
+#[derive(Clone, ValueEnum, Default, Debug)]
+pub enum IdempotencyStoreType {
+    #[default]
+    Memory,
+    Redis,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Idempotency {
+    #[arg(long, env, default_value_t, value_enum)]
+    pub idempotency_store_type: IdempotencyStoreType,
+    #[arg(long, env, default_value = "")]
+    pub idempotency_store_dsn: String,
+    /// How long responses are kept for replay to retries with the same Idempotency-Key
+    #[arg(long, env, value_parser = value_parser!(u64).range(1..), default_value = "86400")]
+    pub idempotency_ttl_seconds: u64,
+    /// How long a key stays reserved while its first request is processed.
+    /// Keys of requests, which outlive it, e.g. because server has crashed, can be retried
+    #[arg(long, env, value_parser = value_parser!(u64).range(1..), default_value = "60")]
+    pub idempotency_lock_ttl_seconds: u64,
+}
+
+#[derive(Clone, ValueEnum, Default, Debug)]
+pub enum RateLimitStoreType {
+    #[default]
+    Memory,
+    Redis,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct RateLimit {
+    /// Instances share limits only with Redis store, memory one limits every instance on its own
+    #[arg(long, env, default_value_t, value_enum)]
+    pub rate_limit_store_type: RateLimitStoreType,
+    #[arg(long, env, default_value = "")]
+    pub rate_limit_store_dsn: String,
+    /// Sustained rate of safe requests per client
+    #[arg(long, env, default_value = "600")]
+    pub rate_limit_read_per_minute: NonZeroU32,
+    /// How many safe requests a client may send at once
+    #[arg(long, env, default_value = "100")]
+    pub rate_limit_read_burst: NonZeroU32,
+    /// Sustained rate of writes and logins per client
+    #[arg(long, env, default_value = "60")]
+    pub rate_limit_write_per_minute: NonZeroU32,
+    /// How many writes and logins a client may send at once
+    #[arg(long, env, default_value = "20")]
+    pub rate_limit_write_burst: NonZeroU32,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Versioning {
+    /// Moment since which unversioned routes are announced as deprecated aliases of /v1
+    #[arg(long, env, default_value = "2026-10-19T00:00:00Z")]
+    pub unversioned_routes_deprecated_at: DateTime<Utc>,
+    /// Moment after which unversioned routes may be removed
+    #[arg(long, env, default_value = "2027-04-19T00:00:00Z")]
+    pub unversioned_routes_sunset_at: DateTime<Utc>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Events {
+    /// Number of the latest item events kept for subscribers resuming with Last-Event-ID
+    #[arg(long, env, default_value = "1024")]
+    pub item_events_replay_size: NonZeroUsize,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Webhooks {
+    /// Number of attempts to deliver an event to a webhook before giving up
+    #[arg(long, env, default_value = "5")]
+    pub webhook_max_attempts: NonZeroU32,
+    /// Delay before the first retry of a failed delivery, doubled with every next one
+    #[arg(long, env, default_value = "500")]
+    pub webhook_initial_backoff_ms: u64,
+    /// Upper bound of the delay between delivery retries
+    #[arg(long, env, default_value = "60000")]
+    pub webhook_max_backoff_ms: u64,
+    /// Number of the latest delivery attempts kept per webhook
+    #[arg(long, env, default_value = "100")]
+    pub webhook_delivery_log_size: NonZeroUsize,
+    /// Allows webhooks to target loopback, private and link-local addresses, including cloud
+    /// metadata services, e.g. for receivers running next to the server in development
+    #[arg(long, env, default_value_t = false)]
+    pub webhook_allow_private_targets: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Grpc {
+    /// Port of gRPC server, which listens on the same host as HTTP one; gRPC is disabled if not
+    /// set. It's served over TLS, rate limited and traced with request ids the same way as HTTP
+    #[arg(long, env, value_parser = value_parser!(u16).range(1..))]
+    pub grpc_port: Option<u16>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Cors {
+    /// Comma-separated origins of browser clients, either exact, e.g. `https://app.example.com`,
+    /// or any subdomain, e.g. `https://*.example.com`; CORS is disabled if not set
+    #[arg(long, env, value_delimiter = ',', value_parser = OriginPattern::from_str)]
+    pub cors_allowed_origins: Vec<OriginPattern>,
+    #[arg(
+        long,
+        env,
+        value_delimiter = ',',
+        value_parser = Method::from_str,
+        default_value = "GET,POST,PUT,PATCH,DELETE"
+    )]
+    pub cors_allowed_methods: Vec<Method>,
+    #[arg(
+        long,
+        env,
+        value_delimiter = ',',
+        value_parser = HeaderName::from_str,
+        default_value = "content-type,if-match,if-none-match,if-modified-since,idempotency-key,last-event-id,x-request-id"
+    )]
+    pub cors_allowed_headers: Vec<HeaderName>,
+    /// Response headers, which browser clients are allowed to read besides the basic ones
+    #[arg(
+        long,
+        env,
+        value_delimiter = ',',
+        value_parser = HeaderName::from_str,
+        default_value = "etag,last-modified,pagination-page,pagination-limit,x-request-id,\
+                         ratelimit-limit,ratelimit-remaining,ratelimit-reset,retry-after,\
+                         deprecation,sunset,link,idempotent-replayed"
+    )]
+    pub cors_exposed_headers: Vec<HeaderName>,
+    /// Whether browsers send session cookie with cross-origin requests
+    #[arg(long, env, default_value_t = false)]
+    pub cors_allow_credentials: bool,
+    /// How long browsers may cache preflight responses
+    #[arg(long, env, default_value = "600")]
+    pub cors_max_age_seconds: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Shutdown {
+    /// How long in-flight requests are waited for after SIGTERM or SIGINT before being dropped
+    #[arg(long, env, default_value = "10")]
+    pub shutdown_timeout_seconds: u64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct Tls {
+    /// PEM certificate chain, HTTP listener serves TLS if it's set along with the key.
+    /// Both files are reloaded on change without restart
+    #[arg(long, env, requires = "tls_key_path")]
+    pub tls_certificate_path: Option<PathBuf>,
+    #[arg(long, env, requires = "tls_certificate_path")]
+    pub tls_key_path: Option<PathBuf>,
+    /// Port of plain HTTP listener, which redirects every request to HTTPS one
+    #[arg(long, env, requires = "tls_certificate_path", value_parser = value_parser!(u16).range(1..))]
+    pub tls_redirect_port: Option<u16>,
+}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum OriginPatternError {
+    #[error(
+        "Origin '{origin}' must be http(s) scheme, host and optional port, \
+         e.g. 'https://app.example.com' or 'https://*.example.com'"
+    )]
+    Malformed { origin: String },
+}
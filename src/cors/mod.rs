@@ -0,0 +1,6 @@
+//! Origins of browser clients, which are parsed from config and matched by CORS layer
+
+pub use origin::OriginPattern;
+
+mod errors;
+mod origin;
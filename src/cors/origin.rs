@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use super::errors::OriginPatternError;
+
+/// Origin allowed to make cross-origin requests
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OriginPattern {
+    Exact(String),
+    /// `scheme://*.domain[:port]`, which matches subdomains of any depth, but not the domain itself
+    Subdomains {
+        scheme: String,
+        suffix: String,
+    },
+}
+
+impl FromStr for OriginPattern {
+    type Err = OriginPatternError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let origin = value.trim().to_ascii_lowercase();
+        let malformed = || OriginPatternError::Malformed {
+            origin: value.to_owned(),
+        };
+
+        let (scheme, authority) = origin.split_once("://").ok_or_else(malformed)?;
+        if !matches!(scheme, "http" | "https")
+            || authority.is_empty()
+            || authority.contains(['/', '?', '#', '@'])
+        {
+            return Err(malformed());
+        }
+
+        match authority.strip_prefix("*.") {
+            Some(domain) if !domain.is_empty() && !domain.contains('*') => {
+                Ok(OriginPattern::Subdomains {
+                    scheme: format!("{scheme}://"),
+                    suffix: format!(".{domain}"),
+                })
+            }
+            None if !authority.contains('*') => Ok(OriginPattern::Exact(origin)),
+            _ => Err(malformed()),
+        }
+    }
+}
+
+impl OriginPattern {
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+
+        match self {
+            OriginPattern::Exact(exact) => origin == *exact,
+            OriginPattern::Subdomains { scheme, suffix } => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|x| x.strip_suffix(suffix.as_str()))
+                .is_some_and(|subdomain| {
+                    !subdomain.is_empty()
+                        && subdomain
+                            .bytes()
+                            .all(|x| x.is_ascii_alphanumeric() || x == b'-' || x == b'.')
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("https://app.example.com", OriginPattern::Exact("https://app.example.com".to_owned()))]
+    #[case("HTTP://Localhost:3000", OriginPattern::Exact("http://localhost:3000".to_owned()))]
+    #[case(
+        "https://*.example.com",
+        OriginPattern::Subdomains {
+            scheme: "https://".to_owned(),
+            suffix: ".example.com".to_owned(),
+        },
+    )]
+    fn parse(#[case] value: &str, #[case] expected: OriginPattern) {
+        assert_eq!(value.parse::<OriginPattern>(), Ok(expected));
+    }
+
+    #[rstest]
+    #[case("*")]
+    #[case("example.com")]
+    #[case("ftp://example.com")]
+    #[case("https://example.com/")]
+    #[case("https://*")]
+    #[case("https://*.")]
+    #[case("https://app.*.example.com")]
+    fn parse_malformed(#[case] value: &str) {
+        assert!(value.parse::<OriginPattern>().is_err());
+    }
+
+    #[rstest]
+    #[case("https://ui.example.com", true)]
+    #[case("https://a.b.example.com", true)]
+    #[case("https://example.com", false)]
+    #[case("http://ui.example.com", false)]
+    #[case("https://ui.example.com:8443", false)]
+    #[case("https://evil.com?.example.com", false)]
+    #[case("https://notexample.com", false)]
+    fn subdomains(#[case] origin: &str, #[case] expected: bool) {
+        let pattern: OriginPattern = "https://*.example.com".parse().unwrap();
+
+        assert_eq!(pattern.matches(origin), expected);
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+        MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct CacheEntry<V> {
+    value: V,
+    expires_at: Instant,
+}
+
+struct Entries<K, V> {
+    lru: LruCache<K, CacheEntry<V>>,
+    /// Bumped on every invalidation, so that values read before it are not cached
+    generation: u64,
+}
+
+pub struct Cache<K, V> {
+    entries: Mutex<Entries<K, V>>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                lru: LruCache::new(capacity),
+                generation: 0,
+            }),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries<K, V>> {
+        self.entries.lock().unwrap()
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.lock();
+
+        let value = match entries.lru.get(key) {
+            Some(entry) if entry.expires_at.gt(&Instant::now()) => Some(entry.value.clone()),
+            Some(_) => {
+                entries.lru.pop(key);
+                None
+            }
+            None => None,
+        };
+
+        if value.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        value
+    }
+
+    /// Should be taken before reading the value to be put from the source
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Skips the value if the cache was invalidated after `generation` was taken
+    pub fn put(&self, key: K, value: V, generation: u64) {
+        let mut entries = self.lock();
+        if entries.generation != generation {
+            return;
+        }
+
+        let expires_at = Instant::now() + self.ttl;
+        entries.lru.put(key, CacheEntry { value, expires_at });
+    }
+
+    pub fn invalidate(&self, key: &K) {
+        let mut entries = self.lock();
+        entries.lru.pop(key);
+        entries.generation += 1;
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.lru.clear();
+        entries.generation += 1;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+
+    fn cache(capacity: usize, ttl: Duration) -> Cache<usize, String> {
+        Cache::new(NonZeroUsize::new(capacity).unwrap(), ttl)
+    }
+
+    #[test]
+    fn hit_and_miss() {
+        let cache = cache(2, Duration::from_secs(60));
+
+        assert_eq!(cache.get(&1), None);
+        cache.put(1, "one".to_owned(), cache.generation());
+        assert_eq!(cache.get(&1), Some("one".to_owned()));
+
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn least_recently_used_evicted() {
+        let cache = cache(2, Duration::from_secs(60));
+
+        cache.put(1, "one".to_owned(), cache.generation());
+        cache.put(2, "two".to_owned(), cache.generation());
+        cache.get(&1);
+        cache.put(3, "three".to_owned(), cache.generation());
+
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("one".to_owned()));
+        assert_eq!(cache.get(&3), Some("three".to_owned()));
+    }
+
+    #[test]
+    fn expired() {
+        let cache = cache(2, Duration::from_millis(10));
+
+        cache.put(1, "one".to_owned(), cache.generation());
+        sleep(Duration::from_millis(20));
+
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 1 });
+    }
+
+    #[test]
+    fn invalidated() {
+        let cache = cache(2, Duration::from_secs(60));
+
+        cache.put(1, "one".to_owned(), cache.generation());
+        cache.invalidate(&1);
+
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn stale_put_skipped() {
+        let cache = cache(2, Duration::from_secs(60));
+
+        let generation = cache.generation();
+        // Concurrent write invalidates the key while the value is being read
+        cache.invalidate(&1);
+        cache.put(1, "stale".to_owned(), generation);
+
+        assert_eq!(cache.get(&1), None);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    future::Future,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, info, info_span, Instrument};
+
+pub trait ErrorVariant {
+    fn variant(&self) -> &'static str;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    Ok,
+    Err(&'static str),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Ok => write!(f, "ok"),
+            Outcome::Err(variant) => write!(f, "{variant}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counter {
+    pub calls: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Label {
+    dao: &'static str,
+    method: &'static str,
+    outcome: Outcome,
+}
+
+#[derive(Default)]
+pub struct DaoMetrics(Mutex<HashMap<Label, Counter>>);
+
+impl DaoMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Label, Counter>> {
+        self.0.lock().unwrap()
+    }
+
+    fn record(
+        &self,
+        dao: &'static str,
+        method: &'static str,
+        outcome: Outcome,
+        latency: Duration,
+    ) -> Counter {
+        let mut counters = self.lock();
+        let counter = counters
+            .entry(Label {
+                dao,
+                method,
+                outcome,
+            })
+            .or_default();
+
+        counter.calls += 1;
+        counter.total_latency += latency;
+        counter.max_latency = counter.max_latency.max(latency);
+
+        *counter
+    }
+
+    #[cfg(test)]
+    pub fn get(&self, dao: &'static str, method: &'static str, outcome: Outcome) -> Counter {
+        self.lock()
+            .get(&Label {
+                dao,
+                method,
+                outcome,
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Logs counters accumulated since the start, one line per DAO, method and outcome
+    pub fn log(&self) {
+        let mut counters: Vec<(Label, Counter)> = self
+            .lock()
+            .iter()
+            .map(|(label, counter)| (*label, *counter))
+            .collect();
+        counters.sort_unstable_by_key(|(label, _)| *label);
+
+        for (
+            Label {
+                dao,
+                method,
+                outcome,
+            },
+            counter,
+        ) in counters
+        {
+            info!(
+                dao,
+                method,
+                %outcome,
+                calls = counter.calls,
+                total_latency_ms = counter.total_latency.as_secs_f64() * 1000.0,
+                max_latency_ms = counter.max_latency.as_secs_f64() * 1000.0,
+                "DAO calls summary"
+            );
+        }
+    }
+
+    pub async fn measure<T, E, F>(
+        &self,
+        dao: &'static str,
+        method: &'static str,
+        future: F,
+    ) -> Result<T, E>
+    where
+        E: ErrorVariant,
+        F: Future<Output = Result<T, E>>,
+    {
+        let span = info_span!("dao", dao, method);
+
+        async {
+            let started_at = Instant::now();
+            let result = future.await;
+            let latency = started_at.elapsed();
+
+            let outcome = match &result {
+                Ok(_) => Outcome::Ok,
+                Err(err) => Outcome::Err(err.variant()),
+            };
+            let counter = self.record(dao, method, outcome, latency);
+
+            debug!(
+                %outcome,
+                latency_ms = latency.as_secs_f64() * 1000.0,
+                calls = counter.calls,
+                total_latency_ms = counter.total_latency.as_secs_f64() * 1000.0,
+                max_latency_ms = counter.max_latency.as_secs_f64() * 1000.0,
+                "DAO call finished"
+            );
+
+            result
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct TestError;
+
+    impl ErrorVariant for TestError {
+        fn variant(&self) -> &'static str {
+            "TestError"
+        }
+    }
+
+    #[tokio::test]
+    async fn outcomes_counted_separately() {
+        let metrics = DaoMetrics::new();
+
+        metrics
+            .measure("test", "call", async { Ok::<_, TestError>(()) })
+            .await
+            .unwrap();
+        metrics
+            .measure("test", "call", async { Ok::<_, TestError>(()) })
+            .await
+            .unwrap();
+        let err = metrics
+            .measure("test", "call", async { Err::<(), _>(TestError) })
+            .await;
+        println!("{err:#?}");
+
+        assert_eq!(err, Err(TestError));
+        assert_eq!(metrics.get("test", "call", Outcome::Ok).calls, 2);
+        assert_eq!(
+            metrics.get("test", "call", Outcome::Err("TestError")).calls,
+            1
+        );
+        assert_eq!(
+            metrics.get("test", "other", Outcome::Ok),
+            Counter::default()
+        );
+    }
+}
@@ -1,3 +1,15 @@
+pub use cache::{Cache, CacheStats};
+#[cfg(test)]
+pub use instrumentation::Outcome;
+pub use instrumentation::{DaoMetrics, ErrorVariant};
 pub use pagination::{Pagination, PaginationBuilder, PaginationBuilderError};
+pub use precondition::Precondition;
+pub use restore::RestoreMode;
+pub use validation::{Constraint, FieldViolation, ValidationErrors, Violation};
 
+mod cache;
+mod instrumentation;
 mod pagination;
+mod precondition;
+mod restore;
+mod validation;
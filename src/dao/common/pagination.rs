@@ -2,6 +2,8 @@
 use fake::Dummy;
 use thiserror::Error;
 
+use super::ErrorVariant;
+
 #[cfg_attr(test, derive(Debug, Dummy, PartialEq, Eq))]
 #[derive(Clone)]
 pub struct Pagination {
@@ -37,6 +39,15 @@ pub enum PaginationBuilderError {
     LimitIsZero,
 }
 
+impl ErrorVariant for PaginationBuilderError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::PageIsZero => "PaginationBuilderError::PageIsZero",
+            Self::LimitIsZero => "PaginationBuilderError::LimitIsZero",
+        }
+    }
+}
+
 impl Default for PaginationBuilder {
     fn default() -> Self {
         Self {
@@ -47,7 +58,7 @@ impl Default for PaginationBuilder {
 }
 
 impl PaginationBuilder {
-    const DEFAULT_PAGINATION_LIMIT: usize = 10;
+    pub const DEFAULT_PAGINATION_LIMIT: usize = 10;
     const DEFAULT_PAGINATION_PAGE: usize = 1;
 
     pub fn new() -> Self {
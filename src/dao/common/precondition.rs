@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Precondition {
+    /// The write applies to whatever version of the entity is stored
+    #[default]
+    None,
+    /// The write only applies if the entity wasn't updated since the given instant
+    UpdatedAt(NaiveDateTime),
+    /// The write only applies if the entity was last updated at one of the given instants
+    UpdatedAtAnyOf(Vec<NaiveDateTime>),
+}
+
+impl Precondition {
+    pub fn holds(&self, updated_at: NaiveDateTime) -> bool {
+        match self {
+            Precondition::None => true,
+            Precondition::UpdatedAt(expected) => *expected == updated_at,
+            Precondition::UpdatedAtAnyOf(expected) => expected.contains(&updated_at),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Restored entities overwrite existing ones with the same id, others are kept
+    Merge,
+    /// Existing entities are dropped before restoring
+    Replace,
+}
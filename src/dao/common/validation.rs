@@ -0,0 +1,103 @@
+use std::fmt::{self, Debug, Display};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    Required,
+    NotEmpty,
+    MaxLength { max: usize },
+    NotBefore { field: &'static str },
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Required => write!(f, "required"),
+            Constraint::NotEmpty => write!(f, "not_empty"),
+            Constraint::MaxLength { max: _ } => write!(f, "max_length"),
+            Constraint::NotBefore { field: _ } => write!(f, "not_before"),
+        }
+    }
+}
+
+/// Validation error, which could be attributed to a single field of an entity
+pub trait FieldViolation: Display {
+    fn field(&self) -> &'static str;
+    fn constraint(&self) -> Constraint;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub field: &'static str,
+    pub constraint: Constraint,
+    pub message: String,
+}
+
+impl<E: FieldViolation> From<&E> for Violation {
+    fn from(value: &E) -> Self {
+        Self {
+            field: value.field(),
+            constraint: value.constraint(),
+            message: value.to_string(),
+        }
+    }
+}
+
+/// Every violation found while validating an entity, not only the first one
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ValidationErrors<E>(Vec<E>);
+
+impl<E> Default for ValidationErrors<E> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<E> ValidationErrors<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, error: E) {
+        self.0.push(error);
+    }
+
+    pub fn errors(&self) -> &[E] {
+        &self.0
+    }
+
+    pub fn into_result<T>(self, value: T) -> Result<T, Self> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<E: FieldViolation> ValidationErrors<E> {
+    pub fn violations(&self) -> Vec<Violation> {
+        self.0.iter().map(Into::into).collect()
+    }
+}
+
+impl<E> From<E> for ValidationErrors<E> {
+    fn from(value: E) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl<E: Display> Display for ValidationErrors<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, error) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Debug + Display> std::error::Error for ValidationErrors<E> {}
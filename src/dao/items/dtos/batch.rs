@@ -0,0 +1,53 @@
+use uuid::Uuid;
+
+use super::{create::CreateItemParams, item::Item, update::UpdateItemParams};
+use crate::dao::{
+    common::Precondition,
+    items::{ItemOperationError, ItemsDao},
+};
+
+#[cfg_attr(test, derive(Debug))]
+pub enum ItemOperation {
+    Create {
+        params: CreateItemParams,
+    },
+    Update {
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    },
+    Delete {
+        id: Uuid,
+        precondition: Precondition,
+    },
+}
+
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+pub enum ItemOperationOutcome {
+    Created(Item),
+    Updated(Item),
+    Deleted(Item),
+}
+
+impl ItemOperation {
+    /// Applies operation on its own, as if it were requested separately
+    pub async fn apply(
+        self,
+        dao: &(dyn ItemsDao + Send + Sync),
+    ) -> Result<ItemOperationOutcome, ItemOperationError> {
+        Ok(match self {
+            ItemOperation::Create { params } => {
+                ItemOperationOutcome::Created(dao.create(params).await?)
+            }
+            ItemOperation::Update {
+                id,
+                params,
+                precondition,
+            } => ItemOperationOutcome::Updated(dao.update(id, params, precondition).await?),
+            ItemOperation::Delete { id, precondition } => {
+                ItemOperationOutcome::Deleted(dao.delete(id, precondition).await?)
+            }
+        })
+    }
+}
@@ -1,8 +1,10 @@
 #[cfg(test)]
 use fake::{faker::address::en::CityName, faker::lorem::en::Word, Dummy};
 use thiserror::Error;
+use uuid::Uuid;
 
 use super::item::{Item, ItemBuilder, ItemBuilderError};
+use crate::dao::common::{ErrorVariant, ValidationErrors};
 
 #[cfg_attr(test, derive(Dummy, Clone, PartialEq, Eq))]
 #[derive(Debug)]
@@ -11,6 +13,8 @@ pub struct CreateItemParams {
     name: String,
     #[cfg_attr(test, dummy(faker = "CityName()"))]
     location: String,
+    #[cfg_attr(test, dummy(default))]
+    owner_id: Option<Uuid>,
 }
 
 impl CreateItemParams {
@@ -21,15 +25,25 @@ impl CreateItemParams {
     pub fn location(&self) -> &str {
         &self.location
     }
+
+    pub fn owner_id(&self) -> Option<Uuid> {
+        self.owner_id
+    }
+
+    /// Same params of an item, which is owned by the user
+    pub fn owned_by(self, owner_id: Option<Uuid>) -> Self {
+        Self { owner_id, ..self }
+    }
 }
 
 impl TryInto<Item> for CreateItemParams {
-    type Error = ItemBuilderError;
+    type Error = ValidationErrors<ItemBuilderError>;
 
     fn try_into(self) -> Result<Item, Self::Error> {
         let entity = ItemBuilder::new()
             .location(self.location().to_owned())
             .name(self.name().to_owned())
+            .owner_id(self.owner_id())
             .build()?;
         Ok(entity)
     }
@@ -51,6 +65,15 @@ pub enum CreateItemParamsBuilderError {
     LocationNotSet,
 }
 
+impl ErrorVariant for CreateItemParamsBuilderError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NameNotSet => "CreateItemParamsBuilderError::NameNotSet",
+            Self::LocationNotSet => "CreateItemParamsBuilderError::LocationNotSet",
+        }
+    }
+}
+
 impl CreateItemsParamsBuilder {
     pub fn new() -> Self {
         Self::default()
@@ -72,6 +95,7 @@ impl CreateItemsParamsBuilder {
             location: self
                 .location
                 .ok_or(CreateItemParamsBuilderError::LocationNotSet)?,
+            owner_id: None,
         })
     }
 }
@@ -120,6 +144,13 @@ mod tests {
             .unwrap();
         println!("{params:#?}");
 
-        assert_eq!(params, CreateItemParams { name, location });
+        assert_eq!(
+            params,
+            CreateItemParams {
+                name,
+                location,
+                owner_id: None
+            }
+        );
     }
 }
@@ -2,12 +2,16 @@ use chrono::{NaiveDateTime, Utc};
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::dao::common::{Constraint, FieldViolation, ValidationErrors};
+
 #[derive(Clone)]
 #[cfg_attr(test, derive(Debug, Eq, PartialEq))]
 pub struct Item {
     id: Uuid,
     name: String,
     location: String,
+    /// Id of the user, who owns the item, it isn't checked to exist
+    owner_id: Option<Uuid>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
@@ -25,6 +29,10 @@ impl Item {
         &self.location
     }
 
+    pub fn owner_id(&self) -> Option<Uuid> {
+        self.owner_id
+    }
+
     pub fn created_at(&self) -> NaiveDateTime {
         self.created_at
     }
@@ -39,6 +47,7 @@ pub struct ItemBuilder {
     id: Uuid,
     name: Option<String>,
     location: Option<String>,
+    owner_id: Option<Uuid>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
@@ -67,6 +76,34 @@ pub enum ItemBuilderError {
     },
 }
 
+impl FieldViolation for ItemBuilderError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::NameNotSet | Self::NameIsEmpty | Self::NameTooLong { .. } => "name",
+            Self::LocationNotSet | Self::LocationIsEmpty | Self::LocationTooLong { .. } => {
+                "location"
+            }
+            Self::UpdatedBeforeCreation { .. } => "updated_at",
+        }
+    }
+
+    fn constraint(&self) -> Constraint {
+        match self {
+            Self::NameNotSet | Self::LocationNotSet => Constraint::Required,
+            Self::NameIsEmpty | Self::LocationIsEmpty => Constraint::NotEmpty,
+            Self::NameTooLong { .. } => Constraint::MaxLength {
+                max: ItemBuilder::MAX_NAME_LENGTH,
+            },
+            Self::LocationTooLong { .. } => Constraint::MaxLength {
+                max: ItemBuilder::MAX_LOCATION_LENGTH,
+            },
+            Self::UpdatedBeforeCreation { .. } => Constraint::NotBefore {
+                field: "created_at",
+            },
+        }
+    }
+}
+
 impl Default for ItemBuilder {
     fn default() -> Self {
         let now = Utc::now().naive_utc();
@@ -74,6 +111,7 @@ impl Default for ItemBuilder {
             id: Uuid::new_v4(),
             name: None,
             location: None,
+            owner_id: None,
             created_at: now,
             updated_at: now,
         }
@@ -103,6 +141,11 @@ impl ItemBuilder {
         self
     }
 
+    pub fn owner_id(mut self, owner_id: Option<Uuid>) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+
     pub fn created_at(mut self, created_at: NaiveDateTime) -> Self {
         self.created_at = created_at;
         self
@@ -113,35 +156,45 @@ impl ItemBuilder {
         self
     }
 
-    pub fn build(self) -> Result<Item, ItemBuilderError> {
-        let name = self.name.ok_or(ItemBuilderError::NameNotSet)?;
-        let location = self.location.ok_or(ItemBuilderError::LocationNotSet)?;
+    pub fn build(self) -> Result<Item, ValidationErrors<ItemBuilderError>> {
+        let mut errors = ValidationErrors::new();
 
-        if name.is_empty() {
-            return Err(ItemBuilderError::NameIsEmpty);
-        }
-        if name.len().gt(&Self::MAX_NAME_LENGTH) {
-            return Err(ItemBuilderError::NameTooLong { name });
+        match &self.name {
+            None => errors.push(ItemBuilderError::NameNotSet),
+            Some(name) if name.is_empty() => errors.push(ItemBuilderError::NameIsEmpty),
+            Some(name) if name.len().gt(&Self::MAX_NAME_LENGTH) => {
+                errors.push(ItemBuilderError::NameTooLong { name: name.clone() });
+            }
+            Some(_) => {}
         }
 
-        if location.is_empty() {
-            return Err(ItemBuilderError::LocationIsEmpty);
-        }
-        if location.len().gt(&Self::MAX_LOCATION_LENGTH) {
-            return Err(ItemBuilderError::LocationTooLong { location });
+        match &self.location {
+            None => errors.push(ItemBuilderError::LocationNotSet),
+            Some(location) if location.is_empty() => {
+                errors.push(ItemBuilderError::LocationIsEmpty);
+            }
+            Some(location) if location.len().gt(&Self::MAX_LOCATION_LENGTH) => {
+                errors.push(ItemBuilderError::LocationTooLong {
+                    location: location.clone(),
+                });
+            }
+            Some(_) => {}
         }
 
         if self.updated_at.lt(&self.created_at) {
-            return Err(ItemBuilderError::UpdatedBeforeCreation {
+            errors.push(ItemBuilderError::UpdatedBeforeCreation {
                 updated_at: self.updated_at,
                 created_at: self.created_at,
             });
         }
 
+        errors.into_result(())?;
+
         Ok(Item {
             id: self.id,
-            name,
-            location,
+            name: self.name.unwrap_or_default(),
+            location: self.location.unwrap_or_default(),
+            owner_id: self.owner_id,
             created_at: self.created_at,
             updated_at: self.updated_at,
         })
@@ -177,7 +230,7 @@ mod tests {
         let builder_err = builder.name(name).build();
         println!("{builder_err:#?}");
 
-        assert_eq!(builder_err, Err(ItemBuilderError::LocationNotSet));
+        assert_eq!(builder_err, Err(ItemBuilderError::LocationNotSet.into()));
     }
 
     #[test]
@@ -188,7 +241,7 @@ mod tests {
         let builder_err = builder.location(location).build();
         println!("{builder_err:#?}");
 
-        assert_eq!(builder_err, Err(ItemBuilderError::NameNotSet));
+        assert_eq!(builder_err, Err(ItemBuilderError::NameNotSet.into()));
     }
 
     #[test]
@@ -199,7 +252,7 @@ mod tests {
         let builder_err = builder.location(String::new()).name(name).build();
         println!("{builder_err:#?}");
 
-        assert_eq!(builder_err, Err(ItemBuilderError::LocationIsEmpty));
+        assert_eq!(builder_err, Err(ItemBuilderError::LocationIsEmpty.into()));
     }
 
     #[test]
@@ -210,7 +263,7 @@ mod tests {
         let builder_err = builder.name(String::new()).location(location).build();
         println!("{builder_err:#?}");
 
-        assert_eq!(builder_err, Err(ItemBuilderError::NameIsEmpty));
+        assert_eq!(builder_err, Err(ItemBuilderError::NameIsEmpty.into()));
     }
 
     #[test]
@@ -225,7 +278,7 @@ mod tests {
 
         assert_eq!(
             builder_err,
-            Err(ItemBuilderError::LocationTooLong { location })
+            Err(ItemBuilderError::LocationTooLong { location }.into())
         );
     }
 
@@ -239,7 +292,43 @@ mod tests {
         let builder_err = builder.name(name.clone()).location(location).build();
         println!("{builder_err:#?}");
 
-        assert_eq!(builder_err, Err(ItemBuilderError::NameTooLong { name }));
+        assert_eq!(
+            builder_err,
+            Err(ItemBuilderError::NameTooLong { name }.into())
+        );
+    }
+
+    #[test]
+    fn all_violations_reported() {
+        let name: String =
+            ((ItemBuilder::MAX_NAME_LENGTH + 1)..(ItemBuilder::MAX_NAME_LENGTH * 2)).fake();
+        let builder = ItemBuilder::default();
+        println!("{builder:#?}");
+        let builder_err = builder
+            .name(name.clone())
+            .location(String::new())
+            .build()
+            .unwrap_err();
+        println!("{builder_err:#?}");
+
+        assert_eq!(
+            builder_err.errors(),
+            [
+                ItemBuilderError::NameTooLong { name },
+                ItemBuilderError::LocationIsEmpty
+            ]
+        );
+        assert_eq!(
+            builder_err
+                .violations()
+                .into_iter()
+                .map(|x| (x.field, x.constraint))
+                .collect::<Vec<_>>(),
+            vec![
+                ("name", Constraint::MaxLength { max: 128 }),
+                ("location", Constraint::NotEmpty)
+            ]
+        );
     }
 
     #[test]
@@ -276,7 +365,8 @@ mod tests {
             Err(ItemBuilderError::UpdatedBeforeCreation {
                 updated_at,
                 created_at
-            })
+            }
+            .into())
         );
     }
 
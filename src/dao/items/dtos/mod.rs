@@ -1,7 +1,13 @@
+pub use batch::{ItemOperation, ItemOperationOutcome};
 pub use create::{CreateItemParams, CreateItemParamsBuilderError, CreateItemsParamsBuilder};
-pub use item::{Item, ItemBuilder};
+pub use item::{Item, ItemBuilder, ItemBuilderError};
+pub use snapshot::ItemsSnapshot;
+pub use trashed::TrashedItem;
 pub use update::{UpdateItemParams, UpdateItemParamsBuilder, UpdateItemParamsBuilderError};
 
+mod batch;
 mod create;
 mod item;
+mod snapshot;
+mod trashed;
 mod update;
@@ -0,0 +1,9 @@
+use super::{item::Item, trashed::TrashedItem};
+
+/// Items together with trash as of a single moment
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+pub struct ItemsSnapshot {
+    pub items: Vec<Item>,
+    pub trash: Vec<TrashedItem>,
+}
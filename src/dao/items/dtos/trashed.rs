@@ -0,0 +1,36 @@
+use chrono::{NaiveDateTime, Utc};
+
+use super::item::Item;
+
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+pub struct TrashedItem {
+    item: Item,
+    deleted_at: NaiveDateTime,
+}
+
+impl TrashedItem {
+    pub fn new(item: Item) -> Self {
+        Self {
+            item,
+            deleted_at: Utc::now().naive_utc(),
+        }
+    }
+
+    /// Restores an item, which was deleted earlier
+    pub fn with_deleted_at(item: Item, deleted_at: NaiveDateTime) -> Self {
+        Self { item, deleted_at }
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn into_item(self) -> Item {
+        self.item
+    }
+
+    pub fn deleted_at(&self) -> NaiveDateTime {
+        self.deleted_at
+    }
+}
@@ -4,6 +4,7 @@ use fake::{faker::address::en::CityName, faker::lorem::en::Word, Dummy};
 use thiserror::Error;
 
 use super::item::{Item, ItemBuilder, ItemBuilderError};
+use crate::dao::common::{ErrorVariant, ValidationErrors};
 
 #[cfg_attr(test, derive(Dummy, Clone, Debug, PartialEq, Eq))]
 pub struct UpdateItemParams {
@@ -14,12 +15,16 @@ pub struct UpdateItemParams {
 }
 
 impl Item {
-    pub fn try_update(self, mutation: &UpdateItemParams) -> Result<Self, ItemBuilderError> {
+    pub fn try_update(
+        self,
+        mutation: &UpdateItemParams,
+    ) -> Result<Self, ValidationErrors<ItemBuilderError>> {
         let now = Utc::now().naive_utc();
         let entity = ItemBuilder::new()
             .id(self.id())
             .name(mutation.name().to_owned())
             .location(mutation.location().to_owned())
+            .owner_id(self.owner_id())
             .created_at(self.created_at())
             .update_at(now)
             .build()?;
@@ -29,7 +34,7 @@ impl Item {
 }
 
 impl TryInto<Item> for UpdateItemParams {
-    type Error = ItemBuilderError;
+    type Error = ValidationErrors<ItemBuilderError>;
 
     fn try_into(self) -> Result<Item, Self::Error> {
         let entity = ItemBuilder::new()
@@ -66,6 +71,15 @@ pub enum UpdateItemParamsBuilderError {
     LocationNotSet,
 }
 
+impl ErrorVariant for UpdateItemParamsBuilderError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NameNotSet => "UpdateItemParamsBuilderError::NameNotSet",
+            Self::LocationNotSet => "UpdateItemParamsBuilderError::LocationNotSet",
+        }
+    }
+}
+
 impl UpdateItemParamsBuilder {
     pub fn new() -> Self {
         Self::default()
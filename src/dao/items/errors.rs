@@ -1,6 +1,9 @@
 use thiserror::Error;
 use uuid::Uuid;
 
+use super::dtos::ItemBuilderError;
+use crate::dao::common::{ErrorVariant, ValidationErrors, Violation};
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum ListItemsError {
@@ -8,17 +11,43 @@ pub enum ListItemsError {
     UnexpectedError,
 }
 
+impl ErrorVariant for ListItemsError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "ListItemsError::UnexpectedError",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum CreateItemError {
     #[error("Cannot create entity from given params")]
-    InvalidParams,
+    InvalidParams { violations: Vec<Violation> },
     #[error("Entity with id '{id:?}' already exists in our records")]
     AlreadyExists { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
+impl ErrorVariant for CreateItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidParams { .. } => "CreateItemError::InvalidParams",
+            Self::AlreadyExists { .. } => "CreateItemError::AlreadyExists",
+            Self::UnexpectedError => "CreateItemError::UnexpectedError",
+        }
+    }
+}
+
+impl From<ValidationErrors<ItemBuilderError>> for CreateItemError {
+    fn from(value: ValidationErrors<ItemBuilderError>) -> Self {
+        Self::InvalidParams {
+            violations: value.violations(),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum GetItemError {
@@ -28,29 +57,208 @@ pub enum GetItemError {
     UnexpectedError,
 }
 
+impl ErrorVariant for GetItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "GetItemError::NoSuchEntity",
+            Self::UnexpectedError => "GetItemError::UnexpectedError",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum UpdateItemError {
     #[error("Cannot update entity with given params")]
-    InvalidParams,
+    InvalidParams { violations: Vec<Violation> },
     #[error("Entity with id '{id:?}' doesn't exist in our records")]
     NoSuchEntity { id: Uuid },
+    #[error("Entity with id '{id:?}' was modified since the given version")]
+    PreconditionFailed { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
+impl ErrorVariant for UpdateItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidParams { .. } => "UpdateItemError::InvalidParams",
+            Self::NoSuchEntity { .. } => "UpdateItemError::NoSuchEntity",
+            Self::PreconditionFailed { .. } => "UpdateItemError::PreconditionFailed",
+            Self::UnexpectedError => "UpdateItemError::UnexpectedError",
+        }
+    }
+}
+
+impl From<ValidationErrors<ItemBuilderError>> for UpdateItemError {
+    fn from(value: ValidationErrors<ItemBuilderError>) -> Self {
+        Self::InvalidParams {
+            violations: value.violations(),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum DeleteItemError {
     #[error("Entity with id '{id:?}' doesn't exist in our records")]
     NoSuchEntity { id: Uuid },
+    #[error("Entity with id '{id:?}' was modified since the given version")]
+    PreconditionFailed { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
+impl ErrorVariant for DeleteItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "DeleteItemError::NoSuchEntity",
+            Self::PreconditionFailed { .. } => "DeleteItemError::PreconditionFailed",
+            Self::UnexpectedError => "DeleteItemError::UnexpectedError",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum ItemsHealthError {
     #[error("Something went wrong")]
     UnexpectedError,
 }
+
+impl ErrorVariant for ItemsHealthError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "ItemsHealthError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RestoreItemsError {
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for RestoreItemsError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "RestoreItemsError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ListTrashError {
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for ListTrashError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "ListTrashError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RecoverItemError {
+    #[error("Entity with id '{id:?}' doesn't exist in trash")]
+    NoSuchEntity { id: Uuid },
+    #[error("Entity with id '{id:?}' already exists in our records")]
+    AlreadyExists { id: Uuid },
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for RecoverItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "RecoverItemError::NoSuchEntity",
+            Self::AlreadyExists { .. } => "RecoverItemError::AlreadyExists",
+            Self::UnexpectedError => "RecoverItemError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum PurgeItemError {
+    #[error("Entity with id '{id:?}' doesn't exist in trash")]
+    NoSuchEntity { id: Uuid },
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for PurgeItemError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "PurgeItemError::NoSuchEntity",
+            Self::UnexpectedError => "PurgeItemError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum PurgeTrashError {
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for PurgeTrashError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "PurgeTrashError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ItemOperationError {
+    #[error(transparent)]
+    Create(#[from] CreateItemError),
+    #[error(transparent)]
+    Update(#[from] UpdateItemError),
+    #[error(transparent)]
+    Delete(#[from] DeleteItemError),
+}
+
+impl ErrorVariant for ItemOperationError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Create(error) => error.variant(),
+            Self::Update(error) => error.variant(),
+            Self::Delete(error) => error.variant(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum BatchItemsError {
+    #[error("Storage doesn't support atomic batches")]
+    Unsupported,
+    #[error("Operation #{index} failed, none of operations were applied: {error}")]
+    OperationFailed {
+        index: usize,
+        error: ItemOperationError,
+    },
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for BatchItemsError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Unsupported => "BatchItemsError::Unsupported",
+            Self::OperationFailed { .. } => "BatchItemsError::OperationFailed",
+            Self::UnexpectedError => "BatchItemsError::UnexpectedError",
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::dao::{
+    common::{Cache, CacheStats, Pagination, Precondition, RestoreMode},
+    items::{
+        BatchItemsError,
+        CreateItemError,
+        CreateItemParams,
+        DeleteItemError,
+        GetItemError,
+        Item,
+        ItemOperation,
+        ItemOperationOutcome,
+        ItemsDao,
+        ItemsHealthError,
+        ItemsSnapshot,
+        ListItemsError,
+        ListTrashError,
+        PurgeItemError,
+        PurgeTrashError,
+        RecoverItemError,
+        RestoreItemsError,
+        TrashedItem,
+        UpdateItemError,
+        UpdateItemParams,
+    },
+};
+
+pub struct CachedItemsDao<D> {
+    inner: D,
+    cache: Cache<Uuid, Item>,
+}
+
+impl<D> CachedItemsDao<D>
+where
+    D: ItemsDao + Send + Sync,
+{
+    pub fn new(inner: D, capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: Cache::new(capacity, ttl),
+        }
+    }
+}
+
+#[async_trait]
+impl<D> ItemsDao for CachedItemsDao<D>
+where
+    D: ItemsDao + Send + Sync,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<Item>, ListItemsError> {
+        self.inner.list(pagination).await
+    }
+
+    async fn list_owned(
+        &self,
+        owner_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<Vec<Item>, ListItemsError> {
+        self.inner.list_owned(owner_id, pagination).await
+    }
+
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError> {
+        self.inner.snapshot().await
+    }
+
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError> {
+        self.inner.modified_at().await
+    }
+
+    async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError> {
+        self.inner.create(params).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Item, GetItemError> {
+        if let Some(entity) = self.cache.get(&id) {
+            let CacheStats { hits, misses } = self.cache.stats();
+            debug!(hits, misses, "Items cache hit for {id}");
+            return Ok(entity);
+        }
+
+        let CacheStats { hits, misses } = self.cache.stats();
+        debug!(hits, misses, "Items cache miss for {id}");
+
+        let generation = self.cache.generation();
+        let entity = self.inner.get(id).await?;
+        self.cache.put(id, entity.clone(), generation);
+
+        Ok(entity)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    ) -> Result<Item, UpdateItemError> {
+        let result = self.inner.update(id, params, precondition).await;
+        self.cache.invalidate(&id);
+
+        result
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<Item, DeleteItemError> {
+        let result = self.inner.delete(id, precondition).await;
+        self.cache.invalidate(&id);
+
+        result
+    }
+
+    async fn batch(
+        &self,
+        operations: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError> {
+        let ids: Vec<Uuid> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                ItemOperation::Create { .. } => None,
+                ItemOperation::Update { id, .. } | ItemOperation::Delete { id, .. } => Some(*id),
+            })
+            .collect();
+        let result = self.inner.batch(operations).await;
+        for id in &ids {
+            self.cache.invalidate(id);
+        }
+
+        result
+    }
+
+    async fn restore(
+        &self,
+        snapshot: ItemsSnapshot,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreItemsError> {
+        let result = self.inner.restore(snapshot, mode).await;
+        self.cache.clear();
+
+        result
+    }
+
+    async fn list_trash(&self, pagination: Pagination) -> Result<Vec<TrashedItem>, ListTrashError> {
+        self.inner.list_trash(pagination).await
+    }
+
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError> {
+        self.inner.recover(id).await
+    }
+
+    async fn purge(&self, id: Uuid) -> Result<(), PurgeItemError> {
+        self.inner.purge(id).await
+    }
+
+    async fn purge_trash(&self, deleted_before: NaiveDateTime) -> Result<usize, PurgeTrashError> {
+        self.inner.purge_trash(deleted_before).await
+    }
+
+    async fn health(&self) -> Result<(), ItemsHealthError> {
+        self.inner.health().await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        Some(self.cache.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+    use crate::dao::items::ItemsHashMapDao;
+
+    /// Cached DAO along with the DAO it decorates, to change entities bypassing the cache
+    fn daos() -> (ItemsHashMapDao, CachedItemsDao<ItemsHashMapDao>) {
+        let inner = ItemsHashMapDao::new();
+        let dao = CachedItemsDao::new(
+            inner.clone(),
+            NonZeroUsize::new(16).unwrap(),
+            Duration::from_secs(60),
+        );
+
+        (inner, dao)
+    }
+
+    #[tokio::test]
+    async fn get_served_from_cache() {
+        let (inner, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        let first = dao.get(entity.id()).await.unwrap();
+        // The cached entity becomes stale
+        let updated = inner
+            .update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+        let second = dao.get(entity.id()).await.unwrap();
+
+        assert_eq!(first, entity);
+        assert_eq!(second, entity);
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 1, misses: 1 }));
+    }
+
+    #[tokio::test]
+    async fn update_invalidates() {
+        let (_, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        dao.get(entity.id()).await.unwrap();
+        let updated = dao
+            .update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+        let result = dao.get(entity.id()).await.unwrap();
+
+        assert_eq!(result, updated);
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 0, misses: 2 }));
+    }
+
+    #[tokio::test]
+    async fn delete_invalidates() {
+        let (_, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        dao.get(entity.id()).await.unwrap();
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+        let result = dao.get(entity.id()).await;
+        println!("{result:#?}");
+
+        assert_eq!(result, Err(GetItemError::NoSuchEntity { id: entity.id() }));
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 0, misses: 2 }));
+    }
+}
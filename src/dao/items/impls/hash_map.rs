@@ -4,39 +4,123 @@ use std::{
 };
 
 use axum::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
 use uuid::Uuid;
 
 use crate::dao::{
-    common::Pagination,
+    common::{Pagination, Precondition, RestoreMode},
     items::{
+        BatchItemsError,
         CreateItemError,
         CreateItemParams,
         DeleteItemError,
         GetItemError,
         Item,
+        ItemOperation,
+        ItemOperationError,
+        ItemOperationOutcome,
         ItemsDao,
         ItemsHealthError,
+        ItemsSnapshot,
         ListItemsError,
+        ListTrashError,
+        PurgeItemError,
+        PurgeTrashError,
+        RecoverItemError,
+        RestoreItemsError,
+        TrashedItem,
         UpdateItemError,
         UpdateItemParams,
     },
 };
 
 #[derive(Clone)]
-pub struct ItemsHashMapDao(Arc<RwLock<HashMap<Uuid, Item>>>);
+pub struct ItemsHashMapDao {
+    items: Arc<RwLock<HashMap<Uuid, Item>>>,
+    trash: Arc<RwLock<HashMap<Uuid, TrashedItem>>>,
+    modified_at: Arc<RwLock<NaiveDateTime>>,
+}
 
 impl ItemsHashMapDao {
     pub fn new() -> Self {
-        ItemsHashMapDao(Arc::new(RwLock::new(HashMap::new())))
+        ItemsHashMapDao {
+            items: Arc::new(RwLock::new(HashMap::new())),
+            trash: Arc::new(RwLock::new(HashMap::new())),
+            modified_at: Arc::new(RwLock::new(Utc::now().naive_utc())),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<Uuid, Item>> {
+        self.items.read().unwrap()
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<Uuid, Item>> {
+        self.items.write().unwrap()
+    }
+
+    // Always acquired after the items lock, if both are needed
+    fn trash_read(&self) -> RwLockReadGuard<'_, HashMap<Uuid, TrashedItem>> {
+        self.trash.read().unwrap()
+    }
+
+    fn trash_write(&self) -> RwLockWriteGuard<'_, HashMap<Uuid, TrashedItem>> {
+        self.trash.write().unwrap()
+    }
+
+    // Called with the items lock held, so versions follow the order of mutations
+    fn touch(&self) {
+        let mut modified_at = self.modified_at.write().unwrap();
+        *modified_at = Utc::now()
+            .naive_utc()
+            .max(*modified_at + Duration::nanoseconds(1));
     }
+}
+
+fn create_in(
+    data: &mut HashMap<Uuid, Item>,
+    params: CreateItemParams,
+) -> Result<Item, CreateItemError> {
+    let entity: Item = params.try_into()?;
 
-    fn read(&self) -> RwLockReadGuard<HashMap<Uuid, Item>> {
-        self.0.read().unwrap()
+    if let Entry::Vacant(e) = data.entry(entity.id()) {
+        Ok(e.insert(entity).to_owned())
+    } else {
+        Err(CreateItemError::AlreadyExists { id: entity.id() }) // Could only happen on a UUID collision
     }
+}
+
+fn update_in(
+    data: &mut HashMap<Uuid, Item>,
+    id: Uuid,
+    params: &UpdateItemParams,
+    precondition: &Precondition,
+) -> Result<Item, UpdateItemError> {
+    if let Some(entity) = data.get(&id) {
+        if !precondition.holds(entity.updated_at()) {
+            return Err(UpdateItemError::PreconditionFailed { id });
+        }
+
+        let updated = entity.clone().try_update(params)?;
+
+        let _ = data.insert(id, updated.clone());
+        Ok(updated)
+    } else {
+        Err(UpdateItemError::NoSuchEntity { id })
+    }
+}
+
+fn delete_in(
+    data: &mut HashMap<Uuid, Item>,
+    id: Uuid,
+    precondition: &Precondition,
+) -> Result<Item, DeleteItemError> {
+    let entity = data.get(&id).ok_or(DeleteItemError::NoSuchEntity { id })?;
 
-    fn write(&self) -> RwLockWriteGuard<HashMap<Uuid, Item>> {
-        self.0.write().unwrap()
+    if !precondition.holds(entity.updated_at()) {
+        return Err(DeleteItemError::PreconditionFailed { id });
     }
+
+    data.remove(&id).ok_or(DeleteItemError::NoSuchEntity { id })
 }
 
 #[async_trait]
@@ -55,15 +139,49 @@ impl ItemsDao for ItemsHashMapDao {
             .collect())
     }
 
+    async fn list_owned(
+        &self,
+        owner_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<Vec<Item>, ListItemsError> {
+        let data = self.read();
+        let mut vec: Vec<&Item> = data
+            .values()
+            .filter(|x| x.owner_id() == Some(owner_id))
+            .collect();
+
+        vec.sort_by_key(|x| x.updated_at());
+
+        Ok(vec
+            .into_iter()
+            .skip((pagination.page() - 1) * pagination.limit())
+            .take(pagination.limit())
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError> {
+        let data = self.read();
+        let trash = self.trash_read();
+        let mut items: Vec<Item> = data.values().cloned().collect();
+        let mut trash: Vec<TrashedItem> = trash.values().cloned().collect();
+
+        items.sort_by_key(Item::updated_at);
+        trash.sort_by_key(TrashedItem::deleted_at);
+
+        Ok(ItemsSnapshot { items, trash })
+    }
+
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError> {
+        Ok(*self.modified_at.read().unwrap())
+    }
+
     async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError> {
         let mut data = self.write();
-        let entity: Item = params.try_into().or(Err(CreateItemError::InvalidParams))?;
+        let entity = create_in(&mut data, params)?;
+        self.touch();
 
-        if let Entry::Vacant(e) = data.entry(entity.id()) {
-            Ok(e.insert(entity).to_owned())
-        } else {
-            Err(CreateItemError::AlreadyExists { id: entity.id() }) // Could only happen on a UUID collision
-        }
+        Ok(entity)
     }
 
     async fn get(&self, id: Uuid) -> Result<Item, GetItemError> {
@@ -74,28 +192,152 @@ impl ItemsDao for ItemsHashMapDao {
             .ok_or(GetItemError::NoSuchEntity { id })?)
     }
 
-    async fn update(&self, id: Uuid, params: UpdateItemParams) -> Result<Item, UpdateItemError> {
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    ) -> Result<Item, UpdateItemError> {
         let mut data = self.write();
-        if let Some(entity) = data.get(&id) {
-            let updated = entity
-                .clone()
-                .try_update(&params)
-                .or(Err(UpdateItemError::InvalidParams))?;
-
-            let _ = data.insert(id, updated.clone());
-            Ok(updated)
-        } else {
-            Err(UpdateItemError::NoSuchEntity { id })
+        let entity = update_in(&mut data, id, &params, &precondition)?;
+        self.touch();
+
+        Ok(entity)
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<Item, DeleteItemError> {
+        let mut data = self.write();
+        let entity = delete_in(&mut data, id, &precondition)?;
+
+        self.trash_write()
+            .insert(id, TrashedItem::new(entity.clone()));
+        self.touch();
+
+        Ok(entity)
+    }
+
+    async fn batch(
+        &self,
+        operations: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError> {
+        let mut data = self.write();
+        // Operations are applied to a copy, which replaces records only if all of them succeed
+        let mut staged = data.clone();
+        let mut deleted = Vec::new();
+        let mut outcomes = Vec::with_capacity(operations.len());
+
+        for (index, operation) in operations.into_iter().enumerate() {
+            let outcome = match operation {
+                ItemOperation::Create { params } => create_in(&mut staged, params)
+                    .map(ItemOperationOutcome::Created)
+                    .map_err(ItemOperationError::from),
+                ItemOperation::Update {
+                    id,
+                    params,
+                    precondition,
+                } => update_in(&mut staged, id, &params, &precondition)
+                    .map(ItemOperationOutcome::Updated)
+                    .map_err(ItemOperationError::from),
+                ItemOperation::Delete { id, precondition } => {
+                    delete_in(&mut staged, id, &precondition)
+                        .map(|entity| {
+                            deleted.push(entity.clone());
+                            ItemOperationOutcome::Deleted(entity)
+                        })
+                        .map_err(ItemOperationError::from)
+                }
+            }
+            .map_err(|error| BatchItemsError::OperationFailed { index, error })?;
+
+            outcomes.push(outcome);
         }
+
+        *data = staged;
+        self.trash_write().extend(
+            deleted
+                .into_iter()
+                .map(|entity| (entity.id(), TrashedItem::new(entity))),
+        );
+        self.touch();
+
+        Ok(outcomes)
     }
 
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteItemError> {
+    async fn restore(
+        &self,
+        snapshot: ItemsSnapshot,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreItemsError> {
         let mut data = self.write();
-        data.remove(&id)
-            .ok_or(DeleteItemError::NoSuchEntity { id })
+        let mut trash = self.trash_write();
+
+        if mode == RestoreMode::Replace {
+            data.clear();
+            trash.clear();
+        }
+
+        // Restored records take place of existing ones with the same id, wherever they are
+        for entity in snapshot.items {
+            trash.remove(&entity.id());
+            data.insert(entity.id(), entity);
+        }
+        for entity in snapshot.trash {
+            data.remove(&entity.item().id());
+            trash.insert(entity.item().id(), entity);
+        }
+        self.touch();
+
+        Ok(())
+    }
+
+    async fn list_trash(&self, pagination: Pagination) -> Result<Vec<TrashedItem>, ListTrashError> {
+        let trash = self.trash_read();
+        let mut vec: Vec<&TrashedItem> = trash.values().collect();
+
+        vec.sort_by_key(|x| x.deleted_at());
+
+        Ok(vec
+            .into_iter()
+            .skip((pagination.page() - 1) * pagination.limit())
+            .take(pagination.limit())
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError> {
+        let mut data = self.write();
+        let mut trash = self.trash_write();
+
+        if data.contains_key(&id) {
+            return Err(RecoverItemError::AlreadyExists { id });
+        }
+
+        let entity = trash
+            .remove(&id)
+            .ok_or(RecoverItemError::NoSuchEntity { id })?
+            .into_item();
+        self.touch();
+
+        Ok(data.entry(id).or_insert(entity).to_owned())
+    }
+
+    async fn purge(&self, id: Uuid) -> Result<(), PurgeItemError> {
+        let mut trash = self.trash_write();
+        trash
+            .remove(&id)
+            .ok_or(PurgeItemError::NoSuchEntity { id })
             .and(Ok(()))
     }
 
+    async fn purge_trash(&self, deleted_before: NaiveDateTime) -> Result<usize, PurgeTrashError> {
+        let mut trash = self.trash_write();
+        let count = trash.len();
+
+        trash.retain(|_, x| x.deleted_at().ge(&deleted_before));
+
+        Ok(count - trash.len())
+    }
+
     async fn health(&self) -> Result<(), ItemsHealthError> {
         Ok(())
     }
@@ -103,9 +345,15 @@ impl ItemsDao for ItemsHashMapDao {
 
 #[cfg(test)]
 mod tests {
+    use chrono::Utc;
     use fake::{Fake, Faker};
 
     use super::*;
+    use crate::dao::{common::PaginationBuilder, items::CreateItemsParamsBuilder};
+
+    fn first_page() -> Pagination {
+        PaginationBuilder::new().build().unwrap()
+    }
 
     #[tokio::test]
     async fn create() {
@@ -151,7 +399,96 @@ mod tests {
         println!("{params:#?}");
         let entity = dao.create(params.clone()).await.unwrap();
         println!("{entity:#?}");
-        dao.delete(entity.id()).await.unwrap();
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_moves_to_trash() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+
+        let trash = dao.list_trash(first_page()).await.unwrap();
+        println!("{trash:#?}");
+
+        assert_eq!(
+            dao.get(entity.id()).await,
+            Err(GetItemError::NoSuchEntity { id: entity.id() })
+        );
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].item(), &entity);
+    }
+
+    #[tokio::test]
+    async fn recover() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+
+        let result = dao.recover(entity.id()).await.unwrap();
+        println!("{result:#?}");
+
+        assert_eq!(result, entity);
+        assert_eq!(dao.get(entity.id()).await.unwrap(), entity);
+        assert!(dao.list_trash(first_page()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recover_non_existent() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        let result = dao.recover(entity.id()).await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(RecoverItemError::AlreadyExists { id: entity.id() })
+        );
+
+        let id = Faker.fake();
+        assert_eq!(
+            dao.recover(id).await,
+            Err(RecoverItemError::NoSuchEntity { id })
+        );
+    }
+
+    #[tokio::test]
+    async fn purge() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+        dao.purge(entity.id()).await.unwrap();
+
+        assert_eq!(
+            dao.recover(entity.id()).await,
+            Err(RecoverItemError::NoSuchEntity { id: entity.id() })
+        );
+        assert_eq!(
+            dao.purge(entity.id()).await,
+            Err(PurgeItemError::NoSuchEntity { id: entity.id() })
+        );
+    }
+
+    #[tokio::test]
+    async fn purge_trash() {
+        let dao = ItemsHashMapDao::new();
+        let expired = dao.create(Faker.fake()).await.unwrap();
+        dao.delete(expired.id(), Precondition::None).await.unwrap();
+        let deleted_before = Utc::now().naive_utc();
+        let kept = dao.create(Faker.fake()).await.unwrap();
+        dao.delete(kept.id(), Precondition::None).await.unwrap();
+
+        let result = dao.purge_trash(deleted_before).await.unwrap();
+        let trash = dao.list_trash(first_page()).await.unwrap();
+        println!("{trash:#?}");
+
+        assert_eq!(result, 1);
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].item(), &kept);
     }
 
     #[tokio::test]
@@ -159,7 +496,7 @@ mod tests {
         let dao = ItemsHashMapDao::new();
         let id = Faker.fake();
         println!("{id:#?}");
-        let result = dao.delete(id).await;
+        let result = dao.delete(id, Precondition::None).await;
         println!("{result:#?}");
 
         assert_eq!(result, Err(DeleteItemError::NoSuchEntity { id }));
@@ -176,7 +513,7 @@ mod tests {
         println!("{update_params:#?}");
 
         let update_result = dao
-            .update(entity.id(), update_params.clone())
+            .update(entity.id(), update_params.clone(), Precondition::None)
             .await
             .unwrap();
         println!("{update_result:#?}");
@@ -199,12 +536,140 @@ mod tests {
         println!("{id:#?}");
         let params = Faker.fake();
         println!("{params:#?}");
-        let result = dao.update(id, params).await;
+        let result = dao.update(id, params, Precondition::None).await;
         println!("{result:#?}");
 
         assert_eq!(result, Err(UpdateItemError::NoSuchEntity { id }));
     }
 
+    #[tokio::test]
+    async fn update_and_delete_precondition() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        let stale = Precondition::UpdatedAt(entity.updated_at());
+
+        let updated = dao
+            .update(entity.id(), Faker.fake(), stale.clone())
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+
+        let result = dao.update(entity.id(), Faker.fake(), stale.clone()).await;
+        assert_eq!(
+            result,
+            Err(UpdateItemError::PreconditionFailed { id: entity.id() })
+        );
+
+        let result = dao.delete(entity.id(), stale).await;
+        assert_eq!(
+            result,
+            Err(DeleteItemError::PreconditionFailed { id: entity.id() })
+        );
+        assert_eq!(dao.get(entity.id()).await, Ok(updated.clone()));
+
+        dao.delete(entity.id(), Precondition::UpdatedAt(updated.updated_at()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn modified_at() {
+        let dao = ItemsHashMapDao::new();
+        let initial = dao.modified_at().await.unwrap();
+
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        let created = dao.modified_at().await.unwrap();
+        assert!(created > initial);
+
+        dao.get(entity.id()).await.unwrap();
+        dao.list(first_page()).await.unwrap();
+        assert_eq!(dao.modified_at().await.unwrap(), created);
+
+        dao.update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        let updated = dao.modified_at().await.unwrap();
+        assert!(updated > created);
+
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+        assert!(dao.modified_at().await.unwrap() > updated);
+    }
+
+    #[tokio::test]
+    async fn batch() {
+        let dao = ItemsHashMapDao::new();
+        let updated = dao.create(Faker.fake()).await.unwrap();
+        let deleted = dao.create(Faker.fake()).await.unwrap();
+        let update_params: UpdateItemParams = Faker.fake();
+
+        let outcomes = dao
+            .batch(vec![
+                ItemOperation::Create {
+                    params: Faker.fake(),
+                },
+                ItemOperation::Update {
+                    id: updated.id(),
+                    params: update_params.clone(),
+                    precondition: Precondition::UpdatedAt(updated.updated_at()),
+                },
+                ItemOperation::Delete {
+                    id: deleted.id(),
+                    precondition: Precondition::None,
+                },
+            ])
+            .await
+            .unwrap();
+        println!("{outcomes:#?}");
+
+        let ItemOperationOutcome::Created(created) = &outcomes[0] else {
+            panic!("Unexpected outcome")
+        };
+        assert_eq!(dao.get(created.id()).await.as_ref(), Ok(created));
+        assert_eq!(
+            dao.get(updated.id()).await.unwrap().location(),
+            update_params.location()
+        );
+        assert_eq!(outcomes[2], ItemOperationOutcome::Deleted(deleted.clone()));
+        assert_eq!(dao.list_trash(first_page()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn batch_rolled_back() {
+        let dao = ItemsHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        let modified_at = dao.modified_at().await.unwrap();
+        let id = Faker.fake();
+
+        let result = dao
+            .batch(vec![
+                ItemOperation::Create {
+                    params: Faker.fake(),
+                },
+                ItemOperation::Delete {
+                    id: entity.id(),
+                    precondition: Precondition::None,
+                },
+                ItemOperation::Delete {
+                    id,
+                    precondition: Precondition::None,
+                },
+            ])
+            .await;
+        println!("{result:#?}");
+
+        assert_eq!(
+            result,
+            Err(BatchItemsError::OperationFailed {
+                index: 2,
+                error: DeleteItemError::NoSuchEntity { id }.into()
+            })
+        );
+        assert_eq!(dao.list(first_page()).await.unwrap(), vec![entity]);
+        assert!(dao.list_trash(first_page()).await.unwrap().is_empty());
+        assert_eq!(dao.modified_at().await.unwrap(), modified_at);
+    }
+
     #[tokio::test]
     async fn list_empty() {
         let dao = ItemsHashMapDao::new();
@@ -239,4 +704,91 @@ mod tests {
         let result = dao.list(pagination).await.unwrap();
         assert_eq!(result, vec);
     }
+
+    #[tokio::test]
+    async fn list_owned() {
+        let dao = ItemsHashMapDao::new();
+        let owner_id = Uuid::new_v4();
+
+        let owned = dao
+            .create(
+                CreateItemsParamsBuilder::new()
+                    .name("Tent".to_owned())
+                    .location("Banff, AB".to_owned())
+                    .build()
+                    .unwrap()
+                    .owned_by(Some(owner_id)),
+            )
+            .await
+            .unwrap();
+        dao.create(Faker.fake()).await.unwrap();
+
+        let result = dao.list_owned(owner_id, first_page()).await.unwrap();
+        assert_eq!(result, vec![owned]);
+        assert!(dao
+            .list_owned(Uuid::new_v4(), first_page())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn restore_merge() {
+        let dao = ItemsHashMapDao::new();
+        let existing = dao.create(Faker.fake()).await.unwrap();
+        println!("{existing:#?}");
+        let restored: Item = Faker.fake::<CreateItemParams>().try_into().unwrap();
+        println!("{restored:#?}");
+
+        dao.restore(
+            ItemsSnapshot {
+                items: vec![restored.clone()],
+                trash: Vec::new(),
+            },
+            RestoreMode::Merge,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(dao.get(existing.id()).await.unwrap(), existing);
+        assert_eq!(dao.get(restored.id()).await.unwrap(), restored);
+    }
+
+    #[tokio::test]
+    async fn restore_replace() {
+        let dao = ItemsHashMapDao::new();
+        let existing = dao.create(Faker.fake()).await.unwrap();
+        println!("{existing:#?}");
+        let trashed = dao.create(Faker.fake()).await.unwrap();
+        dao.delete(trashed.id(), Precondition::None).await.unwrap();
+        let restored: Item = Faker.fake::<CreateItemParams>().try_into().unwrap();
+        println!("{restored:#?}");
+        let restored_trash = TrashedItem::with_deleted_at(
+            Faker.fake::<CreateItemParams>().try_into().unwrap(),
+            Utc::now().naive_utc(),
+        );
+        println!("{restored_trash:#?}");
+
+        dao.restore(
+            ItemsSnapshot {
+                items: vec![restored.clone()],
+                trash: vec![restored_trash.clone()],
+            },
+            RestoreMode::Replace,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            dao.get(existing.id()).await,
+            Err(GetItemError::NoSuchEntity { id: existing.id() })
+        );
+        assert_eq!(
+            dao.snapshot().await.unwrap(),
+            ItemsSnapshot {
+                items: vec![restored],
+                trash: vec![restored_trash],
+            }
+        );
+    }
 }
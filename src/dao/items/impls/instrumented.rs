@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::dao::{
+    common::{CacheStats, DaoMetrics, Pagination, Precondition, RestoreMode},
+    items::{
+        BatchItemsError,
+        CreateItemError,
+        CreateItemParams,
+        DeleteItemError,
+        GetItemError,
+        Item,
+        ItemOperation,
+        ItemOperationOutcome,
+        ItemsDao,
+        ItemsHealthError,
+        ItemsSnapshot,
+        ListItemsError,
+        ListTrashError,
+        PurgeItemError,
+        PurgeTrashError,
+        RecoverItemError,
+        RestoreItemsError,
+        TrashedItem,
+        UpdateItemError,
+        UpdateItemParams,
+    },
+};
+
+const DAO_LABEL: &str = "items";
+
+pub struct InstrumentedItemsDao<D> {
+    inner: D,
+    metrics: Arc<DaoMetrics>,
+}
+
+impl<D> InstrumentedItemsDao<D>
+where
+    D: ItemsDao + Send + Sync,
+{
+    pub fn new(inner: D, metrics: Arc<DaoMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl<D> ItemsDao for InstrumentedItemsDao<D>
+where
+    D: ItemsDao + Send + Sync,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<Item>, ListItemsError> {
+        self.metrics
+            .measure(DAO_LABEL, "list", self.inner.list(pagination))
+            .await
+    }
+
+    async fn list_owned(
+        &self,
+        owner_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<Vec<Item>, ListItemsError> {
+        self.metrics
+            .measure(
+                DAO_LABEL,
+                "list_owned",
+                self.inner.list_owned(owner_id, pagination),
+            )
+            .await
+    }
+
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError> {
+        self.metrics
+            .measure(DAO_LABEL, "snapshot", self.inner.snapshot())
+            .await
+    }
+
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError> {
+        self.metrics
+            .measure(DAO_LABEL, "modified_at", self.inner.modified_at())
+            .await
+    }
+
+    async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError> {
+        self.metrics
+            .measure(DAO_LABEL, "create", self.inner.create(params))
+            .await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Item, GetItemError> {
+        self.metrics
+            .measure(DAO_LABEL, "get", self.inner.get(id))
+            .await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    ) -> Result<Item, UpdateItemError> {
+        self.metrics
+            .measure(
+                DAO_LABEL,
+                "update",
+                self.inner.update(id, params, precondition),
+            )
+            .await
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<Item, DeleteItemError> {
+        self.metrics
+            .measure(DAO_LABEL, "delete", self.inner.delete(id, precondition))
+            .await
+    }
+
+    async fn batch(
+        &self,
+        operations: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError> {
+        self.metrics
+            .measure(DAO_LABEL, "batch", self.inner.batch(operations))
+            .await
+    }
+
+    async fn restore(
+        &self,
+        snapshot: ItemsSnapshot,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreItemsError> {
+        self.metrics
+            .measure(DAO_LABEL, "restore", self.inner.restore(snapshot, mode))
+            .await
+    }
+
+    async fn list_trash(&self, pagination: Pagination) -> Result<Vec<TrashedItem>, ListTrashError> {
+        self.metrics
+            .measure(DAO_LABEL, "list_trash", self.inner.list_trash(pagination))
+            .await
+    }
+
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError> {
+        self.metrics
+            .measure(DAO_LABEL, "recover", self.inner.recover(id))
+            .await
+    }
+
+    async fn purge(&self, id: Uuid) -> Result<(), PurgeItemError> {
+        self.metrics
+            .measure(DAO_LABEL, "purge", self.inner.purge(id))
+            .await
+    }
+
+    async fn purge_trash(&self, deleted_before: NaiveDateTime) -> Result<usize, PurgeTrashError> {
+        self.metrics
+            .measure(
+                DAO_LABEL,
+                "purge_trash",
+                self.inner.purge_trash(deleted_before),
+            )
+            .await
+    }
+
+    async fn health(&self) -> Result<(), ItemsHealthError> {
+        self.metrics
+            .measure(DAO_LABEL, "health", self.inner.health())
+            .await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+    use crate::dao::{common::Outcome, items::ItemsHashMapDao};
+
+    #[tokio::test]
+    async fn calls_recorded() {
+        let metrics = Arc::new(DaoMetrics::new());
+        let dao = InstrumentedItemsDao::new(ItemsHashMapDao::new(), metrics.clone());
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        dao.get(entity.id()).await.unwrap();
+        let id = Faker.fake();
+        let err = dao.get(id).await;
+        println!("{err:#?}");
+
+        assert_eq!(err, Err(GetItemError::NoSuchEntity { id }));
+        assert_eq!(metrics.get(DAO_LABEL, "create", Outcome::Ok).calls, 1);
+        assert_eq!(metrics.get(DAO_LABEL, "get", Outcome::Ok).calls, 1);
+        assert_eq!(
+            metrics
+                .get(DAO_LABEL, "get", Outcome::Err("GetItemError::NoSuchEntity"))
+                .calls,
+            1
+        );
+    }
+}
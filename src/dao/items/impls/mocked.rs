@@ -1,18 +1,29 @@
 use axum::async_trait;
+use chrono::{DateTime, NaiveDateTime};
 use uuid::Uuid;
 
 use crate::dao::{
-    common::Pagination,
+    common::{Pagination, Precondition, RestoreMode},
     items::{
         dtos::ItemBuilder,
+        BatchItemsError,
         CreateItemError,
         CreateItemParams,
         DeleteItemError,
         GetItemError,
         Item,
+        ItemOperation,
+        ItemOperationOutcome,
         ItemsDao,
         ItemsHealthError,
+        ItemsSnapshot,
         ListItemsError,
+        ListTrashError,
+        PurgeItemError,
+        PurgeTrashError,
+        RecoverItemError,
+        RestoreItemsError,
+        TrashedItem,
         UpdateItemError,
         UpdateItemParams,
     },
@@ -32,8 +43,36 @@ impl ItemsDao for ItemsMockedDao {
         Ok(vec![entity])
     }
 
+    async fn list_owned(&self, owner_id: Uuid, _: Pagination) -> Result<Vec<Item>, ListItemsError> {
+        let entity = ItemBuilder::new()
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .owner_id(Some(owner_id))
+            .build()
+            .or(Err(ListItemsError::UnexpectedError))?;
+
+        Ok(vec![entity])
+    }
+
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError> {
+        let entity = ItemBuilder::new()
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .build()
+            .or(Err(ListItemsError::UnexpectedError))?;
+
+        Ok(ItemsSnapshot {
+            items: vec![entity],
+            trash: Vec::new(),
+        })
+    }
+
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError> {
+        Ok(DateTime::UNIX_EPOCH.naive_utc())
+    }
+
     async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError> {
-        let entity = params.try_into().or(Err(CreateItemError::InvalidParams))?;
+        let entity = params.try_into()?;
 
         Ok(entity)
     }
@@ -49,23 +88,72 @@ impl ItemsDao for ItemsMockedDao {
         Ok(entity)
     }
 
-    async fn update(&self, id: Uuid, params: UpdateItemParams) -> Result<Item, UpdateItemError> {
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        _: Precondition,
+    ) -> Result<Item, UpdateItemError> {
         let entity = ItemBuilder::new()
             .id(id)
             .name("Sleeping Bag".to_owned())
             .location("Calgary, AB".to_owned())
             .build()
             .or(Err(UpdateItemError::UnexpectedError))?
-            .try_update(&params)
-            .or(Err(UpdateItemError::InvalidParams))?;
+            .try_update(&params)?;
+
+        Ok(entity)
+    }
+
+    async fn delete(&self, id: Uuid, _: Precondition) -> Result<Item, DeleteItemError> {
+        ItemBuilder::new()
+            .id(id)
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .build()
+            .or(Err(DeleteItemError::UnexpectedError))
+    }
+
+    async fn batch(
+        &self,
+        _: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError> {
+        Err(BatchItemsError::Unsupported)
+    }
+
+    async fn restore(&self, _: ItemsSnapshot, _: RestoreMode) -> Result<(), RestoreItemsError> {
+        Ok(())
+    }
+
+    async fn list_trash(&self, _: Pagination) -> Result<Vec<TrashedItem>, ListTrashError> {
+        let entity = ItemBuilder::new()
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .build()
+            .or(Err(ListTrashError::UnexpectedError))?;
+
+        Ok(vec![TrashedItem::new(entity)])
+    }
+
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError> {
+        let entity = ItemBuilder::new()
+            .id(id)
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .build()
+            .or(Err(RecoverItemError::UnexpectedError))?;
 
         Ok(entity)
     }
 
-    async fn delete(&self, _: Uuid) -> Result<(), DeleteItemError> {
+    async fn purge(&self, _: Uuid) -> Result<(), PurgeItemError> {
         Ok(())
     }
 
+    async fn purge_trash(&self, _: NaiveDateTime) -> Result<usize, PurgeTrashError> {
+        Ok(0)
+    }
+
     async fn health(&self) -> Result<(), ItemsHealthError> {
         Ok(())
     }
@@ -1,5 +1,9 @@
+pub use cached::CachedItemsDao;
 pub use hash_map::ItemsHashMapDao;
+pub use instrumented::InstrumentedItemsDao;
 pub use mocked::ItemsMockedDao;
 
+mod cached;
 mod hash_map;
+mod instrumented;
 mod mocked;
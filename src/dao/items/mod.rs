@@ -1,25 +1,41 @@
+use std::sync::Arc;
+
 use axum::async_trait;
+use chrono::NaiveDateTime;
 pub use dtos::{
     CreateItemParams,
     CreateItemParamsBuilderError,
     CreateItemsParamsBuilder,
     Item,
+    ItemBuilder,
+    ItemBuilderError,
+    ItemOperation,
+    ItemOperationOutcome,
+    ItemsSnapshot,
+    TrashedItem,
     UpdateItemParams,
     UpdateItemParamsBuilder,
     UpdateItemParamsBuilderError,
 };
 pub use errors::{
+    BatchItemsError,
     CreateItemError,
     DeleteItemError,
     GetItemError,
+    ItemOperationError,
     ItemsHealthError,
     ListItemsError,
+    ListTrashError,
+    PurgeItemError,
+    PurgeTrashError,
+    RecoverItemError,
+    RestoreItemsError,
     UpdateItemError,
 };
-pub use impls::{ItemsHashMapDao, ItemsMockedDao};
+pub use impls::{CachedItemsDao, InstrumentedItemsDao, ItemsHashMapDao, ItemsMockedDao};
 use uuid::Uuid;
 
-use crate::dao::common::Pagination;
+use crate::dao::common::{CacheStats, Pagination, Precondition, RestoreMode};
 
 mod dtos;
 mod errors;
@@ -28,9 +44,130 @@ mod impls;
 #[async_trait]
 pub trait ItemsDao {
     async fn list(&self, pagination: Pagination) -> Result<Vec<Item>, ListItemsError>;
+    /// Same as [`Self::list`], but only items of the owner
+    async fn list_owned(
+        &self,
+        owner_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<Vec<Item>, ListItemsError>;
+    /// Entities and trash as of a single moment, ordered like in [`Self::list`] and
+    /// [`Self::list_trash`]
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError>;
+    /// Moment of the latest change to the collection, which affects listing
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError>;
     async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError>;
     async fn get(&self, id: Uuid) -> Result<Item, GetItemError>;
-    async fn update(&self, id: Uuid, params: UpdateItemParams) -> Result<Item, UpdateItemError>;
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteItemError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    ) -> Result<Item, UpdateItemError>;
+    /// Moves item to trash and returns it as it was before deletion
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<Item, DeleteItemError>;
+    /// Applies either all of operations or none of them
+    async fn batch(
+        &self,
+        operations: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError>;
+    async fn restore(
+        &self,
+        snapshot: ItemsSnapshot,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreItemsError>;
+    async fn list_trash(&self, pagination: Pagination) -> Result<Vec<TrashedItem>, ListTrashError>;
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError>;
+    async fn purge(&self, id: Uuid) -> Result<(), PurgeItemError>;
+    async fn purge_trash(&self, deleted_before: NaiveDateTime) -> Result<usize, PurgeTrashError>;
     async fn health(&self) -> Result<(), ItemsHealthError>;
+    /// Statistics of the cache in front of the DAO, if any
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+#[async_trait]
+impl<T> ItemsDao for Arc<T>
+where
+    T: ItemsDao + Send + Sync + ?Sized,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<Item>, ListItemsError> {
+        self.as_ref().list(pagination).await
+    }
+
+    async fn list_owned(
+        &self,
+        owner_id: Uuid,
+        pagination: Pagination,
+    ) -> Result<Vec<Item>, ListItemsError> {
+        self.as_ref().list_owned(owner_id, pagination).await
+    }
+
+    async fn snapshot(&self) -> Result<ItemsSnapshot, ListItemsError> {
+        self.as_ref().snapshot().await
+    }
+
+    async fn modified_at(&self) -> Result<NaiveDateTime, ListItemsError> {
+        self.as_ref().modified_at().await
+    }
+
+    async fn create(&self, params: CreateItemParams) -> Result<Item, CreateItemError> {
+        self.as_ref().create(params).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Item, GetItemError> {
+        self.as_ref().get(id).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateItemParams,
+        precondition: Precondition,
+    ) -> Result<Item, UpdateItemError> {
+        self.as_ref().update(id, params, precondition).await
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<Item, DeleteItemError> {
+        self.as_ref().delete(id, precondition).await
+    }
+
+    async fn batch(
+        &self,
+        operations: Vec<ItemOperation>,
+    ) -> Result<Vec<ItemOperationOutcome>, BatchItemsError> {
+        self.as_ref().batch(operations).await
+    }
+
+    async fn restore(
+        &self,
+        snapshot: ItemsSnapshot,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreItemsError> {
+        self.as_ref().restore(snapshot, mode).await
+    }
+
+    async fn list_trash(&self, pagination: Pagination) -> Result<Vec<TrashedItem>, ListTrashError> {
+        self.as_ref().list_trash(pagination).await
+    }
+
+    async fn recover(&self, id: Uuid) -> Result<Item, RecoverItemError> {
+        self.as_ref().recover(id).await
+    }
+
+    async fn purge(&self, id: Uuid) -> Result<(), PurgeItemError> {
+        self.as_ref().purge(id).await
+    }
+
+    async fn purge_trash(&self, deleted_before: NaiveDateTime) -> Result<usize, PurgeTrashError> {
+        self.as_ref().purge_trash(deleted_before).await
+    }
+
+    async fn health(&self) -> Result<(), ItemsHealthError> {
+        self.as_ref().health().await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.as_ref().cache_stats()
+    }
 }
@@ -1,27 +1,58 @@
-pub use common::{Pagination, PaginationBuilder, PaginationBuilderError};
+pub use common::{
+    CacheStats,
+    Constraint,
+    DaoMetrics,
+    ErrorVariant,
+    Pagination,
+    PaginationBuilder,
+    PaginationBuilderError,
+    Precondition,
+    RestoreMode,
+    ValidationErrors,
+    Violation,
+};
 pub use items::{
+    BatchItemsError,
+    CachedItemsDao,
     CreateItemError,
     CreateItemParams,
     CreateItemParamsBuilderError,
     CreateItemsParamsBuilder,
     DeleteItemError,
     GetItemError,
+    InstrumentedItemsDao,
     Item,
+    ItemBuilder,
+    ItemBuilderError,
+    ItemOperation,
+    ItemOperationError,
+    ItemOperationOutcome,
     ItemsDao,
     ItemsHashMapDao,
     ItemsHealthError,
     ItemsMockedDao,
+    ItemsSnapshot,
     ListItemsError,
+    ListTrashError,
+    PurgeItemError,
+    RecoverItemError,
+    RestoreItemsError,
+    TrashedItem,
     UpdateItemError,
     UpdateItemParams,
     UpdateItemParamsBuilder,
     UpdateItemParamsBuilderError,
 };
 pub use users::{
+    CachedUsersDao,
     CreateUserError,
     CreateUserParams,
+    CreateUserValidationError,
     DeleteUserError,
     GetUserError,
+    InstrumentedUsersDao,
+    ListUsersError,
+    RestoreUsersError,
     UpdateUserError,
     UpdateUserParams,
     User,
@@ -4,6 +4,7 @@ use fake::{faker::lorem::en::Word, faker::name::en::Name, Dummy};
 use uuid::Uuid;
 
 use super::entity::{CreateUserValidationError, User, UserAuthType};
+use crate::dao::common::ValidationErrors;
 
 #[cfg_attr(test, derive(Dummy, Debug, Clone, PartialEq, Eq))]
 pub struct CreateUserParams {
@@ -37,7 +38,7 @@ impl CreateUserParams {
 }
 
 impl TryInto<User> for CreateUserParams {
-    type Error = CreateUserValidationError;
+    type Error = ValidationErrors<CreateUserValidationError>;
 
     fn try_into(self) -> Result<User, Self::Error> {
         let now = Utc::now().naive_utc();
@@ -13,8 +13,9 @@ use fake::{
 use thiserror::Error;
 use uuid::Uuid;
 
-#[derive(Clone, Debug, Copy)]
-#[cfg_attr(test, derive(Eq, PartialEq))]
+use crate::dao::common::{Constraint, FieldViolation, ValidationErrors};
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq)]
 pub enum UserAuthType {
     Github,
 }
@@ -66,39 +67,85 @@ pub enum UpdateUserValidationError {
     NameTooLong { name: String },
 }
 
+impl FieldViolation for CreateUserValidationError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::NameIsEmpty | Self::NameTooLong { .. } => "name",
+            Self::ExternalIdIsEmpty | Self::ExternalIdTooLong { .. } => "external_id",
+            Self::UpdatedBeforeCreation { .. } => "updated_at",
+        }
+    }
+
+    fn constraint(&self) -> Constraint {
+        match self {
+            Self::NameIsEmpty | Self::ExternalIdIsEmpty => Constraint::NotEmpty,
+            Self::NameTooLong { .. } => Constraint::MaxLength {
+                max: User::MAX_NAME_LENGTH,
+            },
+            Self::ExternalIdTooLong { .. } => Constraint::MaxLength {
+                max: User::MAX_EXTERNAL_ID_LENGTH,
+            },
+            Self::UpdatedBeforeCreation { .. } => Constraint::NotBefore {
+                field: "created_at",
+            },
+        }
+    }
+}
+
+impl FieldViolation for UpdateUserValidationError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::NameIsEmpty | Self::NameTooLong { .. } => "name",
+        }
+    }
+
+    fn constraint(&self) -> Constraint {
+        match self {
+            Self::NameIsEmpty => Constraint::NotEmpty,
+            Self::NameTooLong { .. } => Constraint::MaxLength {
+                max: User::MAX_NAME_LENGTH,
+            },
+        }
+    }
+}
+
 impl User {
     const MAX_NAME_LENGTH: usize = 128;
     const MAX_EXTERNAL_ID_LENGTH: usize = 128;
 
-    pub(super) fn new(
+    pub fn new(
         id: Uuid,
         name: String,
         auth_type: UserAuthType,
         external_id: String,
         created_at: NaiveDateTime,
         updated_at: NaiveDateTime,
-    ) -> Result<Self, CreateUserValidationError> {
+    ) -> Result<Self, ValidationErrors<CreateUserValidationError>> {
+        let mut errors = ValidationErrors::new();
+
         if name.is_empty() {
-            return Err(CreateUserValidationError::NameIsEmpty);
-        }
-        if name.len().gt(&Self::MAX_NAME_LENGTH) {
-            return Err(CreateUserValidationError::NameTooLong { name });
+            errors.push(CreateUserValidationError::NameIsEmpty);
+        } else if name.len().gt(&Self::MAX_NAME_LENGTH) {
+            errors.push(CreateUserValidationError::NameTooLong { name: name.clone() });
         }
 
         if external_id.is_empty() {
-            return Err(CreateUserValidationError::ExternalIdIsEmpty);
-        }
-        if external_id.len().gt(&Self::MAX_EXTERNAL_ID_LENGTH) {
-            return Err(CreateUserValidationError::ExternalIdTooLong { external_id });
+            errors.push(CreateUserValidationError::ExternalIdIsEmpty);
+        } else if external_id.len().gt(&Self::MAX_EXTERNAL_ID_LENGTH) {
+            errors.push(CreateUserValidationError::ExternalIdTooLong {
+                external_id: external_id.clone(),
+            });
         }
 
         if updated_at.lt(&created_at) {
-            return Err(CreateUserValidationError::UpdatedBeforeCreation {
+            errors.push(CreateUserValidationError::UpdatedBeforeCreation {
                 updated_at,
                 created_at,
             });
         }
 
+        errors.into_result(())?;
+
         Ok(User {
             id,
             name,
@@ -187,7 +234,7 @@ mod tests {
             faked.created_at,
             faked.updated_at,
         );
-        assert_eq!(err, Err(CreateUserValidationError::NameIsEmpty));
+        assert_eq!(err, Err(CreateUserValidationError::NameIsEmpty.into()));
 
         let long: String = ((User::MAX_NAME_LENGTH + 1)..(User::MAX_NAME_LENGTH * 2)).fake();
 
@@ -202,7 +249,7 @@ mod tests {
 
         assert_eq!(
             err,
-            Err(CreateUserValidationError::NameTooLong { name: long })
+            Err(CreateUserValidationError::NameTooLong { name: long }.into())
         );
     }
 
@@ -242,7 +289,10 @@ mod tests {
             faked.created_at,
             faked.updated_at,
         );
-        assert_eq!(err, Err(CreateUserValidationError::ExternalIdIsEmpty));
+        assert_eq!(
+            err,
+            Err(CreateUserValidationError::ExternalIdIsEmpty.into())
+        );
 
         let long: String =
             ((User::MAX_EXTERNAL_ID_LENGTH + 1)..(User::MAX_EXTERNAL_ID_LENGTH * 2)).fake();
@@ -258,7 +308,7 @@ mod tests {
 
         assert_eq!(
             err,
-            Err(CreateUserValidationError::ExternalIdTooLong { external_id: long })
+            Err(CreateUserValidationError::ExternalIdTooLong { external_id: long }.into())
         );
     }
 
@@ -287,7 +337,31 @@ mod tests {
             Err(CreateUserValidationError::UpdatedBeforeCreation {
                 updated_at,
                 created_at
-            })
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn all_violations_reported() {
+        let faked: User = Faker.fake();
+        let err = User::new(
+            faked.id,
+            String::new(),
+            faked.auth_type,
+            String::new(),
+            faked.created_at,
+            faked.updated_at,
+        )
+        .unwrap_err();
+        println!("{err:#?}");
+
+        assert_eq!(
+            err.errors(),
+            [
+                CreateUserValidationError::NameIsEmpty,
+                CreateUserValidationError::ExternalIdIsEmpty
+            ]
         );
     }
 }
@@ -2,21 +2,34 @@ use thiserror::Error;
 use uuid::Uuid;
 
 use super::dtos::{CreateUserValidationError, UpdateUserValidationError};
+use crate::dao::common::{ErrorVariant, ValidationErrors, Violation};
 
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum CreateUserError {
     #[error("Cannot create entity from given params")]
-    InvalidParams,
+    InvalidParams { violations: Vec<Violation> },
     #[error("Entity with id '{id:?}' already exists in our records")]
     AlreadyExists { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
-impl From<CreateUserValidationError> for CreateUserError {
-    fn from(_: CreateUserValidationError) -> Self {
-        Self::InvalidParams
+impl ErrorVariant for CreateUserError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidParams { .. } => "CreateUserError::InvalidParams",
+            Self::AlreadyExists { .. } => "CreateUserError::AlreadyExists",
+            Self::UnexpectedError => "CreateUserError::UnexpectedError",
+        }
+    }
+}
+
+impl From<ValidationErrors<CreateUserValidationError>> for CreateUserError {
+    fn from(value: ValidationErrors<CreateUserValidationError>) -> Self {
+        Self::InvalidParams {
+            violations: value.violations(),
+        }
     }
 }
 
@@ -29,20 +42,44 @@ pub enum GetUserError {
     UnexpectedError,
 }
 
+impl ErrorVariant for GetUserError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "GetUserError::NoSuchEntity",
+            Self::UnexpectedError => "GetUserError::UnexpectedError",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum UpdateUserError {
     #[error("Cannot update entity with given params")]
-    InvalidParams,
+    InvalidParams { violations: Vec<Violation> },
     #[error("Entity with id '{id:?}' doesn't exist in our records")]
     NoSuchEntity { id: Uuid },
+    #[error("Entity with id '{id:?}' was modified since the given version")]
+    PreconditionFailed { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
+impl ErrorVariant for UpdateUserError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidParams { .. } => "UpdateUserError::InvalidParams",
+            Self::NoSuchEntity { .. } => "UpdateUserError::NoSuchEntity",
+            Self::PreconditionFailed { .. } => "UpdateUserError::PreconditionFailed",
+            Self::UnexpectedError => "UpdateUserError::UnexpectedError",
+        }
+    }
+}
+
 impl From<UpdateUserValidationError> for UpdateUserError {
-    fn from(_: UpdateUserValidationError) -> Self {
-        UpdateUserError::InvalidParams
+    fn from(value: UpdateUserValidationError) -> Self {
+        UpdateUserError::InvalidParams {
+            violations: vec![(&value).into()],
+        }
     }
 }
 
@@ -51,13 +88,63 @@ impl From<UpdateUserValidationError> for UpdateUserError {
 pub enum DeleteUserError {
     #[error("Entity with id '{id:?}' doesn't exist in our records")]
     NoSuchEntity { id: Uuid },
+    #[error("Entity with id '{id:?}' was modified since the given version")]
+    PreconditionFailed { id: Uuid },
     #[error("Something went wrong")]
     UnexpectedError,
 }
 
+impl ErrorVariant for DeleteUserError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchEntity { .. } => "DeleteUserError::NoSuchEntity",
+            Self::PreconditionFailed { .. } => "DeleteUserError::PreconditionFailed",
+            Self::UnexpectedError => "DeleteUserError::UnexpectedError",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum UsersHealthError {
     #[error("Something went wrong")]
     UnexpectedError,
 }
+
+impl ErrorVariant for UsersHealthError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "UsersHealthError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ListUsersError {
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for ListUsersError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "ListUsersError::UnexpectedError",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RestoreUsersError {
+    #[error("Something went wrong")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for RestoreUsersError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnexpectedError => "RestoreUsersError::UnexpectedError",
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use axum::async_trait;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::{
+    dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType},
+    errors::{
+        CreateUserError,
+        DeleteUserError,
+        GetUserError,
+        ListUsersError,
+        RestoreUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
+    interface::UsersDao,
+};
+use crate::dao::common::{Cache, CacheStats, Pagination, Precondition, RestoreMode};
+
+pub struct CachedUsersDao<D> {
+    inner: D,
+    cache: Cache<Uuid, User>,
+}
+
+impl<D> CachedUsersDao<D>
+where
+    D: UsersDao + Send + Sync,
+{
+    pub fn new(inner: D, capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: Cache::new(capacity, ttl),
+        }
+    }
+}
+
+#[async_trait]
+impl<D> UsersDao for CachedUsersDao<D>
+where
+    D: UsersDao + Send + Sync,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<User>, ListUsersError> {
+        self.inner.list(pagination).await
+    }
+
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+        self.inner.snapshot().await
+    }
+
+    async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError> {
+        self.inner.create(params).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<User, GetUserError> {
+        if let Some(entity) = self.cache.get(&id) {
+            let CacheStats { hits, misses } = self.cache.stats();
+            debug!(hits, misses, "Users cache hit for {id}");
+            return Ok(entity);
+        }
+
+        let CacheStats { hits, misses } = self.cache.stats();
+        debug!(hits, misses, "Users cache miss for {id}");
+
+        let generation = self.cache.generation();
+        let entity = self.inner.get(id).await?;
+        self.cache.put(id, entity.clone(), generation);
+
+        Ok(entity)
+    }
+
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError> {
+        self.inner.find_by_external_id(auth_type, external_id).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        precondition: Precondition,
+    ) -> Result<User, UpdateUserError> {
+        let result = self.inner.update(id, params, precondition).await;
+        self.cache.invalidate(&id);
+
+        result
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<(), DeleteUserError> {
+        let result = self.inner.delete(id, precondition).await;
+        self.cache.invalidate(&id);
+
+        result
+    }
+
+    async fn restore(
+        &self,
+        entities: Vec<User>,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreUsersError> {
+        let result = self.inner.restore(entities, mode).await;
+        self.cache.clear();
+
+        result
+    }
+
+    async fn health(&self) -> Result<(), UsersHealthError> {
+        self.inner.health().await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        Some(self.cache.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+    use crate::dao::users::UsersHashMapDao;
+
+    /// Cached DAO along with the DAO it decorates, to change entities bypassing the cache
+    fn daos() -> (UsersHashMapDao, CachedUsersDao<UsersHashMapDao>) {
+        let inner = UsersHashMapDao::new();
+        let dao = CachedUsersDao::new(
+            inner.clone(),
+            NonZeroUsize::new(16).unwrap(),
+            Duration::from_secs(60),
+        );
+
+        (inner, dao)
+    }
+
+    #[tokio::test]
+    async fn get_served_from_cache() {
+        let (inner, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        let first = dao.get(entity.id()).await.unwrap();
+        // The cached entity becomes stale
+        let updated = inner
+            .update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+        let second = dao.get(entity.id()).await.unwrap();
+
+        assert_eq!(first, entity);
+        assert_eq!(second, entity);
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 1, misses: 1 }));
+    }
+
+    #[tokio::test]
+    async fn update_invalidates() {
+        let (_, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        dao.get(entity.id()).await.unwrap();
+        let updated = dao
+            .update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+        let result = dao.get(entity.id()).await.unwrap();
+
+        assert_eq!(result, updated);
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 0, misses: 2 }));
+    }
+
+    #[tokio::test]
+    async fn delete_invalidates() {
+        let (_, dao) = daos();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+
+        dao.get(entity.id()).await.unwrap();
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
+        let result = dao.get(entity.id()).await;
+        println!("{result:#?}");
+
+        assert_eq!(result, Err(GetUserError::NoSuchEntity { id: entity.id() }));
+        assert_eq!(dao.cache_stats(), Some(CacheStats { hits: 0, misses: 2 }));
+    }
+}
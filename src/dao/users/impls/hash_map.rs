@@ -7,10 +7,19 @@ use axum::async_trait;
 use uuid::Uuid;
 
 use super::{
-    dtos::{CreateUserParams, UpdateUserParams, User},
-    errors::{CreateUserError, DeleteUserError, GetUserError, UpdateUserError, UsersHealthError},
+    dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType},
+    errors::{
+        CreateUserError,
+        DeleteUserError,
+        GetUserError,
+        ListUsersError,
+        RestoreUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
     interface::UsersDao,
 };
+use crate::dao::common::{Pagination, Precondition, RestoreMode};
 
 #[derive(Clone)]
 pub struct UsersHashMapDao(Arc<RwLock<HashMap<Uuid, User>>>);
@@ -20,17 +29,40 @@ impl UsersHashMapDao {
         UsersHashMapDao(Arc::new(RwLock::new(HashMap::new())))
     }
 
-    fn read(&self) -> RwLockReadGuard<HashMap<Uuid, User>> {
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<Uuid, User>> {
         self.0.read().unwrap()
     }
 
-    fn write(&self) -> RwLockWriteGuard<HashMap<Uuid, User>> {
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<Uuid, User>> {
         self.0.write().unwrap()
     }
 }
 
 #[async_trait]
 impl UsersDao for UsersHashMapDao {
+    async fn list(&self, pagination: Pagination) -> Result<Vec<User>, ListUsersError> {
+        let data = self.read();
+        let mut vec: Vec<&User> = data.values().collect();
+
+        vec.sort_by_key(|x| x.updated_at());
+
+        Ok(vec
+            .into_iter()
+            .skip((pagination.page() - 1) * pagination.limit())
+            .take(pagination.limit())
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+        let data = self.read();
+        let mut vec: Vec<User> = data.values().cloned().collect();
+
+        vec.sort_by_key(User::updated_at);
+
+        Ok(vec)
+    }
+
     async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError> {
         let mut data = self.write();
         let entity: User = params.try_into()?;
@@ -50,9 +82,30 @@ impl UsersDao for UsersHashMapDao {
             .ok_or(GetUserError::NoSuchEntity { id })?)
     }
 
-    async fn update(&self, id: Uuid, params: UpdateUserParams) -> Result<User, UpdateUserError> {
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError> {
+        let data = self.read();
+        Ok(data
+            .values()
+            .find(|x| x.auth_type() == auth_type && x.external_id() == external_id)
+            .cloned())
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        precondition: Precondition,
+    ) -> Result<User, UpdateUserError> {
         let mut data = self.write();
         if let Some(entity) = data.get_mut(&id) {
+            if !precondition.holds(entity.updated_at()) {
+                return Err(UpdateUserError::PreconditionFailed { id });
+            }
+
             entity.try_update(params)?;
 
             Ok(entity.to_owned())
@@ -61,13 +114,35 @@ impl UsersDao for UsersHashMapDao {
         }
     }
 
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteUserError> {
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<(), DeleteUserError> {
         let mut data = self.write();
+        let entity = data.get(&id).ok_or(DeleteUserError::NoSuchEntity { id })?;
+
+        if !precondition.holds(entity.updated_at()) {
+            return Err(DeleteUserError::PreconditionFailed { id });
+        }
+
         data.remove(&id)
             .ok_or(DeleteUserError::NoSuchEntity { id })
             .and(Ok(()))
     }
 
+    async fn restore(
+        &self,
+        entities: Vec<User>,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreUsersError> {
+        let mut data = self.write();
+
+        if mode == RestoreMode::Replace {
+            data.clear();
+        }
+
+        data.extend(entities.into_iter().map(|entity| (entity.id(), entity)));
+
+        Ok(())
+    }
+
     async fn health(&self) -> Result<(), UsersHealthError> {
         Ok(())
     }
@@ -78,6 +153,7 @@ mod tests {
     use fake::{Fake, Faker};
 
     use super::*;
+    use crate::dao::common::{Constraint, Violation};
 
     #[tokio::test]
     async fn create() {
@@ -93,7 +169,16 @@ mod tests {
             ))
             .await;
 
-        assert_eq!(err, Err(CreateUserError::InvalidParams));
+        assert_eq!(
+            err,
+            Err(CreateUserError::InvalidParams {
+                violations: vec![Violation {
+                    field: "name",
+                    constraint: Constraint::NotEmpty,
+                    message: "Empty name is not allowed".to_owned(),
+                }]
+            })
+        );
 
         let entity = dao.create(params.clone()).await.unwrap();
         println!("{entity:#?}");
@@ -124,6 +209,29 @@ mod tests {
         assert_eq!(err, Err(GetUserError::NoSuchEntity { id }));
     }
 
+    #[tokio::test]
+    async fn find_by_external_id() {
+        let dao = UsersHashMapDao::new();
+        let params: CreateUserParams = Faker.fake();
+        println!("{params:#?}");
+        let entity = dao.create(params.clone()).await.unwrap();
+        println!("{entity:#?}");
+
+        let result = dao
+            .find_by_external_id(params.auth_type(), params.external_id())
+            .await
+            .unwrap();
+
+        assert_eq!(result, Some(entity));
+
+        let result = dao
+            .find_by_external_id(params.auth_type(), "unknown")
+            .await
+            .unwrap();
+
+        assert_eq!(result, None);
+    }
+
     #[tokio::test]
     async fn delete() {
         let dao = UsersHashMapDao::new();
@@ -131,11 +239,11 @@ mod tests {
         println!("{params:#?}");
         let entity = dao.create(params.clone()).await.unwrap();
         println!("{entity:#?}");
-        dao.delete(entity.id()).await.unwrap();
+        dao.delete(entity.id(), Precondition::None).await.unwrap();
 
         let id = Faker.fake();
 
-        let err = dao.delete(id).await;
+        let err = dao.delete(id, Precondition::None).await;
         println!("{err:#?}");
 
         assert_eq!(err, Err(DeleteUserError::NoSuchEntity { id }));
@@ -152,7 +260,7 @@ mod tests {
         println!("{update_params:#?}");
 
         let update_result = dao
-            .update(entity.id(), update_params.clone())
+            .update(entity.id(), update_params.clone(), Precondition::None)
             .await
             .unwrap();
         println!("{update_result:#?}");
@@ -168,15 +276,114 @@ mod tests {
 
         let id = Faker.fake();
         println!("{id:#?}");
-        let err = dao.update(id, update_params).await;
+        let err = dao.update(id, update_params, Precondition::None).await;
         println!("{err:#?}");
 
         assert_eq!(err, Err(UpdateUserError::NoSuchEntity { id }));
 
         let err = dao
-            .update(entity.id(), UpdateUserParams::new(String::new()))
+            .update(
+                entity.id(),
+                UpdateUserParams::new(String::new()),
+                Precondition::None,
+            )
             .await;
 
-        assert_eq!(err, Err(UpdateUserError::InvalidParams));
+        assert_eq!(
+            err,
+            Err(UpdateUserError::InvalidParams {
+                violations: vec![Violation {
+                    field: "name",
+                    constraint: Constraint::NotEmpty,
+                    message: "Empty name is not allowed".to_owned(),
+                }]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn update_and_delete_precondition() {
+        let dao = UsersHashMapDao::new();
+        let entity = dao.create(Faker.fake()).await.unwrap();
+        println!("{entity:#?}");
+        let stale = Precondition::UpdatedAt(entity.updated_at());
+
+        let updated = dao
+            .update(entity.id(), Faker.fake(), stale.clone())
+            .await
+            .unwrap();
+        println!("{updated:#?}");
+
+        let result = dao.update(entity.id(), Faker.fake(), stale.clone()).await;
+        assert_eq!(
+            result,
+            Err(UpdateUserError::PreconditionFailed { id: entity.id() })
+        );
+
+        let result = dao.delete(entity.id(), stale).await;
+        assert_eq!(
+            result,
+            Err(DeleteUserError::PreconditionFailed { id: entity.id() })
+        );
+        assert_eq!(dao.get(entity.id()).await, Ok(updated.clone()));
+
+        dao.delete(entity.id(), Precondition::UpdatedAt(updated.updated_at()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn list() {
+        let dao = UsersHashMapDao::new();
+        let pagination: Pagination = Faker.fake();
+        println!("{pagination:#?}");
+
+        let result = dao.list(pagination.clone()).await.unwrap();
+        assert!(result.is_empty());
+
+        let count = 2 * pagination.page() * pagination.limit();
+        let mut vec = Vec::new();
+
+        for i in 0..count {
+            let entity = dao.create(Faker.fake()).await.unwrap();
+            println!("{entity:#?}");
+            if i >= (pagination.page() - 1) * pagination.limit()
+                && i < pagination.page() * pagination.limit()
+            {
+                vec.push(entity);
+            }
+        }
+
+        let result = dao.list(pagination).await.unwrap();
+        assert_eq!(result, vec);
+    }
+
+    #[tokio::test]
+    async fn restore() {
+        let dao = UsersHashMapDao::new();
+        let existing = dao.create(Faker.fake()).await.unwrap();
+        println!("{existing:#?}");
+        let restored: User = Faker.fake();
+        println!("{restored:#?}");
+
+        dao.restore(vec![restored.clone()], RestoreMode::Merge)
+            .await
+            .unwrap();
+
+        assert_eq!(dao.get(existing.id()).await.unwrap(), existing);
+        assert_eq!(dao.get(restored.id()).await.unwrap(), restored);
+
+        let replacement: User = Faker.fake();
+        println!("{replacement:#?}");
+
+        dao.restore(vec![replacement.clone()], RestoreMode::Replace)
+            .await
+            .unwrap();
+
+        let err = dao.get(existing.id()).await;
+        assert_eq!(err, Err(GetUserError::NoSuchEntity { id: existing.id() }));
+        let err = dao.get(restored.id()).await;
+        assert_eq!(err, Err(GetUserError::NoSuchEntity { id: restored.id() }));
+        assert_eq!(dao.get(replacement.id()).await.unwrap(), replacement);
     }
 }
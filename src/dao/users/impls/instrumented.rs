@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use uuid::Uuid;
+
+use super::{
+    dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType},
+    errors::{
+        CreateUserError,
+        DeleteUserError,
+        GetUserError,
+        ListUsersError,
+        RestoreUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
+    interface::UsersDao,
+};
+use crate::dao::common::{CacheStats, DaoMetrics, Pagination, Precondition, RestoreMode};
+
+const DAO_LABEL: &str = "users";
+
+pub struct InstrumentedUsersDao<D> {
+    inner: D,
+    metrics: Arc<DaoMetrics>,
+}
+
+impl<D> InstrumentedUsersDao<D>
+where
+    D: UsersDao + Send + Sync,
+{
+    pub fn new(inner: D, metrics: Arc<DaoMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl<D> UsersDao for InstrumentedUsersDao<D>
+where
+    D: UsersDao + Send + Sync,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<User>, ListUsersError> {
+        self.metrics
+            .measure(DAO_LABEL, "list", self.inner.list(pagination))
+            .await
+    }
+
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+        self.metrics
+            .measure(DAO_LABEL, "snapshot", self.inner.snapshot())
+            .await
+    }
+
+    async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError> {
+        self.metrics
+            .measure(DAO_LABEL, "create", self.inner.create(params))
+            .await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<User, GetUserError> {
+        self.metrics
+            .measure(DAO_LABEL, "get", self.inner.get(id))
+            .await
+    }
+
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError> {
+        self.metrics
+            .measure(
+                DAO_LABEL,
+                "find_by_external_id",
+                self.inner.find_by_external_id(auth_type, external_id),
+            )
+            .await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        precondition: Precondition,
+    ) -> Result<User, UpdateUserError> {
+        self.metrics
+            .measure(
+                DAO_LABEL,
+                "update",
+                self.inner.update(id, params, precondition),
+            )
+            .await
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<(), DeleteUserError> {
+        self.metrics
+            .measure(DAO_LABEL, "delete", self.inner.delete(id, precondition))
+            .await
+    }
+
+    async fn restore(
+        &self,
+        entities: Vec<User>,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreUsersError> {
+        self.metrics
+            .measure(DAO_LABEL, "restore", self.inner.restore(entities, mode))
+            .await
+    }
+
+    async fn health(&self) -> Result<(), UsersHealthError> {
+        self.metrics
+            .measure(DAO_LABEL, "health", self.inner.health())
+            .await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+    use crate::dao::{common::Outcome, users::UsersHashMapDao};
+
+    #[tokio::test]
+    async fn calls_recorded() {
+        let metrics = Arc::new(DaoMetrics::new());
+        let dao = InstrumentedUsersDao::new(UsersHashMapDao::new(), metrics.clone());
+
+        let id = Faker.fake();
+        let err = dao.delete(id, Precondition::None).await;
+        println!("{err:#?}");
+
+        assert_eq!(err, Err(DeleteUserError::NoSuchEntity { id }));
+        assert_eq!(
+            metrics
+                .get(
+                    DAO_LABEL,
+                    "delete",
+                    Outcome::Err("DeleteUserError::NoSuchEntity")
+                )
+                .calls,
+            1
+        );
+        assert_eq!(metrics.get(DAO_LABEL, "delete", Outcome::Ok).calls, 0);
+    }
+}
@@ -3,14 +3,39 @@ use uuid::Uuid;
 
 use super::{
     dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType},
-    errors::{CreateUserError, DeleteUserError, GetUserError, UpdateUserError, UsersHealthError},
+    errors::{
+        CreateUserError,
+        DeleteUserError,
+        GetUserError,
+        ListUsersError,
+        RestoreUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
     interface::UsersDao,
 };
+use crate::dao::common::{Pagination, Precondition, RestoreMode};
 
 pub struct UsersMockedDao {}
 
 #[async_trait]
 impl UsersDao for UsersMockedDao {
+    async fn list(&self, _: Pagination) -> Result<Vec<User>, ListUsersError> {
+        self.snapshot().await
+    }
+
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+        let entity = CreateUserParams::new(
+            "Sleeping Bag".to_owned(),
+            UserAuthType::Github,
+            "awesome-github-id".to_owned(),
+        )
+        .try_into()
+        .or(Err(ListUsersError::UnexpectedError))?;
+
+        Ok(vec![entity])
+    }
+
     async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError> {
         if params.external_id().eq("AlreadyExistingID") {
             return Err(CreateUserError::AlreadyExists { id: Uuid::new_v4() });
@@ -33,7 +58,23 @@ impl UsersDao for UsersMockedDao {
         .or(Err(GetUserError::UnexpectedError))
     }
 
-    async fn update(&self, id: Uuid, params: UpdateUserParams) -> Result<User, UpdateUserError> {
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError> {
+        CreateUserParams::new("Sleeping Bag".to_owned(), auth_type, external_id.to_owned())
+            .try_into()
+            .map(Some)
+            .or(Err(ListUsersError::UnexpectedError))
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        _: Precondition,
+    ) -> Result<User, UpdateUserError> {
         if id.is_nil() {
             return Err(UpdateUserError::NoSuchEntity { id });
         }
@@ -51,7 +92,7 @@ impl UsersDao for UsersMockedDao {
         Ok(user)
     }
 
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteUserError> {
+    async fn delete(&self, id: Uuid, _: Precondition) -> Result<(), DeleteUserError> {
         if id.is_nil() {
             return Err(DeleteUserError::NoSuchEntity { id });
         }
@@ -59,6 +100,10 @@ impl UsersDao for UsersMockedDao {
         Ok(())
     }
 
+    async fn restore(&self, _: Vec<User>, _: RestoreMode) -> Result<(), RestoreUsersError> {
+        Ok(())
+    }
+
     async fn health(&self) -> Result<(), UsersHealthError> {
         Ok(())
     }
@@ -1,7 +1,11 @@
+pub use cached::CachedUsersDao;
 pub use hash_map::UsersHashMapDao;
+pub use instrumented::InstrumentedUsersDao;
 pub use mocked::UsersMockedDao;
 
 use super::{dtos, errors, interface};
 
+mod cached;
 mod hash_map;
+mod instrumented;
 mod mocked;
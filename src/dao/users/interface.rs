@@ -1,16 +1,111 @@
+use std::sync::Arc;
+
 use axum::async_trait;
 use uuid::Uuid;
 
 use super::{
-    dtos::{CreateUserParams, UpdateUserParams, User},
-    errors::{CreateUserError, DeleteUserError, GetUserError, UpdateUserError, UsersHealthError},
+    dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType},
+    errors::{
+        CreateUserError,
+        DeleteUserError,
+        GetUserError,
+        ListUsersError,
+        RestoreUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
 };
+use crate::dao::common::{CacheStats, Pagination, Precondition, RestoreMode};
 
 #[async_trait]
 pub trait UsersDao {
+    async fn list(&self, pagination: Pagination) -> Result<Vec<User>, ListUsersError>;
+    /// All entities as of a single moment, ordered like in [`Self::list`]
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError>;
     async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError>;
     async fn get(&self, id: Uuid) -> Result<User, GetUserError>;
-    async fn update(&self, id: Uuid, params: UpdateUserParams) -> Result<User, UpdateUserError>;
-    async fn delete(&self, id: Uuid) -> Result<(), DeleteUserError>;
+    /// User, who is authenticated by the provider under the id, if registered
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        precondition: Precondition,
+    ) -> Result<User, UpdateUserError>;
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<(), DeleteUserError>;
+    async fn restore(
+        &self,
+        entities: Vec<User>,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreUsersError>;
     async fn health(&self) -> Result<(), UsersHealthError>;
+    /// Statistics of the cache in front of the DAO, if any
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+#[async_trait]
+impl<T> UsersDao for Arc<T>
+where
+    T: UsersDao + Send + Sync + ?Sized,
+{
+    async fn list(&self, pagination: Pagination) -> Result<Vec<User>, ListUsersError> {
+        self.as_ref().list(pagination).await
+    }
+
+    async fn snapshot(&self) -> Result<Vec<User>, ListUsersError> {
+        self.as_ref().snapshot().await
+    }
+
+    async fn create(&self, params: CreateUserParams) -> Result<User, CreateUserError> {
+        self.as_ref().create(params).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<User, GetUserError> {
+        self.as_ref().get(id).await
+    }
+
+    async fn find_by_external_id(
+        &self,
+        auth_type: UserAuthType,
+        external_id: &str,
+    ) -> Result<Option<User>, ListUsersError> {
+        self.as_ref()
+            .find_by_external_id(auth_type, external_id)
+            .await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        params: UpdateUserParams,
+        precondition: Precondition,
+    ) -> Result<User, UpdateUserError> {
+        self.as_ref().update(id, params, precondition).await
+    }
+
+    async fn delete(&self, id: Uuid, precondition: Precondition) -> Result<(), DeleteUserError> {
+        self.as_ref().delete(id, precondition).await
+    }
+
+    async fn restore(
+        &self,
+        entities: Vec<User>,
+        mode: RestoreMode,
+    ) -> Result<(), RestoreUsersError> {
+        self.as_ref().restore(entities, mode).await
+    }
+
+    async fn health(&self) -> Result<(), UsersHealthError> {
+        self.as_ref().health().await
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.as_ref().cache_stats()
+    }
 }
@@ -1,12 +1,14 @@
-pub use dtos::{CreateUserParams, UpdateUserParams, User, UserAuthType};
+pub use dtos::{CreateUserParams, CreateUserValidationError, UpdateUserParams, User, UserAuthType};
 pub use errors::{
     CreateUserError,
     DeleteUserError,
     GetUserError,
+    ListUsersError,
+    RestoreUsersError,
     UpdateUserError,
     UsersHealthError,
 };
-pub use impls::{UsersHashMapDao, UsersMockedDao};
+pub use impls::{CachedUsersDao, InstrumentedUsersDao, UsersHashMapDao, UsersMockedDao};
 pub use interface::UsersDao;
 
 mod dtos;
@@ -0,0 +1,61 @@
+use std::time::SystemTime;
+
+use chrono::NaiveDateTime;
+use prost_types::Timestamp;
+use tonic::Status;
+
+use super::proto;
+use crate::{
+    dao::UserAuthType,
+    http::{HttpItem, HttpUser},
+};
+
+fn timestamp(value: NaiveDateTime) -> Timestamp {
+    SystemTime::from(value.and_utc()).into()
+}
+
+impl From<HttpItem> for proto::Item {
+    fn from(value: HttpItem) -> Self {
+        proto::Item {
+            id: value.id().to_string(),
+            name: value.name().to_owned(),
+            location: value.location().to_owned(),
+            created_at: Some(timestamp(value.created_at())),
+            updated_at: Some(timestamp(value.updated_at())),
+        }
+    }
+}
+
+impl From<UserAuthType> for proto::UserAuthType {
+    fn from(value: UserAuthType) -> Self {
+        match value {
+            UserAuthType::Github => Self::Github,
+        }
+    }
+}
+
+impl TryFrom<proto::UserAuthType> for UserAuthType {
+    type Error = Status;
+
+    fn try_from(value: proto::UserAuthType) -> Result<Self, Self::Error> {
+        match value {
+            proto::UserAuthType::Github => Ok(Self::Github),
+            proto::UserAuthType::Unspecified => {
+                Err(Status::invalid_argument("User auth type must be specified"))
+            }
+        }
+    }
+}
+
+impl From<HttpUser> for proto::User {
+    fn from(value: HttpUser) -> Self {
+        proto::User {
+            id: value.id().to_string(),
+            name: value.name().to_owned(),
+            auth_type: proto::UserAuthType::from(UserAuthType::from(value.auth_type())).into(),
+            external_id: value.external_id().to_owned(),
+            created_at: Some(timestamp(value.created_at())),
+            updated_at: Some(timestamp(value.updated_at())),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::Write;
+
+use axum::http::StatusCode;
+use tonic::{metadata::MetadataValue, Code, Status};
+use uuid::Uuid;
+
+use crate::http::AppError;
+
+/// Metadata key of the same code, which is reported in problem documents
+pub const ERROR_CODE_METADATA: &str = "error-code";
+
+fn code(status_code: StatusCode) -> Code {
+    match status_code {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::CONFLICT => Code::AlreadyExists,
+        StatusCode::PRECONDITION_FAILED => Code::FailedPrecondition,
+        StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        x if x.is_server_error() => Code::Internal,
+        _ => Code::Unknown,
+    }
+}
+
+/// Status with the same code and details, as REST response would have. Violations are
+/// appended to message
+pub fn status(error: impl Into<AppError>) -> Status {
+    let error: AppError = error.into();
+
+    let mut message = error.details;
+    for (index, violation) in error.violations.iter().enumerate() {
+        let separator = if index == 0 { ": " } else { "; " };
+        let _ = write!(
+            message,
+            "{separator}{} {}",
+            violation.field, violation.message
+        );
+    }
+
+    let mut status = Status::new(code(error.status_code), message);
+    status
+        .metadata_mut()
+        .insert(ERROR_CODE_METADATA, MetadataValue::from_static(error.code));
+
+    status
+}
+
+pub fn parse_id(id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(id).map_err(|_| Status::invalid_argument(format!("'{id}' is not a valid id")))
+}
+
+/// Protobuf has no `usize`, values beyond it are rejected rather than truncated
+pub fn to_usize(value: u32) -> Result<usize, Status> {
+    usize::try_from(value)
+        .map_err(|_| Status::invalid_argument(format!("{value} is out of range of the platform")))
+}
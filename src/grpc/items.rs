@@ -0,0 +1,200 @@
+use tonic::{Request, Response, Status};
+
+use super::{
+    errors::{parse_id, status, to_usize},
+    proto::{
+        item_service_server::ItemService,
+        CreateItemRequest,
+        DeleteItemRequest,
+        GetItemRequest,
+        Item,
+        ListItemsRequest,
+        ListItemsResponse,
+        UpdateItemRequest,
+    },
+};
+use crate::{
+    dao::{CreateItemsParamsBuilder, Pagination, Precondition, UpdateItemParamsBuilder},
+    http::{AppState, HttpItem, HttpPaginationParams},
+};
+
+/// Item RPCs, which do the same as REST handlers including published events. Writes are
+/// unconditional, as requests without `If-Match` are
+pub struct ItemsGrpc {
+    state: AppState,
+}
+
+impl ItemsGrpc {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ItemService for ItemsGrpc {
+    async fn list_items(
+        &self,
+        request: Request<ListItemsRequest>,
+    ) -> Result<Response<ListItemsResponse>, Status> {
+        let request = request.into_inner();
+        // Validated by `PaginationBuilder`, same as query parameters of REST listing
+        let pagination: Pagination = HttpPaginationParams {
+            page: request.page.map(to_usize).transpose()?,
+            limit: request.limit.map(to_usize).transpose()?,
+        }
+        .try_into()
+        .map_err(status)?;
+
+        let items = self
+            .state
+            .items
+            .list(pagination)
+            .await
+            .map_err(status)?
+            .into_iter()
+            .map(|x| HttpItem::from(x).into())
+            .collect();
+
+        Ok(Response::new(ListItemsResponse { items }))
+    }
+
+    async fn get_item(&self, request: Request<GetItemRequest>) -> Result<Response<Item>, Status> {
+        let id = parse_id(&request.into_inner().id)?;
+        let entity = self.state.items.get(id).await.map_err(status)?;
+
+        Ok(Response::new(HttpItem::from(entity).into()))
+    }
+
+    async fn create_item(
+        &self,
+        request: Request<CreateItemRequest>,
+    ) -> Result<Response<Item>, Status> {
+        let request = request.into_inner();
+        let params = CreateItemsParamsBuilder::new()
+            .name(request.name)
+            .location(request.location)
+            .build()
+            .map_err(status)?;
+        let entity = self.state.items.create(params).await.map_err(status)?;
+        self.state.item_events.created(&entity);
+
+        Ok(Response::new(HttpItem::from(entity).into()))
+    }
+
+    async fn update_item(
+        &self,
+        request: Request<UpdateItemRequest>,
+    ) -> Result<Response<Item>, Status> {
+        let request = request.into_inner();
+        let id = parse_id(&request.id)?;
+        let params = UpdateItemParamsBuilder::new()
+            .name(request.name)
+            .location(request.location)
+            .build()
+            .map_err(status)?;
+        let entity = self
+            .state
+            .items
+            .update(id, params, Precondition::None)
+            .await
+            .map_err(status)?;
+        self.state.item_events.updated(&entity);
+
+        Ok(Response::new(HttpItem::from(entity).into()))
+    }
+
+    async fn delete_item(
+        &self,
+        request: Request<DeleteItemRequest>,
+    ) -> Result<Response<()>, Status> {
+        let id = parse_id(&request.into_inner().id)?;
+        let entity = self
+            .state
+            .items
+            .delete(id, Precondition::None)
+            .await
+            .map_err(status)?;
+        self.state.item_events.deleted(&entity);
+
+        Ok(Response::new(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Code;
+
+    use super::*;
+    use crate::grpc::errors::ERROR_CODE_METADATA;
+
+    #[tokio::test]
+    async fn list() {
+        let service = ItemsGrpc::new(AppState::default());
+
+        let response = service
+            .list_items(Request::new(ListItemsRequest {
+                page: Some(1),
+                limit: Some(10),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.items[0].name, "Sleeping Bag");
+    }
+
+    #[tokio::test]
+    async fn list_invalid_pagination() {
+        let service = ItemsGrpc::new(AppState::default());
+
+        let status = service
+            .list_items(Request::new(ListItemsRequest {
+                page: Some(0),
+                limit: None,
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(
+            status.metadata().get(ERROR_CODE_METADATA).unwrap(),
+            "PaginationBuilderError::PageIsZero"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_invalid() {
+        let service = ItemsGrpc::new(AppState::default());
+
+        let status = service
+            .create_item(Request::new(CreateItemRequest {
+                name: String::new(),
+                location: "Calgary, AB".to_owned(),
+            }))
+            .await
+            .unwrap_err();
+        println!("{status:#?}");
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(
+            status.metadata().get(ERROR_CODE_METADATA).unwrap(),
+            "CreateItemError::InvalidParams"
+        );
+        assert!(status.message().contains("name"));
+    }
+
+    #[tokio::test]
+    async fn get_invalid_id() {
+        let service = ItemsGrpc::new(AppState::default());
+
+        let status = service
+            .get_item(Request::new(GetItemRequest {
+                id: "not-an-id".to_owned(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+}
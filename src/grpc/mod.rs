@@ -0,0 +1,20 @@
+// `Status` is the error type of every RPC, there is no point in boxing it
+#![allow(clippy::result_large_err)]
+
+pub use errors::status;
+pub use items::ItemsGrpc;
+pub use proto::{item_service_server::ItemServiceServer, user_service_server::UserServiceServer};
+pub use users::UsersGrpc;
+
+/// Prefix of content types of every gRPC request, `application/grpc+proto` included
+pub const GRPC_CONTENT_TYPE: &str = "application/grpc";
+
+mod dtos;
+mod errors;
+mod items;
+mod users;
+
+#[allow(clippy::pedantic)]
+mod proto {
+    tonic::include_proto!("sleeping_bag_locator.v1");
+}
@@ -0,0 +1,155 @@
+use tonic::{Request, Response, Status};
+
+use super::{
+    errors::{parse_id, status},
+    proto::{
+        user_service_server::UserService,
+        CreateUserRequest,
+        DeleteUserRequest,
+        GetUserRequest,
+        UpdateUserRequest,
+        User,
+    },
+};
+use crate::{
+    dao::{CreateUserParams, Precondition, UpdateUserParams},
+    http::{notify_user_webhooks, AppState, HttpUser, UserChange},
+};
+
+/// User RPCs, which do the same as REST handlers including webhooks
+pub struct UsersGrpc {
+    state: AppState,
+}
+
+impl UsersGrpc {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl UserService for UsersGrpc {
+    async fn get_user(&self, request: Request<GetUserRequest>) -> Result<Response<User>, Status> {
+        let id = parse_id(&request.into_inner().id)?;
+        let entity = self.state.users.get(id).await.map_err(status)?;
+
+        Ok(Response::new(HttpUser::from(entity).into()))
+    }
+
+    async fn create_user(
+        &self,
+        request: Request<CreateUserRequest>,
+    ) -> Result<Response<User>, Status> {
+        let request = request.into_inner();
+        let auth_type = request.auth_type().try_into()?;
+        let params = CreateUserParams::new(request.name, auth_type, request.external_id);
+        let result: HttpUser = self
+            .state
+            .users
+            .create(params)
+            .await
+            .map_err(status)?
+            .into();
+        notify_user_webhooks(&self.state.webhooks, UserChange::Created(&result));
+
+        Ok(Response::new(result.into()))
+    }
+
+    async fn update_user(
+        &self,
+        request: Request<UpdateUserRequest>,
+    ) -> Result<Response<User>, Status> {
+        let request = request.into_inner();
+        let id = parse_id(&request.id)?;
+        let result: HttpUser = self
+            .state
+            .users
+            .update(id, UpdateUserParams::new(request.name), Precondition::None)
+            .await
+            .map_err(status)?
+            .into();
+        notify_user_webhooks(&self.state.webhooks, UserChange::Updated(&result));
+
+        Ok(Response::new(result.into()))
+    }
+
+    async fn delete_user(
+        &self,
+        request: Request<DeleteUserRequest>,
+    ) -> Result<Response<()>, Status> {
+        let id = parse_id(&request.into_inner().id)?;
+        self.state
+            .users
+            .delete(id, Precondition::None)
+            .await
+            .map_err(status)?;
+        notify_user_webhooks(&self.state.webhooks, UserChange::Deleted(id));
+
+        Ok(Response::new(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Code;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::grpc::proto::UserAuthType;
+
+    #[tokio::test]
+    async fn create_and_get() {
+        let service = UsersGrpc::new(AppState::default());
+
+        let created = service
+            .create_user(Request::new(CreateUserRequest {
+                name: "John".to_owned(),
+                auth_type: UserAuthType::Github.into(),
+                external_id: "john".to_owned(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let fetched = service
+            .get_user(Request::new(GetUserRequest {
+                id: created.id.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(fetched, created);
+        assert_eq!(fetched.auth_type(), UserAuthType::Github);
+        assert_eq!(fetched.created_at, fetched.updated_at);
+    }
+
+    #[tokio::test]
+    async fn create_without_auth_type() {
+        let service = UsersGrpc::new(AppState::default());
+
+        let status = service
+            .create_user(Request::new(CreateUserRequest {
+                name: "John".to_owned(),
+                auth_type: UserAuthType::Unspecified.into(),
+                external_id: "john".to_owned(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn get_missing() {
+        let service = UsersGrpc::new(AppState::default());
+
+        let status = service
+            .get_user(Request::new(GetUserRequest {
+                id: Uuid::new_v4().to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::NotFound);
+    }
+}
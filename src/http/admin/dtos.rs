@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{backup::RestoreReport, dao::RestoreMode};
+
+#[derive(Deserialize, Clone, Copy, Default, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(as = RestoreMode)]
+pub enum HttpRestoreMode {
+    #[default]
+    Merge,
+    Replace,
+}
+
+impl From<HttpRestoreMode> for RestoreMode {
+    fn from(value: HttpRestoreMode) -> Self {
+        match value {
+            HttpRestoreMode::Merge => RestoreMode::Merge,
+            HttpRestoreMode::Replace => RestoreMode::Replace,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct HttpRestoreQuery {
+    #[serde(default)]
+    #[param(inline)]
+    pub mode: HttpRestoreMode,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, PartialEq, Eq))]
+#[schema(as = RestoreReport)]
+pub struct HttpRestoreReport {
+    #[schema(example = 10)]
+    pub items: usize,
+    #[schema(example = 3)]
+    pub trash: usize,
+    #[schema(example = 2)]
+    pub users: usize,
+}
+
+impl From<RestoreReport> for HttpRestoreReport {
+    fn from(value: RestoreReport) -> Self {
+        HttpRestoreReport {
+            items: value.items,
+            trash: value.trash,
+            users: value.users,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+use tracing::error;
+
+use crate::{
+    backup::{ExportError, RestoreError},
+    dao::ErrorVariant,
+    http::common::AppError,
+};
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum AdminAuthError {
+    #[error("Admin endpoints are disabled, admin token is not configured")]
+    Disabled,
+    #[error("Bearer token is missing in Authorization header")]
+    MissingToken,
+    #[error("Bearer token diverges from admin token")]
+    InvalidToken,
+}
+
+impl ErrorVariant for AdminAuthError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Disabled => "AdminAuthError::Disabled",
+            Self::MissingToken => "AdminAuthError::MissingToken",
+            Self::InvalidToken => "AdminAuthError::InvalidToken",
+        }
+    }
+}
+
+impl From<AdminAuthError> for AppError {
+    fn from(value: AdminAuthError) -> Self {
+        let status_code = match value {
+            AdminAuthError::Disabled => StatusCode::NOT_FOUND,
+            AdminAuthError::MissingToken | AdminAuthError::InvalidToken => StatusCode::UNAUTHORIZED,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for AdminAuthError {
+    fn into_response(self) -> Response {
+        AppError::from(self).into_response()
+    }
+}
+
+impl From<ExportError> for AppError {
+    fn from(value: ExportError) -> Self {
+        error!("{:#?}", value.to_string());
+        let status_code = match value {
+            ExportError::ItemsListing { internal: _ }
+            | ExportError::UsersListing { internal: _ }
+            | ExportError::Serialization { internal: _ } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<RestoreError> for AppError {
+    fn from(value: RestoreError) -> Self {
+        let status_code = match value {
+            RestoreError::MissingManifest
+            | RestoreError::InvalidManifest { internal: _ }
+            | RestoreError::UnknownFormat { format: _ }
+            | RestoreError::UnsupportedVersion { version: _ }
+            | RestoreError::InvalidRecord {
+                line: _,
+                internal: _,
+            }
+            | RestoreError::UnknownSection { section: _ }
+            | RestoreError::RecordsCountMismatch {
+                section: _,
+                expected: _,
+                actual: _,
+            }
+            | RestoreError::ChecksumMismatch { section: _ } => StatusCode::UNPROCESSABLE_ENTITY,
+            RestoreError::ItemsStorage { internal: _ }
+            | RestoreError::UsersStorage { internal: _ }
+            | RestoreError::ItemsListing { internal: _ }
+            | RestoreError::Rollback { internal: _ } => {
+                error!("{:#?}", value.to_string());
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use axum::{
+    debug_handler,
+    extract::State,
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use utoipa::OpenApi;
+
+use super::{
+    common::{AppError, HttpProblem, Json, Query, PROBLEM_CONTENT_TYPE},
+    dtos::{HttpRestoreQuery, HttpRestoreReport},
+    state::AppState,
+    AdminToken,
+};
+use crate::{backup, http::idempotency::IdempotencyKey};
+
+const ARCHIVE_CONTENT_TYPE: &str = "application/x-ndjson";
+
+#[derive(Default)]
+pub struct AdminRouter {}
+
+#[derive(OpenApi)]
+#[openapi(paths(export_backup, restore_backup), modifiers(&AdminToken))]
+pub struct AdminApi;
+
+#[utoipa::path(
+    get,
+    path = "/backup",
+    responses(
+        (
+            status = 200,
+            description = "JSON lines, manifest with per-section checksums followed by records",
+            body = String,
+            content_type = ARCHIVE_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("AdminToken" = [])),
+)]
+#[debug_handler]
+pub async fn export_backup(
+    _: AdminToken,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let archive = backup::export(&state.items, &state.users).await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, ARCHIVE_CONTENT_TYPE),
+            (CONTENT_DISPOSITION, "attachment; filename=\"backup.jsonl\""),
+        ],
+        archive,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/restore",
+    params(IdempotencyKey, HttpRestoreQuery),
+    request_body(
+        content = String,
+        content_type = ARCHIVE_CONTENT_TYPE,
+        description = "JSON lines, manifest with per-section checksums followed by records",
+    ),
+    responses(
+        (status = 200, description = "OK", body = HttpRestoreReport),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("AdminToken" = [])),
+)]
+#[debug_handler]
+pub async fn restore_backup(
+    _: AdminToken,
+    State(state): State<AppState>,
+    Query(query): Query<HttpRestoreQuery>,
+    archive: String,
+) -> Result<impl IntoResponse, AppError> {
+    let result: HttpRestoreReport =
+        backup::restore(&state.items, &state.users, &archive, query.mode.into())
+            .await?
+            .into();
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
+impl From<AdminRouter> for Router<AppState> {
+    fn from(_: AdminRouter) -> Self {
+        Router::new()
+            .route("/backup", get(export_backup))
+            .route("/restore", post(restore_backup))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use fake::{Fake, Faker};
+    use http_body_util::BodyExt;
+    use reqwest::{header::AUTHORIZATION, Method};
+    use rstest::rstest;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::dao::UsersDao;
+
+    const TOKEN: &str = "secret";
+
+    fn state() -> AppState {
+        AppState {
+            admin_token: Some(TOKEN.to_owned()),
+            ..AppState::default()
+        }
+    }
+
+    async fn export(state: AppState) -> String {
+        let router: Router<AppState> = AdminRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/backup")
+                    .header(AUTHORIZATION, format!("Bearer {TOKEN}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+
+        String::from_utf8(
+            raw_response
+                .into_body()
+                .collect()
+                .await
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_and_restore_ok() {
+        let source = state();
+        for _ in 0..3 {
+            source.users.create(Faker.fake()).await.unwrap();
+        }
+        let archive = export(source).await;
+        println!("{archive}");
+
+        let router: Router<AppState> = AdminRouter::default().into();
+        let raw_response = router
+            .with_state(state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/restore?mode=replace")
+                    .header(AUTHORIZATION, format!("Bearer {TOKEN}"))
+                    .body(archive)
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+
+        let response = from_slice::<HttpRestoreReport>(
+            &raw_response.into_body().collect().await.unwrap().to_bytes(),
+        )
+        .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(
+            response,
+            HttpRestoreReport {
+                items: 1,
+                trash: 0,
+                users: 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_invalid_archive() {
+        let router: Router<AppState> = AdminRouter::default().into();
+
+        let raw_response = router
+            .with_state(state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/restore")
+                    .header(AUTHORIZATION, format!("Bearer {TOKEN}"))
+                    .body("not a manifest".to_owned())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[rstest]
+    #[case::no_header(None, StatusCode::UNAUTHORIZED)]
+    #[case::wrong_token(Some("Bearer wrong"), StatusCode::UNAUTHORIZED)]
+    #[case::wrong_scheme(Some("Basic secret"), StatusCode::UNAUTHORIZED)]
+    #[tokio::test]
+    async fn export_unauthorized(#[case] header: Option<&str>, #[case] expected: StatusCode) {
+        let router: Router<AppState> = AdminRouter::default().into();
+
+        let mut request = Request::builder().uri("/backup");
+        if let Some(header) = header {
+            request = request.header(AUTHORIZATION, header);
+        }
+
+        let raw_response = router
+            .with_state(state())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), expected);
+    }
+
+    #[tokio::test]
+    async fn export_disabled() {
+        let router: Router<AppState> = AdminRouter::default().into();
+
+        let raw_response = router
+            .with_state(AppState::default())
+            .oneshot(
+                Request::builder()
+                    .uri("/backup")
+                    .header(AUTHORIZATION, format!("Bearer {TOKEN}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,67 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+pub use handlers::{AdminApi, AdminRouter};
+use subtle::ConstantTimeEq;
+use utoipa::{
+    openapi::{
+        security::{Http, HttpAuthScheme, SecurityScheme},
+        OpenApi,
+    },
+    Modify,
+};
+
+use self::errors::AdminAuthError;
+use super::{common, state};
+
+mod dtos;
+mod errors;
+mod handlers;
+
+const BEARER_PREFIX: &str = "Bearer ";
+const SECURITY_SCHEME: &str = "AdminToken";
+
+pub struct AdminToken;
+
+impl Modify for AdminToken {
+    fn modify(&self, openapi: &mut OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                SECURITY_SCHEME,
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<state::AppState> for AdminToken {
+    type Rejection = AdminAuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &state::AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let expected = state
+            .admin_token
+            .as_deref()
+            .ok_or(AdminAuthError::Disabled)?;
+
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.strip_prefix(BEARER_PREFIX))
+            .ok_or(AdminAuthError::MissingToken)?;
+
+        // Comparison time mustn't reveal how long a prefix of the token was guessed
+        if !bool::from(token.as_bytes().ct_eq(expected.as_bytes())) {
+            return Err(AdminAuthError::InvalidToken);
+        }
+
+        Ok(AdminToken)
+    }
+}
@@ -1,8 +1,9 @@
+use axum::response::{IntoResponse, Response};
 use reqwest::StatusCode;
 use thiserror::Error;
 use tracing::error;
 
-use crate::http::common::AppError;
+use crate::{dao::ErrorVariant, http::common::AppError};
 
 #[derive(Error, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -39,6 +40,34 @@ pub enum AuthCallbackError {
     UserInfoStorageEmptyCookie,
 }
 
+impl ErrorVariant for AuthCallbackError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchCookie { .. } => "AuthCallbackError::NoSuchCookie",
+            Self::SessionLoadingFailed { .. } => "AuthCallbackError::SessionLoadingFailed",
+            Self::InappropriateCookieFormat => "AuthCallbackError::InappropriateCookieFormat",
+            Self::EmptySession => "AuthCallbackError::EmptySession",
+            Self::CsrfTokenDeserializationError => {
+                "AuthCallbackError::CsrfTokenDeserializationError"
+            }
+            Self::CsrfTokenSessionDestructionError { .. } => {
+                "AuthCallbackError::CsrfTokenSessionDestructionError"
+            }
+            Self::CsrfTokensMismatch => "AuthCallbackError::CsrfTokensMismatch",
+            Self::CodeExchangeError { .. } => "AuthCallbackError::CodeExchangeError",
+            Self::UserInfoRequestError { .. } => "AuthCallbackError::UserInfoRequestError",
+            Self::UserInfoDeserializeResponseError { .. } => {
+                "AuthCallbackError::UserInfoDeserializeResponseError"
+            }
+            Self::UserInfoSerializationError { .. } => {
+                "AuthCallbackError::UserInfoSerializationError"
+            }
+            Self::UserInfoStorageError { .. } => "AuthCallbackError::UserInfoStorageError",
+            Self::UserInfoStorageEmptyCookie => "AuthCallbackError::UserInfoStorageEmptyCookie",
+        }
+    }
+}
+
 impl From<AuthCallbackError> for AppError {
     fn from(value: AuthCallbackError) -> Self {
         error!("{:#?}", value.to_string());
@@ -60,7 +89,9 @@ impl From<AuthCallbackError> for AppError {
 
         Self {
             status_code,
-            details: String::new(),
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -76,6 +107,16 @@ pub enum LoginError {
     CsrfTokenStorageEmptyCookie,
 }
 
+impl ErrorVariant for LoginError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::CsrfTokenSerialization { .. } => "LoginError::CsrfTokenSerialization",
+            Self::CsrfTokenStorage { .. } => "LoginError::CsrfTokenStorage",
+            Self::CsrfTokenStorageEmptyCookie => "LoginError::CsrfTokenStorageEmptyCookie",
+        }
+    }
+}
+
 impl From<LoginError> for AppError {
     fn from(value: LoginError) -> Self {
         error!("{:#?}", value.to_string());
@@ -87,7 +128,9 @@ impl From<LoginError> for AppError {
 
         Self {
             status_code,
-            details: String::new(),
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -101,6 +144,15 @@ pub enum LogoutError {
     SessionDestructionError { internal: String },
 }
 
+impl ErrorVariant for LogoutError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::SessionLoadError { .. } => "LogoutError::SessionLoadError",
+            Self::SessionDestructionError { .. } => "LogoutError::SessionDestructionError",
+        }
+    }
+}
+
 impl From<LogoutError> for AppError {
     fn from(value: LogoutError) -> Self {
         error!("{:#?}", value.to_string());
@@ -113,7 +165,53 @@ impl From<LogoutError> for AppError {
 
         Self {
             status_code,
-            details: String::new(),
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum SessionUserError {
+    #[error("Session cookie of a logged in user is required")]
+    Unauthenticated,
+    #[error("Logged in account isn't registered as a user")]
+    NotRegistered,
+    #[error("Unexpected error while looking up the user")]
+    UnexpectedError,
+}
+
+impl ErrorVariant for SessionUserError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated => "SessionUserError::Unauthenticated",
+            Self::NotRegistered => "SessionUserError::NotRegistered",
+            Self::UnexpectedError => "SessionUserError::UnexpectedError",
+        }
+    }
+}
+
+impl From<SessionUserError> for AppError {
+    fn from(value: SessionUserError) -> Self {
+        let status_code = match value {
+            SessionUserError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            SessionUserError::NotRegistered => StatusCode::FORBIDDEN,
+            SessionUserError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
+
+impl IntoResponse for SessionUserError {
+    fn into_response(self) -> Response {
+        AppError::from(self).into_response()
+    }
+}
@@ -1,10 +1,9 @@
 use async_session::Session;
 use axum::{
     debug_handler,
-    extract::{Query, State},
+    extract::State,
     http::{header::USER_AGENT, StatusCode},
     response::{IntoResponse, Redirect},
-    Json,
 };
 use axum_extra::extract::{
     cookie::{Cookie, SameSite},
@@ -23,7 +22,7 @@ use super::{
     CSRF_TOKEN,
     USER_INFO,
 };
-use crate::http::common::AppError;
+use crate::http::common::{AppError, Json, Query};
 
 #[debug_handler]
 pub async fn auth_callback(
@@ -128,7 +127,7 @@ pub async fn auth_callback(
     let cookie = Cookie::build((COOKIE_NAME, session_id))
         .same_site(SameSite::Lax)
         .http_only(true)
-        .secure(true)
+        .secure(state.session_cookie_secure)
         .path("/")
         .build();
 
@@ -169,7 +168,7 @@ pub async fn login(
     let cookie = Cookie::build((COOKIE_NAME, session_id))
         .same_site(SameSite::Lax)
         .http_only(true)
-        .secure(true)
+        .secure(state.session_cookie_secure)
         .path("/")
         .build();
 
@@ -1,7 +1,9 @@
+use std::net::SocketAddr;
+
 use async_session::{MemoryStore, SessionStore};
 use axum::{
     async_trait,
-    extract::{FromRef, FromRequestParts},
+    extract::{ConnectInfo, FromRef, FromRequestParts},
     http::request::Parts,
     response::{IntoResponse, Redirect, Response},
     RequestPartsExt,
@@ -9,8 +11,19 @@ use axum::{
 use axum_extra::extract::CookieJar;
 pub use handlers::{auth_callback, login, logout};
 use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, SecurityScheme},
+        OpenApi,
+    },
+    Modify,
+};
+use uuid::Uuid;
 
+pub use self::errors::SessionUserError;
 use super::state;
+use crate::dao::{UserAuthType, UsersDao};
 
 mod dtos;
 mod errors;
@@ -21,6 +34,7 @@ const USER_INFO: &str = "user_info";
 const COOKIE_NAME: &str = "session";
 const AUTH_PATH: &str = "/auth";
 const HOME_PATH: &str = "/";
+const SECURITY_SCHEME: &str = "SessionCookie";
 
 pub struct AuthRedirect;
 
@@ -43,6 +57,136 @@ pub struct UserInfo {
     id: usize,
 }
 
+/// Id of the user, who is logged in with session cookie from the jar, if any.
+/// Storage errors are treated as no session, since callers only use it to tell clients apart
+pub async fn session_user_id(
+    session_store: &(dyn SessionStore + Send + Sync),
+    cookie_jar: &CookieJar,
+) -> Option<usize> {
+    let cookie = cookie_jar.get(COOKIE_NAME)?;
+    let session = session_store
+        .load_session(cookie.value().to_owned())
+        .await
+        .ok()??;
+
+    session.get::<UserInfo>(USER_INFO).map(|x| x.id)
+}
+
+/// Outcome of looking the session up, which is kept in request extensions, so rate limits,
+/// idempotency and extractors load the session once per request
+#[derive(Clone, Copy)]
+struct SessionLookup(Option<usize>);
+
+/// Same as `session_user_id`, but loads the session only for the first caller of a request
+async fn request_user_id(
+    session_store: &(dyn SessionStore + Send + Sync),
+    parts: &mut Parts,
+) -> Option<usize> {
+    if let Some(SessionLookup(id)) = parts.extensions.get() {
+        return *id;
+    }
+
+    let id = session_user_id(session_store, &CookieJar::from_headers(&parts.headers)).await;
+    parts.extensions.insert(SessionLookup(id));
+
+    id
+}
+
+/// Tells clients apart: logged in users by session, anyone else by IP address
+pub async fn client_key(
+    session_store: &(dyn SessionStore + Send + Sync),
+    parts: &mut Parts,
+) -> String {
+    if let Some(id) = request_user_id(session_store, parts).await {
+        return format!("user:{id}");
+    }
+
+    // Address is missing only if server isn't started with connect info, e.g. in tests
+    parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or_else(
+            || "ip:unknown".to_owned(),
+            |ConnectInfo(address)| format!("ip:{}", address.ip()),
+        )
+}
+
+/// Id of the registered user, whose GitHub account is logged in with session cookie. Unlike
+/// `UserInfo` it's meant for API routes, so anonymous requests are rejected with 401 problem
+/// instead of being redirected, and accounts without a user are rejected with 403 problem
+#[derive(Clone, Copy)]
+pub struct SessionUser(pub Uuid);
+
+/// Describes session cookie, which `SessionUser` routes require, in API document
+pub struct SessionCookie;
+
+impl Modify for SessionCookie {
+    fn modify(&self, openapi: &mut OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                SECURITY_SCHEME,
+                SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(COOKIE_NAME))),
+            );
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<state::AppState> for SessionUser {
+    type Rejection = SessionUserError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &state::AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let github_id = request_user_id(state.session_store.as_ref(), parts)
+            .await
+            .ok_or(SessionUserError::Unauthenticated)?;
+
+        state
+            .users
+            .find_by_external_id(UserAuthType::Github, &github_id.to_string())
+            .await
+            .map_err(|x| {
+                error!("Cannot look up user of GitHub account {github_id}: {x}");
+                SessionUserError::UnexpectedError
+            })?
+            .map(|x| SessionUser(x.id()))
+            .ok_or(SessionUserError::NotRegistered)
+    }
+}
+
+/// Registers user of the GitHub account, stores its session and returns id of the user along
+/// with `Cookie` header value, which logs requests in
+#[cfg(test)]
+pub async fn session_cookie(state: &state::AppState, github_id: usize) -> (Uuid, String) {
+    use crate::dao::CreateUserParams;
+
+    let user = state
+        .users
+        .create(CreateUserParams::new(
+            format!("User {github_id}"),
+            UserAuthType::Github,
+            github_id.to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let mut session = async_session::Session::new();
+    session
+        .insert(USER_INFO, UserInfo { id: github_id })
+        .unwrap();
+    let session_id = state
+        .session_store
+        .store_session(session)
+        .await
+        .unwrap()
+        .unwrap();
+
+    (user.id(), format!("{COOKIE_NAME}={session_id}"))
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for UserInfo
 where
@@ -67,3 +211,87 @@ where
         Ok(user)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_session::Session;
+    use axum::http::{header::COOKIE, Request};
+    use axum_extra::extract::cookie::Cookie;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn session_user() {
+        let store = MemoryStore::new();
+        let mut session = Session::new();
+        session.insert(USER_INFO, UserInfo { id: 42 }).unwrap();
+        let session_id = store.store_session(session).await.unwrap().unwrap();
+
+        let cookie_jar = CookieJar::new().add(Cookie::new(COOKIE_NAME, session_id));
+
+        assert_eq!(session_user_id(&store, &cookie_jar).await, Some(42));
+        assert_eq!(session_user_id(&store, &CookieJar::new()).await, None);
+
+        let cookie_jar = CookieJar::new().add(Cookie::new(COOKIE_NAME, "foo"));
+
+        assert_eq!(session_user_id(&store, &cookie_jar).await, None);
+    }
+
+    #[tokio::test]
+    async fn session_user_registered() {
+        let state = state::AppState::default();
+        let mut session = Session::new();
+        session.insert(USER_INFO, UserInfo { id: 7 }).unwrap();
+        let session_id = state
+            .session_store
+            .store_session(session)
+            .await
+            .unwrap()
+            .unwrap();
+        let parts = |cookie: &str| {
+            Request::builder()
+                .header(COOKIE, cookie)
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0
+        };
+
+        let result = SessionUser::from_request_parts(
+            &mut parts(&format!("{COOKIE_NAME}={session_id}")),
+            &state,
+        )
+        .await;
+
+        assert_eq!(result.err(), Some(SessionUserError::NotRegistered));
+
+        let (id, cookie) = session_cookie(&state, 8).await;
+        let SessionUser(result) = SessionUser::from_request_parts(&mut parts(&cookie), &state)
+            .await
+            .ok()
+            .unwrap();
+
+        assert_eq!(result, id);
+    }
+
+    #[tokio::test]
+    async fn session_loaded_once() {
+        let store = MemoryStore::new();
+        let mut session = Session::new();
+        session.insert(USER_INFO, UserInfo { id: 42 }).unwrap();
+        let session_id = store.store_session(session.clone()).await.unwrap().unwrap();
+
+        let (mut parts, ()) = Request::builder()
+            .header(COOKIE, format!("{COOKIE_NAME}={session_id}"))
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        assert_eq!(client_key(&store, &mut parts).await, "user:42");
+
+        // Lookup of the first caller is reused, even though the session is gone by now
+        store.destroy_session(session).await.unwrap();
+
+        assert_eq!(request_user_id(&store, &mut parts).await, Some(42));
+    }
+}
@@ -0,0 +1,296 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{
+        header::{ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+        request::Parts,
+        HeaderValue,
+    },
+    response::{IntoResponseParts, ResponseParts},
+};
+use chrono::{DateTime, NaiveDateTime, SubsecRound};
+use utoipa::IntoParams;
+
+use super::errors::{AppError, IfMatchError};
+use crate::dao::Precondition;
+
+const ETAG_FORMAT: &str = "%Y%m%dT%H%M%S%.9f";
+pub const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Strong entity tag derived from the moment an entity was last updated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ETag(pub NaiveDateTime);
+
+impl ETag {
+    fn parse(value: &str) -> Option<Self> {
+        let opaque = value.strip_prefix('"')?.strip_suffix('"')?;
+
+        NaiveDateTime::parse_from_str(opaque, ETAG_FORMAT)
+            .ok()
+            .map(ETag)
+    }
+}
+
+impl std::fmt::Display for ETag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.0.format(ETAG_FORMAT))
+    }
+}
+
+impl IntoResponseParts for ETag {
+    type Error = AppError;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.headers_mut()
+            .insert(ETAG, HeaderValue::from_str(&self.to_string())?);
+
+        Ok(res)
+    }
+}
+
+/// `Last-Modified` response header, precise up to a second
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LastModified(pub NaiveDateTime);
+
+impl IntoResponseParts for LastModified {
+    type Error = AppError;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.headers_mut().insert(
+            LAST_MODIFIED,
+            HeaderValue::from_str(&self.0.format(HTTP_DATE_FORMAT).to_string())?,
+        );
+
+        Ok(res)
+    }
+}
+
+/// `If-None-Match` and `If-Modified-Since` request headers of a conditional GET
+#[derive(IntoParams)]
+#[into_params(parameter_in = Header)]
+pub struct ConditionalGet {
+    /// Entity tags of versions held by client, `*` matches any version
+    #[param(rename = "If-None-Match")]
+    if_none_match: Option<String>,
+    /// Ignored when If-None-Match is present
+    #[param(rename = "If-Modified-Since", value_type = Option<String>, format = "http-date")]
+    if_modified_since: Option<NaiveDateTime>,
+}
+
+impl ConditionalGet {
+    /// Whether representation last modified at the given instant is already held by client
+    pub fn is_not_modified(&self, modified_at: NaiveDateTime) -> bool {
+        // If-Modified-Since is ignored when If-None-Match is present, as RFC 9110 requires
+        if let Some(if_none_match) = &self.if_none_match {
+            let current = ETag(modified_at).to_string();
+
+            return if_none_match
+                .split(',')
+                .map(str::trim)
+                .any(|x| x == "*" || x.strip_prefix("W/").unwrap_or(x) == current);
+        }
+
+        self.if_modified_since
+            .is_some_and(|since| modified_at.trunc_subsecs(0) <= since)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ConditionalGet
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let header = |name| parts.headers.get(name).and_then(|x| x.to_str().ok());
+
+        // Invalid dates are ignored, which makes the request unconditional
+        Ok(ConditionalGet {
+            if_none_match: header(IF_NONE_MATCH).map(ToOwned::to_owned),
+            if_modified_since: header(IF_MODIFIED_SINCE)
+                .and_then(|x| DateTime::parse_from_rfc2822(x).ok())
+                .map(|x| x.naive_utc()),
+        })
+    }
+}
+
+/// `If-Match` request header, turned into a precondition checked by DAO on write
+#[derive(IntoParams)]
+#[into_params(names("If-Match"), parameter_in = Header)]
+pub struct IfMatch(
+    /// Entity tags of versions, one of which is expected to be current. `*` matches any version
+    #[param(value_type = Option<String>, example = "\"20180320T091228.123456789\"")]
+    pub Precondition,
+);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = IfMatchError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(IF_MATCH) else {
+            return Ok(IfMatch(Precondition::None));
+        };
+
+        IfMatch::parse(value.to_str().map_err(|_| IfMatchError::Malformed)?)
+    }
+}
+
+impl IfMatch {
+    pub fn parse(value: &str) -> Result<Self, IfMatchError> {
+        let value = value.trim();
+        if value == "*" {
+            return Ok(IfMatch(Precondition::None));
+        }
+
+        // Weak and foreign tags never match strongly, so they are skipped
+        let mut updated_at: Vec<NaiveDateTime> = value
+            .split(',')
+            .filter_map(|x| ETag::parse(x.trim()))
+            .map(|ETag(x)| x)
+            .collect();
+
+        match updated_at.len() {
+            0 => Err(IfMatchError::NoMatch),
+            1 => Ok(IfMatch(Precondition::UpdatedAt(updated_at.remove(0)))),
+            _ => Ok(IfMatch(Precondition::UpdatedAtAnyOf(updated_at))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderName, Request};
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    async fn if_match(value: Option<&str>) -> Result<Precondition, IfMatchError> {
+        let mut builder = Request::builder();
+        if let Some(value) = value {
+            builder = builder.header(IF_MATCH, value);
+        }
+        let (mut parts, ()) = builder.body(()).unwrap().into_parts();
+
+        IfMatch::from_request_parts(&mut parts, &())
+            .await
+            .map(|IfMatch(x)| x)
+    }
+
+    async fn conditional_get(headers: &[(HeaderName, &str)]) -> ConditionalGet {
+        let mut builder = Request::builder();
+        for (name, value) in headers {
+            builder = builder.header(name, *value);
+        }
+        let (mut parts, ()) = builder.body(()).unwrap().into_parts();
+
+        let Ok(result) = ConditionalGet::from_request_parts(&mut parts, &()).await;
+        result
+    }
+
+    #[tokio::test]
+    async fn if_none_match() {
+        let modified_at = Utc::now().naive_utc();
+        let etag = ETag(modified_at).to_string();
+
+        assert!(!conditional_get(&[]).await.is_not_modified(modified_at));
+        assert!(conditional_get(&[(IF_NONE_MATCH, &etag)])
+            .await
+            .is_not_modified(modified_at));
+        assert!(
+            conditional_get(&[(IF_NONE_MATCH, &format!("\"foo\", W/{etag}"))])
+                .await
+                .is_not_modified(modified_at)
+        );
+        assert!(conditional_get(&[(IF_NONE_MATCH, "*")])
+            .await
+            .is_not_modified(modified_at));
+        assert!(!conditional_get(&[(IF_NONE_MATCH, &etag)])
+            .await
+            .is_not_modified(modified_at + Duration::nanoseconds(1)));
+    }
+
+    #[tokio::test]
+    async fn if_modified_since() {
+        let modified_at = Utc::now().naive_utc();
+        let date = modified_at.format(HTTP_DATE_FORMAT).to_string();
+        println!("{date}");
+
+        assert!(conditional_get(&[(IF_MODIFIED_SINCE, &date)])
+            .await
+            .is_not_modified(modified_at));
+        assert!(!conditional_get(&[(IF_MODIFIED_SINCE, &date)])
+            .await
+            .is_not_modified(modified_at + Duration::seconds(1)));
+        assert!(!conditional_get(&[(IF_MODIFIED_SINCE, "yesterday")])
+            .await
+            .is_not_modified(modified_at));
+        // If-None-Match takes precedence
+        assert!(
+            !conditional_get(&[(IF_NONE_MATCH, "\"foo\""), (IF_MODIFIED_SINCE, &date)])
+                .await
+                .is_not_modified(modified_at)
+        );
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let updated_at = Utc::now().naive_utc();
+        let etag = ETag(updated_at).to_string();
+        println!("{etag}");
+
+        assert_eq!(
+            if_match(Some(&etag)).await,
+            Ok(Precondition::UpdatedAt(updated_at))
+        );
+    }
+
+    #[tokio::test]
+    async fn any() {
+        assert_eq!(if_match(None).await, Ok(Precondition::None));
+        assert_eq!(if_match(Some("*")).await, Ok(Precondition::None));
+    }
+
+    #[tokio::test]
+    async fn unsupported() {
+        let etag = ETag(Utc::now().naive_utc()).to_string();
+
+        assert_eq!(
+            if_match(Some(&format!("W/{etag}"))).await,
+            Err(IfMatchError::NoMatch)
+        );
+        assert_eq!(
+            if_match(Some(&format!("W/{etag}, \"foo\""))).await,
+            Err(IfMatchError::NoMatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn list() {
+        let updated_at = Utc::now().naive_utc();
+        let other = updated_at - Duration::seconds(1);
+        let header = format!("{}, \"foo\", W/{}", ETag(other), ETag(updated_at));
+        println!("{header}");
+
+        let precondition = if_match(Some(&header)).await.unwrap();
+
+        assert_eq!(precondition, Precondition::UpdatedAt(other));
+
+        let header = format!("{},{}", ETag(other), ETag(updated_at));
+        let precondition = if_match(Some(&header)).await.unwrap();
+
+        assert_eq!(
+            precondition,
+            Precondition::UpdatedAtAnyOf(vec![other, updated_at])
+        );
+        assert!(precondition.holds(updated_at));
+        assert!(precondition.holds(other));
+        assert!(!precondition.holds(updated_at + Duration::seconds(1)));
+    }
+}
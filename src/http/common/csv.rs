@@ -0,0 +1,130 @@
+//! Minimal CSV support as described in RFC 4180
+
+use std::mem;
+
+use super::errors::CsvError;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Appends a record terminated with CRLF, quoting fields which contain separators or quotes
+pub fn write_record<'a>(output: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+
+        if field.contains([',', '"', '\r', '\n']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+
+    output.push_str("\r\n");
+}
+
+/// Splits input into records of fields, skipping empty lines.
+/// Both CRLF and LF line endings are accepted, as spreadsheets differ in what they produce
+pub fn parse(input: &str) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let (mut quoted, mut line, mut quote_line) = (false, 1, 1);
+    let mut chars = input
+        .strip_prefix(BYTE_ORDER_MARK)
+        .unwrap_or(input)
+        .chars()
+        .peekable();
+
+    while let Some(x) = chars.next() {
+        match (quoted, x) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (false, '"') if field.is_empty() => {
+                quoted = true;
+                quote_line = line;
+            }
+            (false, ',') => record.push(mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                line += 1;
+                record.push(mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            (_, x) => {
+                if x == '\n' {
+                    line += 1;
+                }
+                field.push(x);
+            }
+        }
+    }
+
+    if quoted {
+        return Err(CsvError::UnterminatedQuote { line: quote_line });
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::plain(&["foo", "bar"], "foo,bar\r\n")]
+    #[case::empty(&["", ""], ",\r\n")]
+    #[case::separator(&["foo,bar", "baz"], "\"foo,bar\",baz\r\n")]
+    #[case::quote(&["say \"foo\""], "\"say \"\"foo\"\"\"\r\n")]
+    #[case::line_break(&["foo\nbar"], "\"foo\nbar\"\r\n")]
+    fn write(#[case] fields: &[&str], #[case] expected: &str) {
+        let mut output = String::new();
+        write_record(&mut output, fields.iter().copied());
+
+        assert_eq!(output, expected);
+    }
+
+    #[rstest]
+    #[case::plain("foo,bar\r\nbaz,qux\r\n", vec![vec!["foo", "bar"], vec!["baz", "qux"]])]
+    #[case::unix("foo,bar\nbaz,qux", vec![vec!["foo", "bar"], vec!["baz", "qux"]])]
+    #[case::byte_order_mark("\u{feff}foo\n", vec![vec!["foo"]])]
+    #[case::empty_lines("foo\n\n\nbar\n", vec![vec!["foo"], vec!["bar"]])]
+    #[case::empty_fields(",\n", vec![vec!["", ""]])]
+    #[case::quoted("\"foo,bar\",\"say \"\"baz\"\"\"\n", vec![vec!["foo,bar", "say \"baz\""]])]
+    #[case::line_break("\"foo\r\nbar\",baz", vec![vec!["foo\r\nbar", "baz"]])]
+    fn parse_ok(#[case] input: &str, #[case] expected: Vec<Vec<&str>>) {
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_unterminated_quote() {
+        assert_eq!(
+            parse("foo\nbar,\"baz\nqux"),
+            Err(CsvError::UnterminatedQuote { line: 2 })
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let fields = ["foo,bar", "say \"baz\"", "qux\r\nquux", ""];
+        let mut output = String::new();
+        write_record(&mut output, fields);
+
+        assert_eq!(parse(&output).unwrap(), [fields]);
+    }
+}
@@ -1,15 +1,22 @@
 use axum::http::{header::InvalidHeaderValue, HeaderMap, HeaderName, HeaderValue};
-use serde::Deserialize;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use super::errors::AppError;
-use crate::dao::{Pagination, PaginationBuilder};
+use crate::{
+    backup::BackupStatus,
+    dao::{CacheStats, Pagination, PaginationBuilder},
+};
 
 pub const PAGINATION_LIMIT_HEADER: &str = "pagination-limit";
 pub const PAGINATION_PAGE_HEADER: &str = "pagination-page";
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, IntoParams)]
 pub struct HttpPaginationParams {
+    #[param(minimum = 1, example = 1)]
     pub page: Option<usize>,
+    #[param(minimum = 1, example = 10)]
     pub limit: Option<usize>,
 }
 
@@ -47,3 +54,83 @@ impl TryInto<Pagination> for HttpPaginationParams {
         Ok(builder.build()?)
     }
 }
+
+/// Member of a JSON Merge Patch document (RFC 7396), absent members are kept and `null` removes them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Keep,
+    Remove,
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    /// Value of the member after patching, `None` if the member was removed
+    pub fn apply(self, current: T) -> Option<T> {
+        match self {
+            Patch::Keep => Some(current),
+            Patch::Remove => None,
+            Patch::Set(value) => Some(value),
+        }
+    }
+}
+
+// Only called for members present in the document, absent ones take `Patch::default()`
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Set(value),
+            None => Patch::Remove,
+        })
+    }
+}
+
+/// Status of the latest scheduled backup
+#[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, PartialEq, Eq))]
+#[schema(as = BackupStatus)]
+pub struct HttpBackupStatus {
+    pub attempted_at: Option<NaiveDateTime>,
+    pub succeeded_at: Option<NaiveDateTime>,
+    #[schema(example = "backups/backup-20180320T091228.jsonl")]
+    pub object: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<BackupStatus> for HttpBackupStatus {
+    fn from(value: BackupStatus) -> Self {
+        HttpBackupStatus {
+            attempted_at: value.attempted_at,
+            succeeded_at: value.succeeded_at,
+            object: value.object,
+            error: value.error,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, PartialEq, Eq))]
+#[schema(as = Health)]
+pub struct HttpHealth {
+    pub backup: Option<HttpBackupStatus>,
+    pub items_cache: Option<HttpCacheStats>,
+    pub users_cache: Option<HttpCacheStats>,
+}
+
+/// Lookups in a DAO cache since the start of the server
+#[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, PartialEq, Eq))]
+#[schema(as = CacheStats)]
+pub struct HttpCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl From<CacheStats> for HttpCacheStats {
+    fn from(value: CacheStats) -> Self {
+        HttpCacheStats {
+            hits: value.hits,
+            misses: value.misses,
+        }
+    }
+}
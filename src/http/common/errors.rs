@@ -1,18 +1,229 @@
+use std::mem;
+
 use axum::{
-    http::{header::InvalidHeaderValue, StatusCode},
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        Request,
+    },
+    http::{
+        header::{InvalidHeaderValue, CONTENT_LENGTH, CONTENT_TYPE},
+        StatusCode,
+    },
+    middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::request_id::RequestId;
+use crate::dao::{Constraint, ErrorVariant, PaginationBuilderError, Violation};
 
-use crate::dao::PaginationBuilderError;
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+const PROBLEM_TYPE_PREFIX: &str = "urn:sleeping-bag-locator:problem:";
 
 pub struct AppError {
     pub status_code: StatusCode,
+    pub code: &'static str,
     pub details: String,
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[schema(as = Violation)]
+pub struct HttpViolation {
+    #[schema(example = "name")]
+    pub field: String,
+    #[schema(example = "max_length")]
+    pub constraint: String,
+    #[schema(example = "Name 'Sleeping Bag...' is very long")]
+    pub message: String,
+}
+
+impl From<Violation> for HttpViolation {
+    fn from(value: Violation) -> Self {
+        HttpViolation {
+            field: value.field.to_owned(),
+            constraint: value.constraint.to_string(),
+            message: value.message,
+        }
+    }
+}
+
+/// Problem Details body, as described in RFC 7807
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[schema(as = Error)]
+pub struct HttpProblem {
+    #[serde(rename = "type")]
+    #[schema(example = "urn:sleeping-bag-locator:problem:GetItemError::NoSuchEntity")]
+    pub problem_type: String,
+    #[schema(example = "Not Found")]
+    pub title: String,
+    #[schema(example = 404)]
+    pub status: u16,
+    #[schema(
+        example = "Entity with id 'd06cd939-f13b-4524-83a6-f025639235e9' doesn't exist in our records"
+    )]
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/v1/items/d06cd939-f13b-4524-83a6-f025639235e9")]
+    pub instance: Option<String>,
+    /// Machine-readable error code, one per error variant
+    #[schema(example = "GetItemError::NoSuchEntity")]
+    pub code: String,
+    /// Every field violation, present for validation errors only
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<HttpViolation>,
+    /// Same as `X-Request-Id` response header, to find log lines of the failed request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "3b7c2a4e-1d9f-4f0a-8e2b-6c5d4a3b2c1d")]
+    pub request_id: Option<String>,
+}
+
+impl HttpProblem {
+    fn render(self) -> Response {
+        let status_code =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (
+            status_code,
+            [(CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+            Json(self.clone()),
+        )
+            .into_response();
+        // Kept for `problem_instance` middleware, which knows the request URI
+        response.extensions_mut().insert(self);
+
+        response
+    }
+}
+
+impl From<AppError> for HttpProblem {
+    fn from(value: AppError) -> Self {
+        HttpProblem {
+            problem_type: format!("{PROBLEM_TYPE_PREFIX}{}", value.code),
+            title: value
+                .status_code
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_owned(),
+            status: value.status_code.as_u16(),
+            detail: value.details,
+            instance: None,
+            code: value.code.to_owned(),
+            errors: value.violations.into_iter().map(Into::into).collect(),
+            request_id: None,
+        }
+    }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (self.status_code, self.details).into_response()
+        HttpProblem::from(self).render()
+    }
+}
+
+/// Fills `instance` and `request_id` of problem responses with the path and id of request,
+/// which caused them
+pub async fn problem_instance(request: Request, next: Next) -> Response {
+    let instance = request.uri().path().to_owned();
+    let request_id = request.extensions().get::<RequestId>().map(|x| x.0.clone());
+    let mut response = next.run(request).await;
+
+    let Some(problem) = response.extensions_mut().remove::<HttpProblem>() else {
+        return response;
+    };
+
+    let mut rendered = HttpProblem {
+        instance: Some(instance),
+        request_id,
+        ..problem
+    }
+    .render();
+    // Headers set next to the problem, e.g. by middlewares, survive rendering
+    let mut headers = mem::take(response.headers_mut());
+    headers.remove(CONTENT_LENGTH);
+    rendered.headers_mut().extend(headers);
+
+    rendered
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum IfMatchError {
+    #[error("If-Match header is not a valid header value")]
+    Malformed,
+    #[error("None of entity tags in If-Match header match current entity")]
+    NoMatch,
+}
+
+impl ErrorVariant for IfMatchError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Malformed => "IfMatchError::Malformed",
+            Self::NoMatch => "IfMatchError::NoMatch",
+        }
+    }
+}
+
+impl From<IfMatchError> for AppError {
+    fn from(value: IfMatchError) -> Self {
+        let status_code = match value {
+            IfMatchError::Malformed => StatusCode::BAD_REQUEST,
+            IfMatchError::NoMatch => StatusCode::PRECONDITION_FAILED,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for IfMatchError {
+    fn into_response(self) -> Response {
+        AppError::from(self).into_response()
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum MergePatchError {
+    #[error("Member '{member}' is required and can't be removed")]
+    RequiredMemberRemoved { member: &'static str },
+}
+
+impl ErrorVariant for MergePatchError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::RequiredMemberRemoved { .. } => "MergePatchError::RequiredMemberRemoved",
+        }
+    }
+}
+
+impl From<MergePatchError> for AppError {
+    fn from(value: MergePatchError) -> Self {
+        let (status_code, violations) = match &value {
+            MergePatchError::RequiredMemberRemoved { member } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                vec![Violation {
+                    field: member,
+                    constraint: Constraint::Required,
+                    message: value.to_string(),
+                }],
+            ),
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations,
+        }
     }
 }
 
@@ -20,7 +231,56 @@ impl From<InvalidHeaderValue> for AppError {
     fn from(value: InvalidHeaderValue) -> Self {
         Self {
             status_code: StatusCode::UNPROCESSABLE_ENTITY,
+            code: "InvalidHeaderValue",
             details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(value: JsonRejection) -> Self {
+        let code = match value {
+            JsonRejection::JsonDataError(_) => "JsonRejection::JsonDataError",
+            JsonRejection::JsonSyntaxError(_) => "JsonRejection::JsonSyntaxError",
+            JsonRejection::MissingJsonContentType(_) => "JsonRejection::MissingJsonContentType",
+            _ => "JsonRejection::BytesRejection",
+        };
+
+        Self {
+            status_code: value.status(),
+            code,
+            details: value.body_text(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(value: PathRejection) -> Self {
+        let code = match value {
+            PathRejection::FailedToDeserializePathParams(_) => {
+                "PathRejection::FailedToDeserializePathParams"
+            }
+            _ => "PathRejection::MissingPathParams",
+        };
+
+        Self {
+            status_code: value.status(),
+            code,
+            details: value.body_text(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(value: QueryRejection) -> Self {
+        Self {
+            status_code: value.status(),
+            code: "QueryRejection::FailedToDeserializeQueryString",
+            details: value.body_text(),
+            violations: Vec::new(),
         }
     }
 }
@@ -35,7 +295,97 @@ impl From<PaginationBuilderError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum CsvError {
+    #[error("Quoted field started at line {line} is never closed")]
+    UnterminatedQuote { line: usize },
+}
+
+impl ErrorVariant for CsvError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnterminatedQuote { .. } => "CsvError::UnterminatedQuote",
+        }
+    }
+}
+
+impl From<CsvError> for AppError {
+    fn from(value: CsvError) -> Self {
+        let status_code = match value {
+            CsvError::UnterminatedQuote { line: _ } => StatusCode::BAD_REQUEST,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Router};
+    use http_body_util::BodyExt;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn failing() -> Result<(), AppError> {
+        Err(PaginationBuilderError::PageIsZero.into())
+    }
+
+    #[tokio::test]
+    async fn problem_details() {
+        let router = Router::new()
+            .route("/failing", get(failing))
+            .layer(middleware::from_fn(problem_instance));
+
+        let raw_response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/failing?page=0")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            raw_response.headers().get(CONTENT_TYPE).unwrap(),
+            PROBLEM_CONTENT_TYPE
+        );
+
+        let response = from_slice::<HttpProblem>(
+            &raw_response.into_body().collect().await.unwrap().to_bytes(),
+        )
+        .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(
+            response,
+            HttpProblem {
+                problem_type: "urn:sleeping-bag-locator:problem:PaginationBuilderError::PageIsZero"
+                    .to_owned(),
+                title: "Unprocessable Entity".to_owned(),
+                status: 422,
+                detail: "Page number must be greater than zero".to_owned(),
+                instance: Some("/failing".to_owned()),
+                code: "PaginationBuilderError::PageIsZero".to_owned(),
+                errors: Vec::new(),
+                request_id: None,
+            }
+        );
+    }
+}
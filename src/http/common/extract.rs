@@ -0,0 +1,115 @@
+//! Drop-in replacements of axum extractors, which reject requests with problem details instead
+//! of plain text
+
+use axum::{
+    extract::{FromRequest, FromRequestParts},
+    response::{IntoResponse, Response},
+};
+
+use super::errors::AppError;
+
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct Json<T>(pub T);
+
+impl<T> IntoResponse for Json<T>
+where
+    axum::Json<T>: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct Query<T>(pub T);
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        routing::{get, post},
+        Router,
+    };
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::http::common::{HttpProblem, PROBLEM_CONTENT_TYPE};
+
+    async fn json(Json(value): Json<Vec<u8>>) -> Json<Vec<u8>> {
+        Json(value)
+    }
+
+    async fn path(Path(id): Path<Uuid>) -> String {
+        id.to_string()
+    }
+
+    async fn query(Query(page): Query<Vec<(String, usize)>>) -> String {
+        format!("{page:?}")
+    }
+
+    #[rstest]
+    #[case::json_syntax(
+        Request::post("/json").header(CONTENT_TYPE, "application/json").body(Body::from("[1,")),
+        StatusCode::BAD_REQUEST,
+        "JsonRejection::JsonSyntaxError"
+    )]
+    #[case::json_data(
+        Request::post("/json").header(CONTENT_TYPE, "application/json").body(Body::from("[-1]")),
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "JsonRejection::JsonDataError"
+    )]
+    #[case::json_content_type(
+        Request::post("/json").body(Body::from("[1]")),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "JsonRejection::MissingJsonContentType"
+    )]
+    #[case::path(
+        Request::get("/path/not-a-uuid").body(Body::empty()),
+        StatusCode::BAD_REQUEST,
+        "PathRejection::FailedToDeserializePathParams"
+    )]
+    #[case::query(
+        Request::get("/query?page=first").body(Body::empty()),
+        StatusCode::BAD_REQUEST,
+        "QueryRejection::FailedToDeserializeQueryString"
+    )]
+    #[tokio::test]
+    async fn rejected_with_problem(
+        #[case] request: Result<Request<Body>, axum::http::Error>,
+        #[case] expected_status: StatusCode,
+        #[case] expected_code: &str,
+    ) {
+        let router = Router::new()
+            .route("/json", post(json))
+            .route("/path/:id", get(path))
+            .route("/query", get(query));
+
+        let raw_response = router.oneshot(request.unwrap()).await.unwrap();
+
+        assert_eq!(raw_response.status(), expected_status);
+        assert_eq!(
+            raw_response.headers().get(CONTENT_TYPE).unwrap(),
+            PROBLEM_CONTENT_TYPE
+        );
+
+        let response = from_slice::<HttpProblem>(
+            &raw_response.into_body().collect().await.unwrap().to_bytes(),
+        )
+        .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(response.code, expected_code);
+        assert_eq!(response.status, expected_status.as_u16());
+    }
+}
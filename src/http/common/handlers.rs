@@ -1,12 +1,28 @@
-use axum::{debug_handler, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{debug_handler, extract::State, http::StatusCode, response::IntoResponse, Json};
+use utoipa::OpenApi;
 
-use super::state::AppState;
+use super::{dtos::HttpHealth, state::AppState};
 use crate::http::common::AppError;
 
+#[derive(OpenApi)]
+#[openapi(paths(health))]
+pub struct HealthApi;
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses((status = 200, description = "OK", body = HttpHealth)),
+)]
 #[debug_handler]
 pub async fn health(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
     state.items.health().await?;
     state.users.health().await?;
 
-    Ok(StatusCode::OK)
+    let result = HttpHealth {
+        backup: state.backup_status.map(|x| x.get().into()),
+        items_cache: state.items.cache_stats().map(Into::into),
+        users_cache: state.users.cache_stats().map(Into::into),
+    };
+
+    Ok((StatusCode::OK, Json(result)))
 }
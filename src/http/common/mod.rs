@@ -1,9 +1,24 @@
-pub use dtos::HttpPaginationParams;
-pub use errors::AppError;
-pub use handlers::health;
+pub use conditional::{ConditionalGet, ETag, IfMatch, LastModified, HTTP_DATE_FORMAT};
+pub use dtos::{HttpPaginationParams, Patch};
+pub use errors::{
+    problem_instance,
+    AppError,
+    CsvError,
+    HttpProblem,
+    HttpViolation,
+    MergePatchError,
+    PROBLEM_CONTENT_TYPE,
+};
+pub use extract::{Json, Path, Query};
+pub use handlers::{health, HealthApi};
+pub use request_id::{request_id, request_span};
 
 use super::state;
 
+mod conditional;
+pub mod csv;
 mod dtos;
 mod errors;
+mod extract;
 mod handlers;
+mod request_id;
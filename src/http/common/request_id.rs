@@ -0,0 +1,136 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{error_span, Span};
+use uuid::Uuid;
+
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Id of the request, which correlates its response with log lines written while serving it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Id supplied by client or proxy in front of us, unless it's unfit for logs
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+
+        (!value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LENGTH
+            && value.bytes().all(|x| x.is_ascii_graphic()))
+        .then(|| RequestId(value.to_owned()))
+    }
+}
+
+/// Accepts `X-Request-Id` of request or generates a new one, and echoes it in response.
+/// It's kept in request extensions for `request_span` and `problem_instance`
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(|| RequestId(Uuid::new_v4().to_string()));
+    // Both values are visible ASCII, so they are always valid header values
+    let header = HeaderValue::from_str(&request_id.0).unwrap();
+
+    request.headers_mut().insert(X_REQUEST_ID, header.clone());
+    request.extensions_mut().insert(request_id);
+    let mut response = next.run(request).await;
+    // Replayed responses may carry id of the original request
+    response.headers_mut().insert(X_REQUEST_ID, header);
+
+    response
+}
+
+/// Span of `TraceLayer`, every event logged while serving the request is attributed with its id
+pub fn request_span(request: &Request) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|x| x.0.as_str())
+        .unwrap_or_default();
+
+    // Span is enabled at any log level, so errors are attributed even if nothing else is logged
+    error_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+        request_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Router};
+    use http_body_util::BodyExt;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+    use tower_http::trace::TraceLayer;
+
+    use super::*;
+    use crate::{
+        dao::PaginationBuilderError,
+        http::common::{problem_instance, AppError, HttpProblem},
+    };
+
+    async fn failing() -> Result<(), AppError> {
+        Err(PaginationBuilderError::PageIsZero.into())
+    }
+
+    async fn call(header: Option<&str>) -> (Response, Option<HttpProblem>) {
+        let router = Router::new()
+            .route("/failing", get(failing))
+            .layer(middleware::from_fn(problem_instance))
+            .layer(TraceLayer::new_for_http().make_span_with(request_span))
+            .layer(middleware::from_fn(request_id));
+
+        let mut request = Request::builder().uri("/failing");
+        if let Some(header) = header {
+            request = request.header(X_REQUEST_ID, header);
+        }
+
+        let raw_response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let (parts, body) = raw_response.into_parts();
+        let body = body.collect().await.unwrap().to_bytes();
+
+        (
+            Response::from_parts(parts, Body::empty()),
+            from_slice(&body).ok(),
+        )
+    }
+
+    #[tokio::test]
+    async fn accepted() {
+        let (response, problem) = call(Some("abc-123")).await;
+
+        assert_eq!(response.headers().get(X_REQUEST_ID).unwrap(), "abc-123");
+        assert_eq!(problem.unwrap().request_id.as_deref(), Some("abc-123"));
+    }
+
+    #[tokio::test]
+    async fn generated() {
+        let (response, problem) = call(Some(&"x".repeat(MAX_REQUEST_ID_LENGTH + 1))).await;
+        let request_id = response
+            .headers()
+            .get(X_REQUEST_ID)
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        assert!(Uuid::parse_str(request_id).is_ok());
+        assert_eq!(problem.unwrap().request_id.as_deref(), Some(request_id));
+
+        let (response, _) = call(None).await;
+
+        assert!(response.headers().contains_key(X_REQUEST_ID));
+    }
+}
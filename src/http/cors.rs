@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use axum::http::{request::Parts, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::cors::OriginPattern;
+
+/// Which cross-origin requests browsers are allowed to make,
+/// responses to requests from other origins carry no CORS headers
+#[derive(Clone, Debug)]
+pub struct CorsPolicy {
+    pub origins: Vec<OriginPattern>,
+    pub methods: Vec<Method>,
+    pub headers: Vec<HeaderName>,
+    pub exposed_headers: Vec<HeaderName>,
+    /// Whether cookies are sent with cross-origin requests
+    pub credentials: bool,
+    pub max_age: Duration,
+}
+
+impl From<CorsPolicy> for CorsLayer {
+    fn from(value: CorsPolicy) -> Self {
+        let origins = value.origins;
+
+        // Matching origin is reflected instead of `*`, which browsers reject for requests
+        // with credentials. Methods and headers are always listed for the same reason
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(
+                move |origin: &HeaderValue, _: &Parts| {
+                    origin
+                        .to_str()
+                        .is_ok_and(|origin| origins.iter().any(|x| x.matches(origin)))
+                },
+            ))
+            .allow_methods(value.methods)
+            .allow_headers(value.headers)
+            .expose_headers(value.exposed_headers)
+            .allow_credentials(value.credentials)
+            .max_age(value.max_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{
+            header::{
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                ACCESS_CONTROL_ALLOW_HEADERS,
+                ACCESS_CONTROL_ALLOW_METHODS,
+                ACCESS_CONTROL_ALLOW_ORIGIN,
+                ACCESS_CONTROL_EXPOSE_HEADERS,
+                ACCESS_CONTROL_MAX_AGE,
+                ACCESS_CONTROL_REQUEST_HEADERS,
+                ACCESS_CONTROL_REQUEST_METHOD,
+                CONTENT_TYPE,
+                ETAG,
+                IF_MATCH,
+                ORIGIN,
+            },
+            Request,
+            StatusCode,
+        },
+        response::Response,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const UI_ORIGIN: &str = "https://ui.example.com";
+
+    fn router() -> Router {
+        let policy = CorsPolicy {
+            origins: vec![
+                "https://*.example.com".parse().unwrap(),
+                "http://localhost:3000".parse().unwrap(),
+            ],
+            methods: vec![Method::GET, Method::PUT],
+            headers: vec![CONTENT_TYPE, IF_MATCH],
+            exposed_headers: vec![ETAG],
+            credentials: true,
+            max_age: Duration::from_secs(600),
+        };
+
+        Router::new()
+            .route("/items", get(|| async {}).put(|| async {}))
+            .layer(CorsLayer::from(policy))
+    }
+
+    async fn preflight(origin: &str) -> Response {
+        router()
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/items")
+                    .header(ORIGIN, origin)
+                    .header(ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+                    .header(ACCESS_CONTROL_REQUEST_HEADERS, "content-type,if-match")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn preflight_with_credentials() {
+        let response = preflight(UI_ORIGIN).await;
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), UI_ORIGIN);
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET,PUT"
+        );
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "content-type,if-match"
+        );
+        assert_eq!(headers.get(ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+    }
+
+    #[tokio::test]
+    async fn preflight_from_unknown_origin() {
+        let response = preflight("https://evil.com").await;
+
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn actual_request() {
+        let response = router()
+            .oneshot(
+                Request::builder()
+                    .uri("/items")
+                    .header(ORIGIN, "http://localhost:3000")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "http://localhost:3000"
+        );
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+        assert_eq!(headers.get(ACCESS_CONTROL_EXPOSE_HEADERS).unwrap(), "etag");
+    }
+}
@@ -0,0 +1,104 @@
+use axum::Router;
+use utoipa::{openapi::OpenApi as Document, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::{
+    common::HealthApi,
+    state::AppState,
+    versions::{V1Api, V1_PREFIX},
+};
+
+const OPENAPI_PATH: &str = "/openapi.json";
+const SWAGGER_UI_PATH: &str = "/docs";
+
+/// Unversioned aliases aren't documented, since they are deprecated
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Sleeping Bag Locator",
+        description = "Resources are served under `/v1`. The same routes without version prefix \
+                       are deprecated aliases of `/v1`, answered with `Deprecation`, `Sunset` and \
+                       `Link: rel=\"successor-version\"` headers until they are removed",
+        license(name = "MIT", url = "https://opensource.org/license/mit"),
+    ),
+    servers((url = "http://localhost:8080", description = "Local development")),
+)]
+struct ApiDoc;
+
+/// API document, generated from DTOs and handlers of the routes
+pub fn openapi() -> Document {
+    ApiDoc::openapi()
+        .nest(V1_PREFIX, V1Api::openapi())
+        .merge_from(HealthApi::openapi())
+}
+
+/// Serves API document at `/openapi.json` and Swagger UI over it at `/docs`
+#[derive(Default)]
+pub struct DocsRouter {}
+
+impl From<DocsRouter> for Router<AppState> {
+    fn from(_: DocsRouter) -> Self {
+        SwaggerUi::new(SWAGGER_UI_PATH)
+            .url(OPENAPI_PATH, openapi())
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path};
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{from_slice, to_value, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const SPEC_FILE: &str = "openapi.yml";
+    const UPDATE_ENV: &str = "UPDATE_OPENAPI";
+
+    #[test]
+    fn committed_spec_is_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(SPEC_FILE);
+        let generated = openapi().to_yaml().unwrap();
+
+        if env::var_os(UPDATE_ENV).is_some() {
+            fs::write(&path, &generated).unwrap();
+        }
+
+        assert!(
+            fs::read_to_string(&path).unwrap() == generated,
+            "{SPEC_FILE} differs from the document generated from code, \
+             run tests with {UPDATE_ENV}=1 to regenerate it"
+        );
+    }
+
+    #[tokio::test]
+    async fn serve_spec() {
+        let router: Router<AppState> = DocsRouter::default().into();
+
+        let raw_response = router
+            .with_state(AppState::default())
+            .oneshot(
+                Request::builder()
+                    .uri(OPENAPI_PATH)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+
+        let body = raw_response.into_body().collect().await.unwrap().to_bytes();
+
+        assert_eq!(
+            from_slice::<Value>(&body).unwrap(),
+            to_value(openapi()).unwrap()
+        );
+    }
+}
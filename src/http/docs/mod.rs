@@ -0,0 +1,5 @@
+pub use handlers::DocsRouter;
+
+use super::{common, state, versions};
+
+mod handlers;
@@ -0,0 +1,51 @@
+use async_graphql::{to_value, Error, ErrorExtensions};
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use super::common::{AppError, HttpViolation};
+use crate::dao::ErrorVariant;
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum GraphQLBatchError {
+    #[error("Batch of {size} GraphQL requests exceeds the limit of {max}")]
+    TooLarge { size: usize, max: usize },
+}
+
+impl ErrorVariant for GraphQLBatchError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "GraphQLBatchError::TooLarge",
+        }
+    }
+}
+
+impl From<GraphQLBatchError> for AppError {
+    fn from(value: GraphQLBatchError) -> Self {
+        let status_code = match value {
+            GraphQLBatchError::TooLarge { size: _, max: _ } => StatusCode::PAYLOAD_TOO_LARGE,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+/// GraphQL counterpart of a problem document, with the same code, status and violations in
+/// error extensions
+pub fn graphql_error(error: impl Into<AppError>) -> Error {
+    let error: AppError = error.into();
+    let violations: Vec<HttpViolation> = error.violations.into_iter().map(Into::into).collect();
+
+    Error::new(error.details).extend_with(|_, extensions| {
+        extensions.set("code", error.code);
+        extensions.set("status", error.status_code.as_u16());
+        if let (false, Ok(violations)) = (violations.is_empty(), to_value(&violations)) {
+            extensions.set("errors", violations);
+        }
+    })
+}
@@ -0,0 +1,389 @@
+use async_graphql::BatchRequest;
+use axum::{
+    debug_handler,
+    extract::State,
+    http::{header::ACCEPT, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse,
+        Response,
+        Sse,
+    },
+    routing::post,
+    Extension,
+    Router,
+};
+use futures::{stream, StreamExt};
+use utoipa::OpenApi;
+
+use super::{
+    authentication::SessionUser,
+    errors::GraphQLBatchError,
+    schema::{schema, AppSchema},
+    state::AppState,
+};
+use crate::http::{
+    common::{AppError, HttpProblem, Json, PROBLEM_CONTENT_TYPE},
+    idempotency::IdempotencyKey,
+};
+
+const EVENT_STREAM: &str = "text/event-stream";
+/// Every request of a batch is limited on its own, so the batch is limited as a whole here
+const MAX_BATCH_SIZE: usize = 16;
+
+#[derive(Default)]
+pub struct GraphQLRouter {}
+
+#[derive(OpenApi)]
+#[openapi(paths(graphql))]
+pub struct GraphQLApi;
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|x| x.to_str().ok())
+        .any(|x| x.contains(EVENT_STREAM))
+}
+
+/// Executes queries and mutations, batched ones included. A single operation is streamed as
+/// Server-Sent Events if asked for, which is the only way to run subscriptions
+///
+/// Validation is the same as of REST routes. Errors carry `code`, `status` and `errors`
+/// extensions of the matching problem document. A single request with
+/// `Accept: text/event-stream` is answered with `next` events followed by `complete`.
+/// Requests are limited in depth and complexity, batches are limited to 16 requests.
+/// Items created by the logged in user are owned by the user, and subscriptions are only
+/// available to logged in users
+#[utoipa::path(
+    post,
+    path = "",
+    params(IdempotencyKey),
+    request_body(
+        content = serde_json::Value,
+        description = "GraphQL request or an array of them, which is executed as a batch",
+        example = json!({"query": "{ items(limit: 10) { id name location } }"}),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            content(
+                (serde_json::Value = "application/json"),
+                (String = "text/event-stream"),
+            ),
+        ),
+        (
+            status = 413,
+            description = "Payload Too Large, batch has too many requests",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn graphql(
+    user: Option<SessionUser>,
+    State(state): State<AppState>,
+    Extension(schema): Extension<AppSchema>,
+    headers: HeaderMap,
+    Json(request): Json<BatchRequest>,
+) -> Result<Response, AppError> {
+    if let BatchRequest::Batch(requests) = &request {
+        if requests.len() > MAX_BATCH_SIZE {
+            return Err(GraphQLBatchError::TooLarge {
+                size: requests.len(),
+                max: MAX_BATCH_SIZE,
+            }
+            .into());
+        }
+    }
+
+    // Resolvers find the logged in user in request data, anonymous requests have none
+    let request = match user {
+        Some(user) => request.data(user),
+        None => request,
+    };
+
+    Ok(match request {
+        BatchRequest::Single(request) if accepts_event_stream(&headers) => {
+            // Same framing as the "distinct connections" mode of GraphQL over SSE protocol
+            let responses = schema
+                .execute_stream(request.data(state))
+                .map(|x| Event::default().event("next").json_data(x));
+            let complete = stream::iter([Ok(Event::default().event("complete").data(""))]);
+
+            Sse::new(responses.chain(complete))
+                .keep_alive(KeepAlive::default())
+                .into_response()
+        }
+        request => Json(schema.execute_batch(request.data(state)).await).into_response(),
+    })
+}
+
+impl From<GraphQLRouter> for Router<AppState> {
+    fn from(_: GraphQLRouter) -> Self {
+        Router::new()
+            .route("/", post(graphql))
+            .layer(Extension(schema()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{body::Body, http::Request};
+    use http_body_util::BodyExt;
+    use reqwest::{
+        header::{CONTENT_TYPE, COOKIE},
+        StatusCode,
+    };
+    use rstest::rstest;
+    use serde_json::{from_slice, json, Value};
+    use tokio::time::timeout;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{dao::ItemBuilder, http::authentication::session_cookie};
+
+    fn request(body: &Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn call(state: AppState, body: &Value) -> Value {
+        let router: Router<AppState> = GraphQLRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(request(body))
+            .await
+            .unwrap();
+        assert_eq!(raw_response.status(), StatusCode::OK);
+        let body = raw_response.into_body().collect().await.unwrap().to_bytes();
+        let response = from_slice::<Value>(&body).unwrap();
+        println!("{response:#?}");
+
+        response
+    }
+
+    #[tokio::test]
+    async fn items_and_user_in_one_request() {
+        let state = AppState::default();
+
+        let created = call(
+            state.clone(),
+            &json!({
+                "query": r#"mutation {
+                    createUser(input: {name: "John", authType: GITHUB, externalId: "john"}) { id }
+                }"#
+            }),
+        )
+        .await;
+        let user_id = &created["data"]["createUser"]["id"];
+
+        let response = call(
+            state,
+            &json!({
+                "query": "query($id: UUID!) { items(limit: 1) { name location } user(id: $id) { name externalId } }",
+                "variables": {"id": user_id}
+            }),
+        )
+        .await;
+
+        assert!(response.get("errors").is_none());
+        assert_eq!(
+            response["data"]["items"],
+            json!([{"name": "Sleeping Bag", "location": "Calgary, AB"}])
+        );
+        assert_eq!(
+            response["data"]["user"],
+            json!({"name": "John", "externalId": "john"})
+        );
+    }
+
+    #[tokio::test]
+    async fn batch() {
+        let response = call(
+            AppState::default(),
+            &json!([
+                {"query": "{ items { name } }"},
+                {"query": "{ items(page: 0) { name } }"},
+            ]),
+        )
+        .await;
+
+        assert_eq!(response[0]["data"]["items"][0]["name"], "Sleeping Bag");
+        assert_eq!(
+            response[1]["errors"][0]["extensions"]["code"],
+            "PaginationBuilderError::PageIsZero"
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_too_large() {
+        let router: Router<AppState> = GraphQLRouter::default().into();
+        let requests: Vec<Value> = (0..=MAX_BATCH_SIZE)
+            .map(|_| json!({"query": "{ items { name } }"}))
+            .collect();
+
+        let response = router
+            .with_state(AppState::default())
+            .oneshot(request(&json!(requests)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn owner_relations() {
+        let state = AppState::default();
+
+        let created = call(
+            state.clone(),
+            &json!({
+                "query": r#"mutation {
+                    createUser(input: {name: "John", authType: GITHUB, externalId: "john"}) { id }
+                }"#
+            }),
+        )
+        .await;
+        // Mocked items are owned by whoever they're listed for
+        let user_id = &created["data"]["createUser"]["id"];
+
+        let response = call(
+            state,
+            &json!({
+                "query": "query($id: UUID!) { users { name } user(id: $id) { items { name owner { name } } } }",
+                "variables": {"id": user_id}
+            }),
+        )
+        .await;
+
+        assert!(response.get("errors").is_none());
+        assert_eq!(response["data"]["users"], json!([{"name": "John"}]));
+        assert_eq!(
+            response["data"]["user"]["items"],
+            json!([{"name": "Sleeping Bag", "owner": {"name": "John"}}])
+        );
+    }
+
+    #[rstest]
+    #[case::too_deep(
+        "{ users { items { owner { items { owner { items { owner { items { name } } } } } } } } }"
+    )]
+    #[case::too_complex("{ items(limit: 1000) { id name location } }")]
+    #[tokio::test]
+    async fn limited(#[case] query: &str) {
+        let response = call(AppState::default(), &json!({ "query": query })).await;
+
+        assert!(response["data"].is_null());
+        assert!(response["errors"][0]["message"].is_string());
+    }
+
+    #[tokio::test]
+    async fn invalid_input() {
+        let response = call(
+            AppState::default(),
+            &json!({
+                "query": r#"mutation { createItem(input: {name: "", location: "Calgary, AB"}) { id } }"#
+            }),
+        )
+        .await;
+
+        let extensions = &response["errors"][0]["extensions"];
+        assert_eq!(extensions["code"], "CreateItemError::InvalidParams");
+        assert_eq!(extensions["status"], 422);
+        assert_eq!(extensions["errors"][0]["field"], "name");
+    }
+
+    fn subscription(cookie: Option<&str>) -> Request<Body> {
+        let mut request = request(&json!({
+            "query": "subscription { itemEvents { kind item { name } } }"
+        }));
+        request
+            .headers_mut()
+            .insert(ACCEPT, EVENT_STREAM.parse().unwrap());
+        if let Some(cookie) = cookie {
+            request
+                .headers_mut()
+                .insert(COOKIE, cookie.parse().unwrap());
+        }
+
+        request
+    }
+
+    #[tokio::test]
+    async fn subscription_over_sse() {
+        let state = AppState::default();
+        let (user_id, cookie) = session_cookie(&state, 42).await;
+        let router: Router<AppState> = GraphQLRouter::default().into();
+
+        let request = subscription(Some(&cookie));
+        let response = router
+            .with_state(state.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[CONTENT_TYPE], EVENT_STREAM);
+
+        // Subscription starts along with the body stream, so events are published until one
+        // arrives. Items of other users are never streamed
+        let item = |name: &str, owner_id| {
+            ItemBuilder::new()
+                .name(name.to_owned())
+                .location("Banff, AB".to_owned())
+                .owner_id(Some(owner_id))
+                .build()
+                .unwrap()
+        };
+        let (other, owned) = (item("Sleeping Bag", Uuid::new_v4()), item("Tent", user_id));
+        let publisher = tokio::spawn(async move {
+            loop {
+                state.item_events.created(&other);
+                state.item_events.created(&owned);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        let frame = timeout(Duration::from_secs(5), response.into_body().frame())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        publisher.abort();
+
+        let frame = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        assert_eq!(
+            frame,
+            "event: next\ndata: {\"data\":{\"itemEvents\":{\"kind\":\"CREATED\",\"item\":{\"name\":\"Tent\"}}}}\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn subscription_unauthenticated() {
+        let router: Router<AppState> = GraphQLRouter::default().into();
+
+        let response = router
+            .with_state(AppState::default())
+            .oneshot(subscription(None))
+            .await
+            .unwrap();
+        let frame = timeout(Duration::from_secs(5), response.into_body().frame())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        let frame = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        println!("{frame}");
+        assert!(frame.starts_with("event: next\n"));
+        assert!(frame.contains("SessionUserError::Unauthenticated"));
+    }
+}
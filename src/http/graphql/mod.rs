@@ -0,0 +1,8 @@
+pub use errors::graphql_error;
+pub use handlers::{GraphQLApi, GraphQLRouter};
+
+use super::{authentication, common, items, state, users};
+
+mod errors;
+mod handlers;
+mod schema;
@@ -0,0 +1,221 @@
+use async_graphql::{Context, Object, Result, Schema, Subscription};
+use futures::Stream;
+use uuid::Uuid;
+
+use super::{
+    authentication::{SessionUser, SessionUserError},
+    common::HttpPaginationParams,
+    errors::graphql_error,
+    items::{HttpCreateItemParams, HttpItem, HttpItemEvent, HttpUpdateItemParams},
+    state::AppState,
+    users::{
+        notify_user_webhooks,
+        HttpCreateUserParams,
+        HttpUpdateUserParams,
+        HttpUser,
+        UserChange,
+    },
+};
+use crate::dao::{CreateItemParams, Pagination, PaginationBuilder, Precondition, UpdateItemParams};
+
+/// Deep enough for items of an owner of an item, but not for endless nesting of relations
+const MAX_DEPTH: usize = 8;
+/// Every field costs one, and list fields are multiplied by their limit
+const MAX_COMPLEXITY: usize = 1000;
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+pub fn schema() -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    #[graphql(complexity = "limit
+        .unwrap_or(PaginationBuilder::DEFAULT_PAGINATION_LIMIT)
+        .saturating_mul(child_complexity)")]
+    async fn items(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<HttpItem>> {
+        let state = ctx.data_unchecked::<AppState>();
+        let pagination: Pagination = HttpPaginationParams { page, limit }
+            .try_into()
+            .map_err(graphql_error)?;
+
+        Ok(state
+            .items
+            .list(pagination)
+            .await
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn item(&self, ctx: &Context<'_>, id: Uuid) -> Result<HttpItem> {
+        let state = ctx.data_unchecked::<AppState>();
+
+        Ok(state.items.get(id).await.map_err(graphql_error)?.into())
+    }
+
+    #[graphql(complexity = "limit
+        .unwrap_or(PaginationBuilder::DEFAULT_PAGINATION_LIMIT)
+        .saturating_mul(child_complexity)")]
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<HttpUser>> {
+        let state = ctx.data_unchecked::<AppState>();
+        let pagination: Pagination = HttpPaginationParams { page, limit }
+            .try_into()
+            .map_err(graphql_error)?;
+
+        Ok(state
+            .users
+            .list(pagination)
+            .await
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn user(&self, ctx: &Context<'_>, id: Uuid) -> Result<HttpUser> {
+        let state = ctx.data_unchecked::<AppState>();
+
+        Ok(state.users.get(id).await.map_err(graphql_error)?.into())
+    }
+}
+
+/// Same operations as the REST handlers, including published events. Writes are unconditional,
+/// as requests without `If-Match` are
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_item(
+        &self,
+        ctx: &Context<'_>,
+        input: HttpCreateItemParams,
+    ) -> Result<HttpItem> {
+        let state = ctx.data_unchecked::<AppState>();
+        let params: CreateItemParams = input.try_into().map_err(graphql_error)?;
+        let owner_id = ctx.data_opt::<SessionUser>().map(|SessionUser(id)| *id);
+        let entity = state
+            .items
+            .create(params.owned_by(owner_id))
+            .await
+            .map_err(graphql_error)?;
+        state.item_events.created(&entity);
+
+        Ok(entity.into())
+    }
+
+    async fn update_item(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        input: HttpUpdateItemParams,
+    ) -> Result<HttpItem> {
+        let state = ctx.data_unchecked::<AppState>();
+        let params: UpdateItemParams = input.try_into().map_err(graphql_error)?;
+        let entity = state
+            .items
+            .update(id, params, Precondition::None)
+            .await
+            .map_err(graphql_error)?;
+        state.item_events.updated(&entity);
+
+        Ok(entity.into())
+    }
+
+    /// Moves item to trash and returns its id
+    async fn delete_item(&self, ctx: &Context<'_>, id: Uuid) -> Result<Uuid> {
+        let state = ctx.data_unchecked::<AppState>();
+        let entity = state
+            .items
+            .delete(id, Precondition::None)
+            .await
+            .map_err(graphql_error)?;
+        state.item_events.deleted(&entity);
+
+        Ok(id)
+    }
+
+    async fn create_user(
+        &self,
+        ctx: &Context<'_>,
+        input: HttpCreateUserParams,
+    ) -> Result<HttpUser> {
+        let state = ctx.data_unchecked::<AppState>();
+        let result: HttpUser = state
+            .users
+            .create(input.into())
+            .await
+            .map_err(graphql_error)?
+            .into();
+        notify_user_webhooks(&state.webhooks, UserChange::Created(&result));
+
+        Ok(result)
+    }
+
+    async fn update_user(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        input: HttpUpdateUserParams,
+    ) -> Result<HttpUser> {
+        let state = ctx.data_unchecked::<AppState>();
+        let result: HttpUser = state
+            .users
+            .update(id, input.into(), Precondition::None)
+            .await
+            .map_err(graphql_error)?
+            .into();
+        notify_user_webhooks(&state.webhooks, UserChange::Updated(&result));
+
+        Ok(result)
+    }
+
+    async fn delete_user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Uuid> {
+        let state = ctx.data_unchecked::<AppState>();
+        state
+            .users
+            .delete(id, Precondition::None)
+            .await
+            .map_err(graphql_error)?;
+        notify_user_webhooks(&state.webhooks, UserChange::Deleted(id));
+
+        Ok(id)
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Changes of the logged in user's items published after subscribing, see
+    /// `GET /items/events` for resumable feed
+    // Subscriptions have to be async, even though nothing is awaited before streaming
+    #[allow(clippy::unused_async)]
+    async fn item_events(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = HttpItemEvent>> {
+        let SessionUser(user_id) = ctx
+            .data_opt::<SessionUser>()
+            .ok_or_else(|| graphql_error(SessionUserError::Unauthenticated))?;
+
+        Ok(ctx
+            .data_unchecked::<AppState>()
+            .item_events
+            .changes(*user_id))
+    }
+}
@@ -0,0 +1,63 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+use tracing::error;
+
+use crate::{dao::ErrorVariant, http::common::AppError, idempotency::IdempotencyStoreError};
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum IdempotencyError {
+    #[error("Idempotency-Key header must contain from 1 to {max} visible ASCII characters")]
+    InvalidKey { max: usize },
+    #[error("Request body exceeds {max} bytes and can't be fingerprinted")]
+    BodyTooLarge { max: usize },
+    #[error("Idempotency key '{key}' was already used with a different request")]
+    KeyReused { key: String },
+    #[error("Request with idempotency key '{key}' is still being processed")]
+    InProgress { key: String },
+    #[error(transparent)]
+    Store(#[from] IdempotencyStoreError),
+}
+
+impl ErrorVariant for IdempotencyError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidKey { .. } => "IdempotencyError::InvalidKey",
+            Self::BodyTooLarge { .. } => "IdempotencyError::BodyTooLarge",
+            Self::KeyReused { .. } => "IdempotencyError::KeyReused",
+            Self::InProgress { .. } => "IdempotencyError::InProgress",
+            Self::Store(err) => err.variant(),
+        }
+    }
+}
+
+impl From<IdempotencyError> for AppError {
+    fn from(value: IdempotencyError) -> Self {
+        let status_code = match value {
+            IdempotencyError::InvalidKey { .. } => StatusCode::BAD_REQUEST,
+            IdempotencyError::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            IdempotencyError::KeyReused { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            IdempotencyError::InProgress { .. } => StatusCode::CONFLICT,
+            IdempotencyError::Store(ref err) => {
+                error!("{:#?}", err.to_string());
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for IdempotencyError {
+    fn into_response(self) -> Response {
+        AppError::from(self).into_response()
+    }
+}
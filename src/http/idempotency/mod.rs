@@ -0,0 +1,489 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        request::Parts,
+        HeaderMap,
+        HeaderName,
+        HeaderValue,
+        Method,
+        StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tokio::runtime::Handle;
+use tracing::error;
+use utoipa::IntoParams;
+
+use self::errors::IdempotencyError;
+use super::{
+    authentication::client_key,
+    common::{HttpProblem, PROBLEM_CONTENT_TYPE},
+    state::AppState,
+};
+use crate::idempotency::{
+    IdempotencyRecord,
+    IdempotencyStore,
+    IdempotencyStoreError,
+    StoredResponse,
+};
+
+mod errors;
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+const MAX_KEY_LENGTH: usize = 255;
+// Same as default limit of `Json` extractor, so no request is rejected earlier than by handler
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+/// Larger responses are passed through without storing, so they are not replayed
+const MAX_STORED_RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// `Idempotency-Key` request header, which is described by POST routes in API document
+#[derive(IntoParams)]
+#[into_params(names("Idempotency-Key"), parameter_in = Header)]
+pub struct IdempotencyKey(
+    /// Makes request safe to retry. The first response is stored and replayed with
+    /// `Idempotent-Replayed: true` header to retries with the same key and request,
+    /// while reusing the key with a different request is rejected with 422.
+    /// Keys are scoped per client. Responses with 5xx status, event streams and responses
+    /// over 2 MiB are not stored
+    #[param(min_length = 1, max_length = 255)]
+    Option<String>,
+);
+
+/// Makes POST requests with `Idempotency-Key` header safe to retry:
+/// the first response is stored and replayed to every retry with the same key and request.
+/// Keys are scoped per client, so different clients never see responses of each other
+pub async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, IdempotencyError> {
+    if request.method() != Method::POST {
+        return Ok(next.run(request).await);
+    }
+    let IdempotencyKey(Some(key)) = IdempotencyKey::parse(request.headers())? else {
+        return Ok(next.run(request).await);
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|_| IdempotencyError::BodyTooLarge { max: MAX_BODY_SIZE })?;
+    let fingerprint = fingerprint(&parts, &body);
+    let client = client_key(state.session_store.as_ref(), &mut parts).await;
+    let scoped_key = format!("{client}:{key}");
+
+    match state
+        .idempotency
+        .begin(&scoped_key, &fingerprint, state.idempotency_lock_ttl)
+        .await?
+    {
+        Some(record) if record.fingerprint() != fingerprint => {
+            return Err(IdempotencyError::KeyReused { key })
+        }
+        Some(IdempotencyRecord::InProgress { .. }) => {
+            return Err(IdempotencyError::InProgress { key })
+        }
+        Some(IdempotencyRecord::Completed { response, .. }) => return Ok(replay(response)),
+        None => {}
+    }
+    let reservation = Reservation::new(state.idempotency.clone(), scoped_key);
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Server errors are transient, so the key is released to let client retry for real.
+    // Streams never end while client listens and huge responses aren't worth storing
+    if response.status().is_server_error() || !storable(&response) {
+        reservation.release().await;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = to_bytes(body, MAX_STORED_RESPONSE_SIZE).await else {
+        reservation.release().await;
+        return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect(),
+        body: body.to_vec(),
+    };
+    reservation
+        .complete(&fingerprint, stored, state.idempotency_ttl)
+        .await?;
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Key reserved for a request in progress. It's released in background if the request is
+/// abandoned before its response is stored, e.g. when client disconnects mid-request
+struct Reservation {
+    store: Arc<dyn IdempotencyStore + Send + Sync>,
+    key: Option<String>,
+}
+
+impl Reservation {
+    fn new(store: Arc<dyn IdempotencyStore + Send + Sync>, key: String) -> Self {
+        Self {
+            store,
+            key: Some(key),
+        }
+    }
+
+    async fn complete(
+        mut self,
+        fingerprint: &str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), IdempotencyStoreError> {
+        if let Some(key) = &self.key {
+            self.store.complete(key, fingerprint, response, ttl).await?;
+        }
+        self.key = None;
+
+        Ok(())
+    }
+
+    async fn release(mut self) {
+        if let Some(key) = self.key.take() {
+            release(self.store.as_ref(), &key).await;
+        }
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        if let Ok(runtime) = Handle::try_current() {
+            let store = self.store.clone();
+            runtime.spawn(async move { release(store.as_ref(), &key).await });
+        }
+    }
+}
+
+async fn release(store: &(dyn IdempotencyStore + Send + Sync), key: &str) {
+    if let Err(err) = store.release(key).await {
+        error!("Cannot release idempotency key {key:?}: {err}");
+    }
+}
+
+/// Whether the response has a known size within the limit and isn't an event stream
+fn storable(response: &Response) -> bool {
+    let streaming = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|x| x.starts_with(EVENT_STREAM_CONTENT_TYPE));
+    let size = response.body().size_hint().upper();
+
+    !streaming && size.is_some_and(|x| x <= MAX_STORED_RESPONSE_SIZE as u64)
+}
+
+impl IdempotencyKey {
+    fn parse(headers: &HeaderMap) -> Result<Self, IdempotencyError> {
+        let Some(value) = headers.get(IDEMPOTENCY_KEY) else {
+            return Ok(IdempotencyKey(None));
+        };
+        let key = value.to_str().unwrap_or_default();
+
+        if key.is_empty()
+            || key.len() > MAX_KEY_LENGTH
+            || !key.bytes().all(|x| x.is_ascii_graphic())
+        {
+            return Err(IdempotencyError::InvalidKey {
+                max: MAX_KEY_LENGTH,
+            });
+        }
+
+        Ok(IdempotencyKey(Some(key.to_owned())))
+    }
+}
+
+fn fingerprint(parts: &Parts, body: &Bytes) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(parts.method.as_str());
+    hasher.update(b" ");
+    hasher.update(parts.uri.to_string());
+    hasher.update(b"\n");
+    hasher.update(body);
+
+    format!("{:x}", hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &stored.headers {
+        if name == CONTENT_LENGTH.as_str() {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::try_from(value.as_str()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+
+    // Stored problems are kept for `problem_instance` middleware, as original ones
+    let problem = (headers.get(CONTENT_TYPE)
+        == Some(&HeaderValue::from_static(PROBLEM_CONTENT_TYPE)))
+    .then(|| serde_json::from_slice::<HttpProblem>(&stored.body).ok())
+    .flatten();
+
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() =
+        StatusCode::from_u16(stored.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    *response.headers_mut() = headers;
+    if let Some(problem) = problem {
+        response.extensions_mut().insert(problem);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{extract::ConnectInfo, middleware, routing::post, Router};
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{http::common::problem_instance, idempotency::IdempotencyMemoryStore};
+
+    /// Router, which counts handler calls and fails with `failure` status on the first one
+    fn router(calls: &Arc<AtomicUsize>, failure: Option<StatusCode>) -> Router {
+        let state = AppState::default();
+        let calls = calls.clone();
+
+        Router::new()
+            .route(
+                "/events",
+                post(|| async { ([(CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)], "data: foo\n\n") }),
+            )
+            .route(
+                "/echo",
+                post(move |body: String| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    match failure {
+                        Some(status) if call == 1 => status.into_response(),
+                        _ => (StatusCode::CREATED, format!("{call}:{body}")).into_response(),
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(state.clone(), idempotency))
+            .layer(middleware::from_fn(problem_instance))
+            .with_state(state)
+    }
+
+    fn request(key: Option<&str>, body: &'static str) -> Request {
+        let mut builder = Request::builder().method(Method::POST).uri("/echo");
+        if let Some(key) = key {
+            builder = builder.header(IDEMPOTENCY_KEY, key);
+        }
+
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    fn request_from(ip: [u8; 4], uri: &str) -> Request {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(IDEMPOTENCY_KEY, "key")
+            .body(Body::from("foo"))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((ip, 4242))));
+
+        request
+    }
+
+    async fn text(response: Response) -> String {
+        String::from_utf8(
+            response
+                .into_body()
+                .collect()
+                .await
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn replay_ok() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, None);
+
+        let first = router
+            .clone()
+            .oneshot(request(Some("key"), "foo"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED).is_none());
+        assert_eq!(text(first).await, "1:foo");
+
+        let second = router.oneshot(request(Some("key"), "foo")).await.unwrap();
+        assert_eq!(second.status(), StatusCode::CREATED);
+        assert_eq!(second.headers().get(IDEMPOTENT_REPLAYED).unwrap(), "true");
+        assert_eq!(text(second).await, "1:foo");
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn without_key() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, None);
+
+        router.clone().oneshot(request(None, "foo")).await.unwrap();
+        let response = router.oneshot(request(None, "foo")).await.unwrap();
+
+        assert_eq!(text(response).await, "2:foo");
+    }
+
+    #[tokio::test]
+    async fn key_reused() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, None);
+
+        router
+            .clone()
+            .oneshot(request(Some("key"), "foo"))
+            .await
+            .unwrap();
+        let response = router.oneshot(request(Some("key"), "bar")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let problem =
+            from_slice::<HttpProblem>(&response.into_body().collect().await.unwrap().to_bytes())
+                .unwrap();
+        println!("{problem:#?}");
+
+        assert_eq!(problem.code, "IdempotencyError::KeyReused");
+        assert_eq!(problem.instance.as_deref(), Some("/echo"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[rstest]
+    #[case::server_error(StatusCode::INTERNAL_SERVER_ERROR, "2:foo", false)]
+    #[case::client_error(StatusCode::NOT_FOUND, "", true)]
+    #[tokio::test]
+    async fn retry_after_failure(
+        #[case] failure: StatusCode,
+        #[case] expected: &str,
+        #[case] replayed: bool,
+    ) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, Some(failure));
+
+        let first = router
+            .clone()
+            .oneshot(request(Some("key"), "foo"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), failure);
+
+        let second = router.oneshot(request(Some("key"), "foo")).await.unwrap();
+        assert_eq!(second.headers().contains_key(IDEMPOTENT_REPLAYED), replayed);
+        assert_eq!(text(second).await, expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::whitespace("foo bar")]
+    #[case::too_long(&"a".repeat(MAX_KEY_LENGTH + 1))]
+    #[tokio::test]
+    async fn invalid_key(#[case] key: &str) {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let response = router(&calls, None)
+            .oneshot(request(Some(key), "foo"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn scoped_per_client() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, None);
+
+        router
+            .clone()
+            .oneshot(request_from([10, 0, 0, 1], "/echo"))
+            .await
+            .unwrap();
+        let other = router
+            .clone()
+            .oneshot(request_from([10, 0, 0, 2], "/echo"))
+            .await
+            .unwrap();
+        assert!(other.headers().get(IDEMPOTENT_REPLAYED).is_none());
+        assert_eq!(text(other).await, "2:foo");
+
+        let retry = router
+            .oneshot(request_from([10, 0, 0, 1], "/echo"))
+            .await
+            .unwrap();
+        assert_eq!(retry.headers().get(IDEMPOTENT_REPLAYED).unwrap(), "true");
+        assert_eq!(text(retry).await, "1:foo");
+    }
+
+    #[tokio::test]
+    async fn event_stream_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = router(&calls, None);
+
+        router
+            .clone()
+            .oneshot(request_from([10, 0, 0, 1], "/events"))
+            .await
+            .unwrap();
+        let retry = router
+            .oneshot(request_from([10, 0, 0, 1], "/events"))
+            .await
+            .unwrap();
+
+        assert_eq!(retry.status(), StatusCode::OK);
+        assert!(retry.headers().get(IDEMPOTENT_REPLAYED).is_none());
+    }
+
+    #[tokio::test]
+    async fn abandoned_reservation_released() {
+        let store = Arc::new(IdempotencyMemoryStore::new());
+        let ttl = Duration::from_secs(60);
+
+        store.begin("key", "foo", ttl).await.unwrap();
+        drop(Reservation::new(store.clone(), "key".to_owned()));
+        tokio::task::yield_now().await;
+
+        assert_eq!(store.begin("key", "foo", ttl).await, Ok(None));
+    }
+}
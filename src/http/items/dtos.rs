@@ -1,41 +1,163 @@
+use async_graphql::{Context, InputObject, Object, Result as GraphQLResult};
+use axum::http::StatusCode;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::dao::{
-    CreateItemParams,
-    CreateItemParamsBuilderError,
-    CreateItemsParamsBuilder,
-    Item,
-    UpdateItemParams,
-    UpdateItemParamsBuilder,
-    UpdateItemParamsBuilderError,
+use super::errors::BatchRequestError;
+use crate::{
+    dao::{
+        CreateItemParams,
+        CreateItemParamsBuilderError,
+        CreateItemsParamsBuilder,
+        GetUserError,
+        Item,
+        ItemOperation,
+        ItemOperationOutcome,
+        Precondition,
+        UpdateItemParams,
+        UpdateItemParamsBuilder,
+        UpdateItemParamsBuilderError,
+    },
+    http::{
+        common::{AppError, HttpProblem, IfMatch, Patch},
+        graphql::graphql_error,
+        state::AppState,
+        users::HttpUser,
+    },
 };
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(as = Item)]
 pub struct HttpItem {
+    #[schema(example = "d06cd939-f13b-4524-83a6-f025639235e9")]
     id: Uuid,
+    #[schema(example = "Sleeping Bag")]
     name: String,
+    #[schema(example = "Calgary, AB")]
     location: String,
+    /// Id of the user, who owns the item, since it was created by the user while logged in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_id: Option<Uuid>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
 
+impl HttpItem {
+    /// Column layout of CSV listings, new columns may only be appended
+    pub const CSV_COLUMNS: [&'static str; 5] =
+        ["id", "name", "location", "created_at", "updated_at"];
+    // Same as serde representation of timestamps in JSON
+    const CSV_TIMESTAMP_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.f";
+
+    pub fn csv_record(&self) -> [String; 5] {
+        [
+            self.id.to_string(),
+            self.name.clone(),
+            self.location.clone(),
+            self.created_at
+                .format(Self::CSV_TIMESTAMP_FORMAT)
+                .to_string(),
+            self.updated_at
+                .format(Self::CSV_TIMESTAMP_FORMAT)
+                .to_string(),
+        ]
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub fn owner_id(&self) -> Option<Uuid> {
+        self.owner_id
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
+
+// Deriving `SimpleObject` would clash with the getters above, same as for `HttpUser`
+#[Object(name = "Item")]
+impl HttpItem {
+    #[graphql(name = "id")]
+    async fn resolve_id(&self) -> Uuid {
+        self.id
+    }
+
+    #[graphql(name = "name")]
+    async fn resolve_name(&self) -> &str {
+        &self.name
+    }
+
+    #[graphql(name = "location")]
+    async fn resolve_location(&self) -> &str {
+        &self.location
+    }
+
+    #[graphql(name = "ownerId")]
+    async fn resolve_owner_id(&self) -> Option<Uuid> {
+        self.owner_id
+    }
+
+    /// User, who owns the item, absent if there is no owner or the user is deleted
+    #[graphql(name = "owner")]
+    async fn resolve_owner(&self, ctx: &Context<'_>) -> GraphQLResult<Option<HttpUser>> {
+        let Some(owner_id) = self.owner_id else {
+            return Ok(None);
+        };
+
+        match ctx.data_unchecked::<AppState>().users.get(owner_id).await {
+            Ok(user) => Ok(Some(user.into())),
+            Err(GetUserError::NoSuchEntity { .. }) => Ok(None),
+            Err(err) => Err(graphql_error(err)),
+        }
+    }
+
+    #[graphql(name = "createdAt")]
+    async fn resolve_created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    #[graphql(name = "updatedAt")]
+    async fn resolve_updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
+
 impl From<Item> for HttpItem {
     fn from(value: Item) -> Self {
         HttpItem {
             id: value.id(),
             name: value.name().to_owned(),
             location: value.location().to_owned(),
+            owner_id: value.owner_id(),
             created_at: value.created_at(),
             updated_at: value.updated_at(),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject, ToSchema)]
+#[graphql(name = "CreateItemInput")]
+#[schema(as = CreateItemBody)]
 pub struct HttpCreateItemParams {
+    #[schema(example = "Sleeping Bag", min_length = 1, max_length = 128)]
     name: String,
+    #[schema(example = "Calgary, AB", min_length = 1, max_length = 128)]
     location: String,
 }
 
@@ -50,9 +172,13 @@ impl TryInto<CreateItemParams> for HttpCreateItemParams {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject, ToSchema)]
+#[graphql(name = "UpdateItemInput")]
+#[schema(as = UpdateItemBody)]
 pub struct HttpUpdateItemParams {
+    #[schema(example = "Sleeping Bag", min_length = 1, max_length = 128)]
     name: String,
+    #[schema(example = "Calgary, AB", min_length = 1, max_length = 128)]
     location: String,
 }
 
@@ -66,3 +192,184 @@ impl TryInto<UpdateItemParams> for HttpUpdateItemParams {
             .build()
     }
 }
+
+/// JSON Merge Patch, absent members are kept and `null` removes them
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(as = PatchItemBody)]
+pub struct HttpPatchItemParams {
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "Sleeping Bag")]
+    name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "Calgary, AB")]
+    location: Patch<String>,
+}
+
+impl HttpPatchItemParams {
+    /// Full update of the given entity, removed members fail the builder as not set
+    pub fn apply(self, entity: &Item) -> Result<UpdateItemParams, UpdateItemParamsBuilderError> {
+        let mut builder = UpdateItemParamsBuilder::new();
+
+        if let Some(name) = self.name.apply(entity.name().to_owned()) {
+            builder = builder.name(name);
+        }
+
+        if let Some(location) = self.location.apply(entity.location().to_owned()) {
+            builder = builder.location(location);
+        }
+
+        builder.build()
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(as = BatchBody)]
+pub struct HttpBatchRequest {
+    /// Whether to apply either all of operations or none of them, not every storage supports it
+    #[serde(default)]
+    pub atomic: bool,
+    pub operations: Vec<HttpItemOperation>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "lowercase")]
+#[schema(as = BatchOperation)]
+pub enum HttpItemOperation {
+    Create(HttpCreateItemParams),
+    Update {
+        id: Uuid,
+        name: String,
+        location: String,
+        /// Same as If-Match header of update request
+        if_match: Option<String>,
+    },
+    Delete {
+        id: Uuid,
+        /// Same as If-Match header of delete request
+        if_match: Option<String>,
+    },
+}
+
+impl TryFrom<HttpItemOperation> for ItemOperation {
+    type Error = AppError;
+
+    fn try_from(value: HttpItemOperation) -> Result<Self, Self::Error> {
+        let precondition = |if_match: Option<String>| {
+            if_match
+                .map_or(Ok(IfMatch(Precondition::None)), |x| IfMatch::parse(&x))
+                .map(|IfMatch(x)| x)
+        };
+
+        Ok(match value {
+            HttpItemOperation::Create(params) => ItemOperation::Create {
+                params: params.try_into()?,
+            },
+            HttpItemOperation::Update {
+                id,
+                name,
+                location,
+                if_match,
+            } => ItemOperation::Update {
+                id,
+                params: HttpUpdateItemParams { name, location }.try_into()?,
+                precondition: precondition(if_match)?,
+            },
+            HttpItemOperation::Delete { id, if_match } => ItemOperation::Delete {
+                id,
+                precondition: precondition(if_match)?,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = BatchResult)]
+pub struct HttpBatchResult {
+    /// Status, which the operation would have if requested separately.
+    /// 424 if it wasn't applied, because another operation of atomic batch failed
+    #[schema(example = 201)]
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<HttpItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<HttpProblem>,
+}
+
+impl From<ItemOperationOutcome> for HttpBatchResult {
+    fn from(value: ItemOperationOutcome) -> Self {
+        let (status, id, item) = match value {
+            ItemOperationOutcome::Created(item) => (StatusCode::CREATED, item.id(), Some(item)),
+            ItemOperationOutcome::Updated(item) => (StatusCode::OK, item.id(), Some(item)),
+            ItemOperationOutcome::Deleted(item) => (StatusCode::NO_CONTENT, item.id(), None),
+        };
+
+        HttpBatchResult {
+            status: status.as_u16(),
+            id: Some(id),
+            item: item.map(Into::into),
+            error: None,
+        }
+    }
+}
+
+impl From<AppError> for HttpBatchResult {
+    fn from(value: AppError) -> Self {
+        HttpBatchResult {
+            status: value.status_code.as_u16(),
+            id: None,
+            item: None,
+            error: Some(value.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = BatchResponse)]
+pub struct HttpBatchResponse {
+    pub results: Vec<HttpBatchResult>,
+}
+
+impl HttpBatchResponse {
+    /// Response to an atomic batch, none of which operations were applied due to a failed one
+    pub fn rolled_back(size: usize, index: usize, error: AppError) -> Self {
+        let mut results: Vec<HttpBatchResult> = (0..size)
+            .map(|_| AppError::from(BatchRequestError::NotApplied { index }).into())
+            .collect();
+        results[index] = error.into();
+
+        HttpBatchResponse { results }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::*;
+    use crate::dao::ItemBuilder;
+
+    #[test]
+    fn patch() {
+        let entity = ItemBuilder::new()
+            .name("Sleeping Bag".to_owned())
+            .location("Calgary, AB".to_owned())
+            .build()
+            .unwrap();
+
+        let params = from_str::<HttpPatchItemParams>(r#"{"location":"Banff, AB"}"#)
+            .unwrap()
+            .apply(&entity)
+            .unwrap();
+
+        assert_eq!(params.name(), "Sleeping Bag");
+        assert_eq!(params.location(), "Banff, AB");
+
+        let result = from_str::<HttpPatchItemParams>(r#"{"name":null}"#)
+            .unwrap()
+            .apply(&entity);
+
+        assert_eq!(result, Err(UpdateItemParamsBuilderError::NameNotSet));
+    }
+}
@@ -1,30 +1,38 @@
 use axum::http::StatusCode;
+use thiserror::Error;
 
 use crate::{
     dao::{
+        BatchItemsError,
         CreateItemError,
         CreateItemParamsBuilderError,
         DeleteItemError,
+        ErrorVariant,
         GetItemError,
+        ItemOperationError,
         ItemsHealthError,
         ListItemsError,
         UpdateItemError,
         UpdateItemParamsBuilderError,
     },
-    http::common::AppError,
+    http::common::{AppError, CsvError},
 };
 
 impl From<CreateItemError> for AppError {
     fn from(value: CreateItemError) -> Self {
-        let status_code = match value {
-            CreateItemError::InvalidParams => StatusCode::UNPROCESSABLE_ENTITY,
-            CreateItemError::AlreadyExists { id: _ } => StatusCode::CONFLICT,
-            CreateItemError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        let (status_code, violations) = match &value {
+            CreateItemError::InvalidParams { violations } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, violations.clone())
+            }
+            CreateItemError::AlreadyExists { id: _ } => (StatusCode::CONFLICT, Vec::new()),
+            CreateItemError::UnexpectedError => (StatusCode::INTERNAL_SERVER_ERROR, Vec::new()),
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations,
         }
     }
 }
@@ -33,12 +41,15 @@ impl From<DeleteItemError> for AppError {
     fn from(value: DeleteItemError) -> Self {
         let status_code = match value {
             DeleteItemError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
+            DeleteItemError::PreconditionFailed { id: _ } => StatusCode::PRECONDITION_FAILED,
             DeleteItemError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -52,7 +63,9 @@ impl From<GetItemError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -65,7 +78,9 @@ impl From<ItemsHealthError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -78,22 +93,31 @@ impl From<ListItemsError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
 
 impl From<UpdateItemError> for AppError {
     fn from(value: UpdateItemError) -> Self {
-        let status_code = match value {
-            UpdateItemError::InvalidParams => StatusCode::UNPROCESSABLE_ENTITY,
-            UpdateItemError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
-            UpdateItemError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        let (status_code, violations) = match &value {
+            UpdateItemError::InvalidParams { violations } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, violations.clone())
+            }
+            UpdateItemError::NoSuchEntity { id: _ } => (StatusCode::NOT_FOUND, Vec::new()),
+            UpdateItemError::PreconditionFailed { id: _ } => {
+                (StatusCode::PRECONDITION_FAILED, Vec::new())
+            }
+            UpdateItemError::UnexpectedError => (StatusCode::INTERNAL_SERVER_ERROR, Vec::new()),
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations,
         }
     }
 }
@@ -107,7 +131,9 @@ impl From<CreateItemParamsBuilderError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -120,7 +146,196 @@ impl From<UpdateItemParamsBuilderError> for AppError {
         };
         Self {
             status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<ItemOperationError> for AppError {
+    fn from(value: ItemOperationError) -> Self {
+        match value {
+            ItemOperationError::Create(error) => error.into(),
+            ItemOperationError::Update(error) => error.into(),
+            ItemOperationError::Delete(error) => error.into(),
+        }
+    }
+}
+
+impl From<BatchItemsError> for AppError {
+    fn from(value: BatchItemsError) -> Self {
+        let status_code = match value {
+            BatchItemsError::Unsupported => StatusCode::NOT_IMPLEMENTED,
+            BatchItemsError::OperationFailed { index: _, error: _ } => StatusCode::CONFLICT,
+            BatchItemsError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum BatchRequestError {
+    #[error("Batch of {size} operations exceeds the limit of {max}")]
+    TooLarge { size: usize, max: usize },
+    #[error("Operation wasn't applied, because operation #{index} failed")]
+    NotApplied { index: usize },
+}
+
+impl ErrorVariant for BatchRequestError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "BatchRequestError::TooLarge",
+            Self::NotApplied { .. } => "BatchRequestError::NotApplied",
+        }
+    }
+}
+
+impl From<BatchRequestError> for AppError {
+    fn from(value: BatchRequestError) -> Self {
+        let status_code = match value {
+            BatchRequestError::TooLarge { size: _, max: _ } => StatusCode::PAYLOAD_TOO_LARGE,
+            BatchRequestError::NotApplied { index: _ } => StatusCode::FAILED_DEPENDENCY,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ListFormatError {
+    #[error("None of listing formats (JSON, CSV, NDJSON) is acceptable for '{accept}'")]
+    NotAcceptable { accept: String },
+    #[error("Cannot serialize listing. This error was a direct following of: {internal}")]
+    Serialization { internal: String },
+}
+
+impl ErrorVariant for ListFormatError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NotAcceptable { .. } => "ListFormatError::NotAcceptable",
+            Self::Serialization { .. } => "ListFormatError::Serialization",
+        }
+    }
+}
+
+impl From<serde_json::Error> for ListFormatError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serialization {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<ListFormatError> for AppError {
+    fn from(value: ListFormatError) -> Self {
+        let status_code = match value {
+            ListFormatError::NotAcceptable { accept: _ } => StatusCode::NOT_ACCEPTABLE,
+            ListFormatError::Serialization { internal: _ } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ImportError {
+    #[error(
+        "Content type '{content_type}' is not supported, use 'text/csv' or 'application/json'"
+    )]
+    UnsupportedMediaType { content_type: String },
+    #[error("Column '{column}' is missing in CSV header")]
+    MissingColumn { column: &'static str },
+    #[error("Cannot read JSON array of rows. This error was a direct following of: {internal}")]
+    MalformedJson { internal: String },
+    #[error(transparent)]
+    Csv(#[from] CsvError),
+}
+
+impl ErrorVariant for ImportError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::UnsupportedMediaType { .. } => "ImportError::UnsupportedMediaType",
+            Self::MissingColumn { .. } => "ImportError::MissingColumn",
+            Self::MalformedJson { .. } => "ImportError::MalformedJson",
+            Self::Csv(error) => error.variant(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ImportError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::MalformedJson {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<ImportError> for AppError {
+    fn from(value: ImportError) -> Self {
+        let status_code = match value {
+            ImportError::UnsupportedMediaType { content_type: _ } => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            ImportError::MissingColumn { column: _ } => StatusCode::UNPROCESSABLE_ENTITY,
+            ImportError::MalformedJson { internal: _ } => StatusCode::BAD_REQUEST,
+            ImportError::Csv(error) => return error.into(),
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ItemEventsError {
+    #[error("Last-Event-ID header must be an id of a previously received event")]
+    InvalidLastEventId,
+}
+
+impl ErrorVariant for ItemEventsError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidLastEventId => "ItemEventsError::InvalidLastEventId",
+        }
+    }
+}
+
+impl From<ItemEventsError> for AppError {
+    fn from(value: ItemEventsError) -> Self {
+        let status_code = match value {
+            ItemEventsError::InvalidLastEventId => StatusCode::BAD_REQUEST,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -0,0 +1,417 @@
+use std::{
+    collections::VecDeque,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use async_graphql::{Enum, Object};
+use axum::response::sse::Event;
+use futures::{stream, Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::dtos::HttpItem;
+use crate::{
+    dao::{Item, ItemOperationOutcome},
+    webhooks::{WebhookEventType, Webhooks},
+};
+
+/// Event sent instead of the missed ones, when they are no longer in replay buffer
+const RESET_EVENT: &str = "reset";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ItemEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ItemEventKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Created => "item.created",
+            Self::Updated => "item.updated",
+            Self::Deleted => "item.deleted",
+        }
+    }
+}
+
+impl From<ItemEventKind> for WebhookEventType {
+    fn from(value: ItemEventKind) -> Self {
+        match value {
+            ItemEventKind::Created => Self::ItemCreated,
+            ItemEventKind::Updated => Self::ItemUpdated,
+            ItemEventKind::Deleted => Self::ItemDeleted,
+        }
+    }
+}
+
+/// Data of `item.created`, `item.updated` and `item.deleted` events.
+/// Event id is a sequence number to resume from with Last-Event-ID
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(as = ItemEvent)]
+pub struct HttpItemEvent {
+    #[serde(skip)]
+    sequence: u64,
+    #[serde(skip)]
+    kind: ItemEventKind,
+    /// Owner of the item, who is the only one to get the event
+    #[serde(skip)]
+    owner_id: Option<Uuid>,
+    #[schema(example = "d06cd939-f13b-4524-83a6-f025639235e9")]
+    id: Uuid,
+    /// Absent for deleted items
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<HttpItem>,
+}
+
+impl HttpItemEvent {
+    pub fn kind(&self) -> ItemEventKind {
+        self.kind
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn item(&self) -> Option<&HttpItem> {
+        self.item.as_ref()
+    }
+
+    fn is_visible_to(&self, user_id: Uuid) -> bool {
+        self.owner_id == Some(user_id)
+    }
+
+    fn into_sse(self) -> Result<Event, axum::Error> {
+        Event::default()
+            .id(self.sequence.to_string())
+            .event(self.kind.name())
+            .json_data(self)
+    }
+}
+
+#[Object(name = "ItemEvent")]
+impl HttpItemEvent {
+    #[graphql(name = "kind")]
+    async fn resolve_kind(&self) -> ItemEventKind {
+        self.kind
+    }
+
+    #[graphql(name = "id")]
+    async fn resolve_id(&self) -> Uuid {
+        self.id
+    }
+
+    #[graphql(name = "item")]
+    async fn resolve_item(&self) -> Option<&HttpItem> {
+        self.item.as_ref()
+    }
+}
+
+fn reset() -> Event {
+    // Browsers don't dispatch events without data
+    Event::default().event(RESET_EVENT).data("{}")
+}
+
+struct Replay {
+    last_sequence: u64,
+    buffer: VecDeque<HttpItemEvent>,
+    /// Taken away once the feed is closed, which ends streams of every subscriber
+    sender: Option<broadcast::Sender<HttpItemEvent>>,
+}
+
+impl Replay {
+    /// Receiver of live events, which is closed right away if the feed is
+    fn receiver(&self) -> broadcast::Receiver<HttpItemEvent> {
+        match &self.sender {
+            Some(sender) => sender.subscribe(),
+            None => broadcast::channel(1).1,
+        }
+    }
+}
+
+/// In-process feed of item changes, which keeps the latest events for resuming subscribers
+#[derive(Clone)]
+pub struct ItemEvents {
+    replay: Arc<Mutex<Replay>>,
+    capacity: usize,
+}
+
+impl ItemEvents {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.get());
+
+        Self {
+            replay: Arc::new(Mutex::new(Replay {
+                last_sequence: 0,
+                buffer: VecDeque::with_capacity(capacity.get()),
+                sender: Some(sender),
+            })),
+            capacity: capacity.get(),
+        }
+    }
+
+    pub fn created(&self, item: &Item) {
+        self.publish(ItemEventKind::Created, item, true);
+    }
+
+    pub fn updated(&self, item: &Item) {
+        self.publish(ItemEventKind::Updated, item, true);
+    }
+
+    pub fn deleted(&self, item: &Item) {
+        self.publish(ItemEventKind::Deleted, item, false);
+    }
+
+    pub fn applied(&self, outcome: &ItemOperationOutcome) {
+        match outcome {
+            ItemOperationOutcome::Created(item) => self.created(item),
+            ItemOperationOutcome::Updated(item) => self.updated(item),
+            ItemOperationOutcome::Deleted(item) => self.deleted(item),
+        }
+    }
+
+    fn publish(&self, kind: ItemEventKind, item: &Item, with_item: bool) {
+        // Sending under the lock keeps sequence of broadcast the same as of replay buffer
+        let mut replay = self.replay.lock().unwrap();
+        let Some(sender) = replay.sender.clone() else {
+            return;
+        };
+        replay.last_sequence += 1;
+        let event = HttpItemEvent {
+            sequence: replay.last_sequence,
+            kind,
+            owner_id: item.owner_id(),
+            id: item.id(),
+            item: with_item.then(|| item.clone().into()),
+        };
+
+        if replay.buffer.len() == self.capacity {
+            replay.buffer.pop_front();
+        }
+        replay.buffer.push_back(event.clone());
+        // There may be no subscribers at all, which is fine
+        let _ = sender.send(event);
+    }
+
+    /// Ends streams of every subscriber, e.g. on shutdown, when they would keep connections
+    /// open forever. Events published afterwards are dropped
+    pub fn close(&self) {
+        self.replay.lock().unwrap().sender.take();
+    }
+
+    /// Stream of events of the user's items published after `last_event_id`. Buffered events
+    /// are replayed first, and a reset event is sent if some of them are already gone
+    pub fn subscribe(
+        &self,
+        user_id: Uuid,
+        last_event_id: Option<u64>,
+    ) -> impl Stream<Item = Result<Event, axum::Error>> {
+        let replay = self.replay.lock().unwrap();
+        let receiver = replay.receiver();
+
+        let mut backlog = Vec::new();
+        if let Some(last_event_id) = last_event_id {
+            let oldest = replay
+                .buffer
+                .front()
+                .map_or(replay.last_sequence + 1, |x| x.sequence);
+            // Sequence also restarts along with server
+            if last_event_id + 1 < oldest || last_event_id > replay.last_sequence {
+                backlog.push(Ok(reset()));
+            }
+            backlog.extend(
+                replay
+                    .buffer
+                    .iter()
+                    .filter(|x| x.sequence > last_event_id && x.is_visible_to(user_id))
+                    .map(|x| x.clone().into_sse()),
+            );
+        }
+        drop(replay);
+
+        let live = stream::unfold(receiver, move |mut receiver| async move {
+            let event = loop {
+                match receiver.recv().await {
+                    Ok(event) if event.is_visible_to(user_id) => break event.into_sse(),
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(_)) => break Ok(reset()),
+                    Err(RecvError::Closed) => return None,
+                }
+            };
+
+            Some((event, receiver))
+        });
+
+        stream::iter(backlog).chain(live)
+    }
+
+    /// Events of the user's items published from now on. Unlike `subscribe`, events missed by
+    /// a lagging subscriber are silently skipped
+    pub fn changes(&self, user_id: Uuid) -> impl Stream<Item = HttpItemEvent> {
+        let receiver = self.replay.lock().unwrap().receiver();
+
+        stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.is_visible_to(user_id) => return Some((event, receiver)),
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Passes every published event on to webhooks of the item owner, until the feed is closed
+    pub async fn forward(self, webhooks: Webhooks) {
+        let mut receiver = self.replay.lock().unwrap().receiver();
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    // Items without owner have nobody to be notified
+                    if let Some(owner_id) = event.owner_id {
+                        webhooks.notify(owner_id, event.kind.into(), &event);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    error!("Item events fell behind, {skipped} of them are not sent to webhooks");
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use fake::{Fake, Faker};
+    use tokio::time::timeout;
+
+    use super::*;
+    use crate::{
+        dao::{CreateItemParams, ItemBuilder},
+        http::AppState,
+    };
+
+    const OWNER: Uuid = Uuid::from_u128(42);
+
+    fn item() -> Item {
+        owned_item(OWNER)
+    }
+
+    fn owned_item(owner_id: Uuid) -> Item {
+        let params: CreateItemParams = Faker.fake();
+
+        ItemBuilder::new()
+            .name(params.name().to_owned())
+            .location(params.location().to_owned())
+            .owner_id(Some(owner_id))
+            .build()
+            .unwrap()
+    }
+
+    /// Renders events, which are ready without waiting for new ones
+    async fn ready(events: impl Stream<Item = Result<Event, axum::Error>>) -> Vec<String> {
+        let mut events = Box::pin(events);
+        let mut result = Vec::new();
+        while let Ok(Some(event)) = timeout(Duration::from_millis(50), events.next()).await {
+            result.push(format!("{:?}", event.unwrap()));
+        }
+
+        result
+    }
+
+    #[tokio::test]
+    async fn live() {
+        let events = ItemEvents::new(NonZeroUsize::new(8).unwrap());
+        let subscription = events.subscribe(OWNER, None);
+        let item = item();
+
+        events.created(&item);
+        events.deleted(&item);
+
+        let result = ready(subscription).await;
+        println!("{result:#?}");
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].contains("item.created"));
+        assert!(result[1].contains("item.deleted"));
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let events = ItemEvents::new(NonZeroUsize::new(8).unwrap());
+        let item = item();
+        events.created(&item);
+        events.updated(&item);
+        events.deleted(&item);
+
+        let result = ready(events.subscribe(OWNER, Some(1))).await;
+        println!("{result:#?}");
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].contains("item.updated"));
+        assert!(result[1].contains("item.deleted"));
+    }
+
+    #[tokio::test]
+    async fn resume_truncated() {
+        let events = ItemEvents::new(NonZeroUsize::new(2).unwrap());
+        let item = item();
+        for _ in 0..4 {
+            events.updated(&item);
+        }
+
+        let result = ready(events.subscribe(OWNER, Some(1))).await;
+        println!("{result:#?}");
+
+        assert_eq!(result.len(), 3);
+        assert!(result[0].contains(RESET_EVENT));
+    }
+
+    #[tokio::test]
+    async fn scoped() {
+        let events = ItemEvents::new(NonZeroUsize::new(8).unwrap());
+        let other = Uuid::new_v4();
+        events.created(&owned_item(other));
+        let subscription = events.subscribe(OWNER, Some(0));
+        let item = item();
+
+        events.created(&item);
+        events.updated(&owned_item(other));
+        events.deleted(&item);
+
+        // Changes of other user's items are neither replayed nor streamed
+        let result = ready(subscription).await;
+        println!("{result:#?}");
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].contains("item.created") && result[0].contains(&item.id().to_string()));
+        assert!(result[1].contains("item.deleted"));
+    }
+
+    #[tokio::test]
+    async fn close() {
+        let events = ItemEvents::new(NonZeroUsize::new(8).unwrap());
+        let subscription = events.subscribe(OWNER, None);
+        let changes = events.changes(OWNER);
+        let forward = tokio::spawn(events.clone().forward(AppState::default().webhooks));
+
+        events.close();
+        events.created(&item());
+
+        // Streams end instead of waiting for events, which would never come
+        let ended = async {
+            assert_eq!(subscription.count().await, 0);
+            assert_eq!(changes.count().await, 0);
+            assert_eq!(events.subscribe(OWNER, Some(0)).count().await, 0);
+            forward.await.unwrap();
+        };
+        timeout(Duration::from_secs(1), ended).await.unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+use axum::{
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        HeaderMap,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use super::{dtos::HttpItem, errors::ListFormatError};
+use crate::http::common::csv;
+
+/// Representation of item listings, chosen by `format` query parameter or `Accept` header
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFormat {
+    #[default]
+    Json,
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HttpListFormatParams {
+    /// Representation of listing, takes precedence over Accept header
+    #[param(inline)]
+    pub format: Option<ListFormat>,
+}
+
+impl ListFormat {
+    // Ties between equally acceptable formats are resolved in favour of the earlier one
+    const ALL: [Self; 3] = [Self::Json, Self::Csv, Self::Ndjson];
+
+    fn media_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    /// Explicitly requested format wins, otherwise the most acceptable one is picked
+    pub fn negotiate(
+        requested: Option<Self>,
+        headers: &HeaderMap,
+    ) -> Result<Self, ListFormatError> {
+        if let Some(format) = requested {
+            return Ok(format);
+        }
+        let Some(accept) = headers.get(ACCEPT) else {
+            return Ok(Self::default());
+        };
+        let accept = accept.to_str().unwrap_or_default();
+
+        Self::ALL
+            .into_iter()
+            .rev()
+            .map(|format| (format, format.quality(accept)))
+            .filter(|(_, quality)| *quality > 0.0)
+            .max_by(|(_, left), (_, right)| left.total_cmp(right))
+            .map(|(format, _)| format)
+            .ok_or_else(|| ListFormatError::NotAcceptable {
+                accept: accept.to_owned(),
+            })
+    }
+
+    /// Quality of the most specific media range in `Accept` header, which matches the format
+    fn quality(self, accept: &str) -> f32 {
+        let (kind, subtype) = self.media_type().split_once('/').unwrap_or_default();
+
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let media_range = params.next()?.trim().to_ascii_lowercase();
+                let specificity = match media_range.split_once('/')? {
+                    ("*", "*") => 1,
+                    (x, "*") if x == kind => 2,
+                    (x, y) if x == kind && y == subtype => 3,
+                    _ => return None,
+                };
+                let quality = params
+                    .filter_map(|x| x.trim().strip_prefix("q="))
+                    .find_map(|x| x.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                Some((specificity, quality))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0.0, |(_, quality)| quality)
+    }
+
+    pub fn render(self, items: &[HttpItem]) -> Result<Response, ListFormatError> {
+        let body = match self {
+            Self::Json => return Ok(Json(items).into_response()),
+            Self::Csv => {
+                let mut body = String::new();
+                csv::write_record(&mut body, HttpItem::CSV_COLUMNS);
+                for item in items {
+                    csv::write_record(&mut body, item.csv_record().iter().map(String::as_str));
+                }
+
+                body
+            }
+            Self::Ndjson => items
+                .iter()
+                .map(|x| serde_json::to_string(x).map(|x| x + "\n"))
+                .collect::<Result<String, _>>()?,
+        };
+
+        Ok(([(CONTENT_TYPE, self.content_type())], body).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::missing(None, None, Some(ListFormat::Json))]
+    #[case::query(Some(ListFormat::Csv), Some("application/json"), Some(ListFormat::Csv))]
+    #[case::any(None, Some("*/*"), Some(ListFormat::Json))]
+    #[case::csv(None, Some("text/csv"), Some(ListFormat::Csv))]
+    #[case::text(None, Some("text/*"), Some(ListFormat::Csv))]
+    #[case::ndjson(None, Some("application/x-ndjson"), Some(ListFormat::Ndjson))]
+    #[case::quality(
+        None,
+        Some("application/json;q=0.5, text/csv;q=0.9"),
+        Some(ListFormat::Csv)
+    )]
+    #[case::excluded(None, Some("*/*, application/json;q=0"), Some(ListFormat::Csv))]
+    #[case::unsupported(None, Some("application/xml"), None)]
+    fn negotiate(
+        #[case] requested: Option<ListFormat>,
+        #[case] accept: Option<&'static str>,
+        #[case] expected: Option<ListFormat>,
+    ) {
+        let mut headers = HeaderMap::new();
+        if let Some(accept) = accept {
+            headers.insert(ACCEPT, HeaderValue::from_static(accept));
+        }
+
+        assert_eq!(ListFormat::negotiate(requested, &headers).ok(), expected);
+    }
+}
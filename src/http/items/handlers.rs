@@ -1,28 +1,156 @@
 use axum::{
     debug_handler,
-    extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
-    response::IntoResponse,
-    Json,
+    extract::State,
+    http::{
+        header::{ACCEPT, VARY},
+        HeaderMap,
+        HeaderName,
+        StatusCode,
+    },
+    response::{
+        sse::{KeepAlive, Sse},
+        IntoResponse,
+        Response,
+    },
+    routing::{get, post},
+    Router,
 };
+use tracing::debug;
+use utoipa::OpenApi;
 use uuid::Uuid;
 
 use super::{
-    dtos::{HttpCreateItemParams, HttpItem, HttpUpdateItemParams},
+    dtos::{
+        HttpBatchRequest,
+        HttpBatchResponse,
+        HttpCreateItemParams,
+        HttpItem,
+        HttpPatchItemParams,
+        HttpUpdateItemParams,
+    },
+    errors::{BatchRequestError, ItemEventsError},
+    events::HttpItemEvent,
+    formats::{HttpListFormatParams, ListFormat},
+    import::{HttpImportParams, HttpImportReport, HttpImportRow},
     state::AppState,
 };
 use crate::{
-    dao::Pagination,
-    http::common::{AppError, HttpPaginationParams},
+    dao::{
+        BatchItemsError,
+        CreateItemParams,
+        ItemOperation,
+        Pagination,
+        Precondition,
+        UpdateItemError,
+    },
+    http::{
+        authentication::{SessionCookie, SessionUser},
+        common::{
+            AppError,
+            ConditionalGet,
+            ETag,
+            HttpPaginationParams,
+            HttpProblem,
+            IfMatch,
+            Json,
+            LastModified,
+            Path,
+            Query,
+            PROBLEM_CONTENT_TYPE,
+        },
+        idempotency::IdempotencyKey,
+    },
 };
 
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+#[derive(Default)]
+pub struct ItemRouter {}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_items,
+        create_item,
+        batch_items,
+        import_items,
+        item_events,
+        get_item,
+        update_item,
+        patch_item,
+        delete_item,
+    ),
+    modifiers(&SessionCookie)
+)]
+pub struct ItemApi;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(HttpPaginationParams, HttpListFormatParams, ConditionalGet),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            headers(
+                ("pagination-page" = usize),
+                ("pagination-limit" = usize),
+                ("ETag" = String, description = "Version of the whole collection"),
+                ("Last-Modified" = String),
+            ),
+            content(
+                (Vec<HttpItem> = "application/json"),
+                (String = "text/csv", example = "id,name,location,created_at,updated_at\r\n"),
+                (String = "application/x-ndjson"),
+            ),
+        ),
+        (
+            status = 304,
+            description = "Not Modified",
+            headers(("ETag" = String), ("Last-Modified" = String)),
+        ),
+        (
+            status = 406,
+            description = "Not Acceptable, none of supported formats matches Accept header",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn list_items(
     Query(pagination_params): Query<HttpPaginationParams>,
+    Query(format_params): Query<HttpListFormatParams>,
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, AppError> {
+    headers: HeaderMap,
+    conditions: ConditionalGet,
+) -> Result<Response, AppError> {
+    let format = ListFormat::negotiate(format_params.format, &headers)?;
     let pagination: Pagination = pagination_params.try_into()?;
     let response_headers: HeaderMap = pagination.clone().try_into()?;
+    let modified_at = state.items.modified_at().await?;
+    let (etag, last_modified) = (ETag(modified_at), LastModified(modified_at));
+
+    let vary = [(VARY, ACCEPT.as_str())];
+
+    if conditions.is_not_modified(modified_at) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            response_headers,
+            etag,
+            last_modified,
+            vary,
+            (),
+        )
+            .into_response());
+    }
+
     let result: Vec<HttpItem> = state
         .items
         .list(pagination)
@@ -31,46 +159,889 @@ pub async fn list_items(
         .map(Into::into)
         .collect();
 
-    Ok((StatusCode::OK, response_headers, Json(result)))
+    Ok((
+        StatusCode::OK,
+        response_headers,
+        etag,
+        last_modified,
+        vary,
+        format.render(&result)?,
+    )
+        .into_response())
+}
+
+/// Server-Sent Events stream of changes of items, which are owned by the logged in user
+///
+/// Events missed since Last-Event-ID are replayed from a bounded buffer; if some of them are gone,
+/// a `reset` event is sent first, and client should refetch items
+#[utoipa::path(
+    get,
+    path = "/events",
+    params(("Last-Event-ID" = Option<u64>, Header)),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            body = HttpItemEvent,
+            content_type = "text/event-stream",
+        ),
+        (
+            status = 400,
+            description = "Bad Request, Last-Event-ID is not an event id",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized, session cookie is missing or expired",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn item_events(
+    SessionUser(user_id): SessionUser,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .map(|x| {
+            x.to_str()
+                .ok()
+                .and_then(|x| x.trim().parse::<u64>().ok())
+                .ok_or(ItemEventsError::InvalidLastEventId)
+        })
+        .transpose()?;
+
+    debug!("User {user_id} subscribed to item events since {last_event_id:?}");
+
+    Ok(
+        Sse::new(state.item_events.subscribe(user_id, last_event_id))
+            .keep_alive(KeepAlive::default()),
+    )
 }
 
+/// Create item, which is owned by the logged in user, if any
+#[utoipa::path(
+    post,
+    path = "",
+    params(IdempotencyKey),
+    request_body = HttpCreateItemParams,
+    responses(
+        (status = 201, description = "Created", body = HttpItem),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 409,
+            description = "Conflict",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn create_item(
+    user: Option<SessionUser>,
     State(state): State<AppState>,
     Json(params): Json<HttpCreateItemParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let result: HttpItem = state.items.create(params.try_into()?).await?.into();
+    let params: CreateItemParams = params.try_into()?;
+    let owner_id = user.map(|SessionUser(id)| id);
+    let entity = state.items.create(params.owned_by(owner_id)).await?;
+    state.item_events.created(&entity);
+    let result: HttpItem = entity.into();
 
     Ok((StatusCode::CREATED, Json(result)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{item_id}",
+    params(("item_id" = Uuid, Path), ConditionalGet),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            body = HttpItem,
+            headers(("ETag" = String), ("Last-Modified" = String)),
+        ),
+        (
+            status = 304,
+            description = "Not Modified",
+            headers(("ETag" = String), ("Last-Modified" = String)),
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn get_item(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, AppError> {
-    let result: HttpItem = state.items.get(id).await?.into();
+    conditions: ConditionalGet,
+) -> Result<Response, AppError> {
+    let entity = state.items.get(id).await?;
+    let (etag, last_modified) = (ETag(entity.updated_at()), LastModified(entity.updated_at()));
 
-    Ok((StatusCode::OK, Json(result)))
+    if conditions.is_not_modified(entity.updated_at()) {
+        return Ok((StatusCode::NOT_MODIFIED, etag, last_modified, ()).into_response());
+    }
+
+    let result: HttpItem = entity.into();
+
+    Ok((StatusCode::OK, etag, last_modified, Json(result)).into_response())
 }
 
+#[utoipa::path(
+    put,
+    path = "/{item_id}",
+    params(("item_id" = Uuid, Path), IfMatch),
+    request_body = HttpUpdateItemParams,
+    responses(
+        (status = 200, description = "OK", body = HttpItem, headers(("ETag" = String))),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn update_item(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
     Json(params): Json<HttpUpdateItemParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let result: HttpItem = state.items.update(id, params.try_into()?).await?.into();
+    let entity = state
+        .items
+        .update(id, params.try_into()?, precondition)
+        .await?;
+    state.item_events.updated(&entity);
+    let etag = ETag(entity.updated_at());
+    let result: HttpItem = entity.into();
+
+    Ok((StatusCode::OK, etag, Json(result)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{item_id}",
+    params(("item_id" = Uuid, Path), IfMatch),
+    request_body(content = HttpPatchItemParams, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "OK", body = HttpItem, headers(("ETag" = String))),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn patch_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
+    Json(patch): Json<HttpPatchItemParams>,
+) -> Result<impl IntoResponse, AppError> {
+    // Patch is applied to the version read, so a concurrent write is never overwritten.
+    // It's reapplied to the newer version, unless client asked for a particular one
+    let entity = loop {
+        let current = state.items.get(id).await?;
+        if !precondition.holds(current.updated_at()) {
+            return Err(UpdateItemError::PreconditionFailed { id }.into());
+        }
+
+        let params = patch.clone().apply(&current)?;
+        let version = Precondition::UpdatedAt(current.updated_at());
+
+        match state.items.update(id, params, version).await {
+            Err(UpdateItemError::PreconditionFailed { .. })
+                if precondition == Precondition::None => {}
+            result => break result?,
+        }
+    };
+    state.item_events.updated(&entity);
+    let etag = ETag(entity.updated_at());
+    let result: HttpItem = entity.into();
 
-    Ok((StatusCode::OK, Json(result)))
+    Ok((StatusCode::OK, etag, Json(result)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{item_id}",
+    params(("item_id" = Uuid, Path), IfMatch),
+    responses(
+        (status = 204, description = "Deleted"),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn delete_item(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
 ) -> Result<impl IntoResponse, AppError> {
-    state.items.delete(id).await?;
+    let entity = state.items.delete(id, precondition).await?;
+    state.item_events.deleted(&entity);
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Create, update and delete items in one request, results follow order of operations
+///
+/// Created items are owned by the logged in user, if any
+#[utoipa::path(
+    post,
+    path = "/batch",
+    params(IdempotencyKey),
+    request_body = HttpBatchRequest,
+    responses(
+        (status = 200, description = "OK", body = HttpBatchResponse),
+        (
+            status = 413,
+            description = "Payload Too Large",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 501,
+            description = "Not Implemented, storage doesn't support atomic batches",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn batch_items(
+    user: Option<SessionUser>,
+    State(state): State<AppState>,
+    Json(request): Json<HttpBatchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let size = request.operations.len();
+    let max = state.items_batch_max_size.get();
+    if size > max {
+        return Err(BatchRequestError::TooLarge { size, max }.into());
+    }
+
+    let owner_id = user.map(|SessionUser(id)| id);
+    let operations = request.operations.into_iter().map(|x| {
+        ItemOperation::try_from(x).map(|x| match x {
+            ItemOperation::Create { params } => ItemOperation::Create {
+                params: params.owned_by(owner_id),
+            },
+            x => x,
+        })
+    });
+
+    let response = if request.atomic {
+        let operations = match operations
+            .enumerate()
+            .map(|(index, x)| x.map_err(|error| (index, error)))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(operations) => operations,
+            Err((index, error)) => {
+                return Ok((
+                    StatusCode::OK,
+                    Json(HttpBatchResponse::rolled_back(size, index, error)),
+                ))
+            }
+        };
+
+        match state.items.batch(operations).await {
+            Ok(outcomes) => HttpBatchResponse {
+                results: outcomes
+                    .into_iter()
+                    .inspect(|x| state.item_events.applied(x))
+                    .map(Into::into)
+                    .collect(),
+            },
+            Err(BatchItemsError::OperationFailed { index, error }) => {
+                HttpBatchResponse::rolled_back(size, index, error.into())
+            }
+            Err(error) => return Err(error.into()),
+        }
+    } else {
+        let mut results = Vec::with_capacity(size);
+        for operation in operations {
+            let result = match operation {
+                Ok(operation) => operation
+                    .apply(state.items.as_ref())
+                    .await
+                    .inspect(|x| state.item_events.applied(x)),
+                Err(error) => {
+                    results.push(error.into());
+                    continue;
+                }
+            };
+
+            results.push(result.map_or_else(|error| AppError::from(error).into(), Into::into));
+        }
+
+        HttpBatchResponse { results }
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Create items from a CSV listing or a JSON array
+///
+/// Rows are validated one by one, valid ones are created and invalid ones are reported with reasons.
+/// Created items are owned by the logged in user, if any
+#[utoipa::path(
+    post,
+    path = "/import",
+    params(IdempotencyKey, HttpImportParams),
+    request_body(
+        description = "CSV listing with header, where `name` and `location` columns are required \
+                       and others are ignored, or JSON array of items",
+        content(
+            (String = "text/csv", example = "name,location\r\nSleeping Bag,\"Calgary, AB\"\r\n"),
+            (Vec<HttpCreateItemParams> = "application/json"),
+        ),
+    ),
+    responses(
+        (status = 200, description = "OK", body = HttpImportReport),
+        (
+            status = 400,
+            description = "Bad Request, body can't be read as CSV or JSON",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 415,
+            description = "Unsupported Media Type",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 422,
+            description = "Unprocessable Entity, required CSV column is missing",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn import_items(
+    user: Option<SessionUser>,
+    State(state): State<AppState>,
+    Query(params): Query<HttpImportParams>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<impl IntoResponse, AppError> {
+    let rows = HttpImportRow::parse(&headers, &body)?;
+    let owner_id = user.map(|SessionUser(id)| id);
+
+    let mut report = HttpImportReport::new(params.dry_run);
+    for (index, row) in rows.into_iter().enumerate() {
+        let result = match row.validate() {
+            Ok(_) if params.dry_run => Ok(None),
+            Ok(params) => state
+                .items
+                .create(params.owned_by(owner_id))
+                .await
+                .inspect(|x| state.item_events.created(x))
+                .map(|x| Some(x.id()))
+                .map_err(Into::into),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(id) => report.accept(index, id),
+            Err(error) => report.reject(index, error),
+        }
+    }
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+impl From<ItemRouter> for Router<AppState> {
+    fn from(_: ItemRouter) -> Self {
+        Router::new()
+            .route("/", get(list_items).post(create_item))
+            .route("/batch", post(batch_items))
+            .route("/import", post(import_items))
+            .route("/events", get(item_events))
+            .route(
+                "/:id",
+                get(get_item)
+                    .put(update_item)
+                    .patch(patch_item)
+                    .delete(delete_item),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, sync::Arc};
+
+    use axum::{body::Body, http::Request};
+    use fake::{Fake, Faker};
+    use http_body_util::BodyExt;
+    use reqwest::header::{CONTENT_TYPE, COOKIE};
+    use rstest::rstest;
+    use serde_json::{from_slice, json, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        dao::{CreateItemsParamsBuilder, ItemsDao, ItemsHashMapDao, PaginationBuilder},
+        http::authentication::session_cookie,
+    };
+
+    async fn batch(state: AppState, body: &Value) -> (StatusCode, Value) {
+        let router: Router<AppState> = ItemRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/batch")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = raw_response.status();
+        let response =
+            from_slice::<Value>(&raw_response.into_body().collect().await.unwrap().to_bytes())
+                .unwrap();
+        println!("{response:#?}");
+
+        (status, response)
+    }
+
+    fn statuses(response: &Value) -> Vec<u64> {
+        response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["status"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn batch_partial() {
+        let items = ItemsHashMapDao::new();
+        let state = AppState {
+            items: Arc::new(items.clone()),
+            ..Default::default()
+        };
+        let body = json!({
+            "operations": [
+                {"op": "create", "name": "Sleeping Bag", "location": "Calgary, AB"},
+                {"op": "delete", "id": Uuid::new_v4()},
+            ]
+        });
+
+        let (status, response) = batch(state, &body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(statuses(&response), vec![201, 404]);
+        assert_eq!(
+            response["results"][1]["error"]["code"],
+            "DeleteItemError::NoSuchEntity"
+        );
+        assert_eq!(
+            items
+                .list(PaginationBuilder::new().build().unwrap())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_atomic() {
+        let items = ItemsHashMapDao::new();
+        let state = AppState {
+            items: Arc::new(items.clone()),
+            ..Default::default()
+        };
+        let body = json!({
+            "atomic": true,
+            "operations": [
+                {"op": "create", "name": "Sleeping Bag", "location": "Calgary, AB"},
+                {"op": "delete", "id": Uuid::new_v4()},
+            ]
+        });
+
+        let (status, response) = batch(state, &body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(statuses(&response), vec![424, 404]);
+        assert!(items
+            .list(PaginationBuilder::new().build().unwrap())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn batch_atomic_unsupported() {
+        let body = json!({"atomic": true, "operations": []});
+
+        let (status, response) = batch(AppState::default(), &body).await;
+
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(response["code"], "BatchItemsError::Unsupported");
+    }
+
+    #[tokio::test]
+    async fn batch_too_large() {
+        let state = AppState {
+            items_batch_max_size: NonZeroUsize::new(1).unwrap(),
+            ..Default::default()
+        };
+        let body = json!({
+            "operations": [
+                {"op": "delete", "id": Uuid::new_v4()},
+                {"op": "delete", "id": Uuid::new_v4()},
+            ]
+        });
+
+        let (status, response) = batch(state, &body).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response["code"], "BatchRequestError::TooLarge");
+    }
+
+    async fn list(state: AppState, uri: &str, accept: &str) -> (StatusCode, HeaderMap, String) {
+        let router: Router<AppState> = ItemRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header(ACCEPT, accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = raw_response.status();
+        let headers = raw_response.headers().clone();
+        let body = String::from_utf8(
+            raw_response
+                .into_body()
+                .collect()
+                .await
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+        )
+        .unwrap();
+        println!("{body}");
+
+        (status, headers, body)
+    }
+
+    #[rstest]
+    #[case::accept("/", "text/csv")]
+    #[case::query("/?format=csv", "application/json")]
+    #[tokio::test]
+    async fn list_csv(#[case] uri: &str, #[case] accept: &str) {
+        let items = ItemsHashMapDao::new();
+        let item = items
+            .create(
+                CreateItemsParamsBuilder::new()
+                    .name("Sleeping Bag \"Polar\"".to_owned())
+                    .location("Calgary, AB".to_owned())
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let state = AppState {
+            items: Arc::new(items),
+            ..Default::default()
+        };
+
+        let (status, headers, body) = list(state, uri, accept).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            headers.get(CONTENT_TYPE).unwrap(),
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(headers.get(VARY).unwrap(), "accept");
+        assert_eq!(
+            body,
+            format!(
+                "id,name,location,created_at,updated_at\r\n\
+                 {},\"Sleeping Bag \"\"Polar\"\"\",\"Calgary, AB\",{},{}\r\n",
+                item.id(),
+                item.created_at().format("%Y-%m-%dT%H:%M:%S%.f"),
+                item.updated_at().format("%Y-%m-%dT%H:%M:%S%.f"),
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn list_ndjson() {
+        let items = ItemsHashMapDao::new();
+        for _ in 0..2 {
+            items.create(Faker.fake()).await.unwrap();
+        }
+        let state = AppState {
+            items: Arc::new(items),
+            ..Default::default()
+        };
+
+        let (status, headers, body) = list(state, "/", "application/x-ndjson").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers.get(CONTENT_TYPE).unwrap(), "application/x-ndjson");
+        assert_eq!(body.lines().count(), 2);
+        for line in body.lines() {
+            assert!(from_slice::<Value>(line.as_bytes()).unwrap().is_object());
+        }
+    }
+
+    #[tokio::test]
+    async fn list_not_acceptable() {
+        let (status, _, body) = list(AppState::default(), "/", "application/xml").await;
+
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(
+            from_slice::<Value>(body.as_bytes()).unwrap()["code"],
+            "ListFormatError::NotAcceptable"
+        );
+    }
+
+    #[rstest]
+    #[case::dry_run(true, 0)]
+    #[case::write(false, 1)]
+    #[tokio::test]
+    async fn import(#[case] dry_run: bool, #[case] written: usize) {
+        let items = ItemsHashMapDao::new();
+        let state = AppState {
+            items: Arc::new(items.clone()),
+            ..Default::default()
+        };
+        let router: Router<AppState> = ItemRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/import?dry_run={dry_run}"))
+                    .header(CONTENT_TYPE, "text/csv")
+                    .body(Body::from(
+                        "name,location\r\nSleeping Bag,\"Calgary, AB\"\r\n,Banff\r\n",
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(raw_response.status(), StatusCode::OK);
+
+        let response =
+            from_slice::<Value>(&raw_response.into_body().collect().await.unwrap().to_bytes())
+                .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(response["dry_run"], dry_run);
+        assert_eq!(response["accepted"], 1);
+        assert_eq!(response["rejected"], 1);
+        assert_eq!(response["rows"][0]["accepted"], true);
+        assert_eq!(response["rows"][0]["id"].is_string(), !dry_run);
+        assert_eq!(response["rows"][1]["accepted"], false);
+        assert_eq!(response["rows"][1]["error"]["errors"][0]["field"], "name");
+
+        let pagination = PaginationBuilder::new().build().unwrap();
+        assert_eq!(items.list(pagination).await.unwrap().len(), written);
+    }
+
+    #[rstest]
+    #[case::logged_in(true)]
+    #[case::anonymous(false)]
+    #[tokio::test]
+    async fn create_owned(#[case] logged_in: bool) {
+        let state = AppState {
+            items: Arc::new(ItemsHashMapDao::new()),
+            ..Default::default()
+        };
+        let (user_id, cookie) = session_cookie(&state, 42).await;
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(CONTENT_TYPE, "application/json");
+        if logged_in {
+            request = request.header(COOKIE, cookie);
+        }
+        let router: Router<AppState> = ItemRouter::default().into();
+
+        // Owner can't be set by clients, it's always the logged in user
+        let body = json!({
+            "name": "Sleeping Bag",
+            "location": "Calgary, AB",
+            "owner_id": Uuid::new_v4(),
+        });
+        let raw_response = router
+            .with_state(state)
+            .oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(raw_response.status(), StatusCode::CREATED);
+
+        let response =
+            from_slice::<Value>(&raw_response.into_body().collect().await.unwrap().to_bytes())
+                .unwrap();
+        println!("{response:#?}");
+
+        let expected = if logged_in {
+            json!(user_id)
+        } else {
+            Value::Null
+        };
+        assert_eq!(response["owner_id"], expected);
+    }
+
+    #[tokio::test]
+    async fn events_resume() {
+        let state = AppState {
+            items: Arc::new(ItemsHashMapDao::new()),
+            ..Default::default()
+        };
+        let (_, cookie) = session_cookie(&state, 42).await;
+        let (_, other) = session_cookie(&state, 43).await;
+        let router: Router<AppState> = ItemRouter::default().into();
+        let router = router.with_state(state);
+
+        // Item of the other user isn't streamed, so the first event is the second one
+        for (cookie, name) in [(&other, "Tent"), (&cookie, "Sleeping Bag")] {
+            router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/")
+                        .header(CONTENT_TYPE, "application/json")
+                        .header(COOKIE, cookie)
+                        .body(Body::from(
+                            json!({"name": name, "location": "Calgary, AB"}).to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let raw_response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/events")
+                    .header(COOKIE, cookie)
+                    .header(LAST_EVENT_ID, "0")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(raw_response.status(), StatusCode::OK);
+        assert_eq!(
+            raw_response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let frame = raw_response.into_body().frame().await.unwrap().unwrap();
+        let frame = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        println!("{frame}");
+
+        assert!(frame.contains("event: item.created\n"));
+        assert!(frame.contains("id: 2\n"));
+        assert!(frame.contains("Sleeping Bag"));
+    }
+
+    #[rstest]
+    #[case::invalid_last_event_id(Some(42), "foo", StatusCode::BAD_REQUEST)]
+    #[case::anonymous(None, "0", StatusCode::UNAUTHORIZED)]
+    #[tokio::test]
+    async fn events_rejected(
+        #[case] user: Option<usize>,
+        #[case] last_event_id: &str,
+        #[case] expected: StatusCode,
+    ) {
+        let state = AppState::default();
+        let mut request = Request::builder()
+            .uri("/events")
+            .header(LAST_EVENT_ID, last_event_id);
+        if let Some(id) = user {
+            request = request.header(COOKIE, session_cookie(&state, id).await.1);
+        }
+        let router: Router<AppState> = ItemRouter::default().into();
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), expected);
+    }
+}
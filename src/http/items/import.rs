@@ -0,0 +1,233 @@
+use std::mem;
+
+use axum::http::{header::CONTENT_TYPE, HeaderMap};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::errors::ImportError;
+use crate::{
+    dao::{CreateItemError, CreateItemParams, CreateItemsParamsBuilder, ItemBuilder},
+    http::common::{csv, AppError, HttpProblem},
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HttpImportParams {
+    /// Only validate rows, nothing is created
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Row of an imported listing. Missing values are reported as rejected rows,
+/// so a single broken row doesn't fail the whole import
+#[derive(Debug, Default, Deserialize)]
+pub struct HttpImportRow {
+    name: Option<String>,
+    location: Option<String>,
+}
+
+impl HttpImportRow {
+    /// Reads rows from a CSV listing with header or from a JSON array of objects
+    pub fn parse(headers: &HeaderMap, body: &str) -> Result<Vec<Self>, ImportError> {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|x| x.to_str().ok())
+            .unwrap_or_default();
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match media_type.as_str() {
+            "text/csv" => Self::from_csv(body),
+            "application/json" => Ok(serde_json::from_str(body)?),
+            _ => Err(ImportError::UnsupportedMediaType {
+                content_type: content_type.to_owned(),
+            }),
+        }
+    }
+
+    // Columns are looked up by name, so listings exported with extra columns can be imported back
+    fn from_csv(body: &str) -> Result<Vec<Self>, ImportError> {
+        let mut records = csv::parse(body)?.into_iter();
+        let header = records.next().unwrap_or_default();
+        let column = |name: &'static str| {
+            header
+                .iter()
+                .position(|x| x.trim().eq_ignore_ascii_case(name))
+                .ok_or(ImportError::MissingColumn { column: name })
+        };
+        let (name, location) = (column("name")?, column("location")?);
+
+        Ok(records
+            .map(|mut record| HttpImportRow {
+                name: record.get_mut(name).map(mem::take),
+                location: record.get_mut(location).map(mem::take),
+            })
+            .collect())
+    }
+
+    /// Checks the row against the same rules, which are applied on item creation
+    pub fn validate(self) -> Result<CreateItemParams, AppError> {
+        let mut builder = CreateItemsParamsBuilder::new();
+        if let Some(name) = self.name {
+            builder = builder.name(name);
+        }
+        if let Some(location) = self.location {
+            builder = builder.location(location);
+        }
+        let params = builder.build()?;
+
+        ItemBuilder::new()
+            .name(params.name().to_owned())
+            .location(params.location().to_owned())
+            .build()
+            .map_err(CreateItemError::from)?;
+
+        Ok(params)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = ImportRowResult)]
+pub struct HttpImportRowResult {
+    /// Zero-based position of row, not counting CSV header
+    #[schema(example = 0)]
+    index: usize,
+    accepted: bool,
+    /// Id of created item, absent in dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Uuid>,
+    /// Reason of rejection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<HttpProblem>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = ImportReport)]
+pub struct HttpImportReport {
+    dry_run: bool,
+    accepted: usize,
+    rejected: usize,
+    rows: Vec<HttpImportRowResult>,
+}
+
+impl HttpImportReport {
+    pub fn new(dry_run: bool) -> Self {
+        HttpImportReport {
+            dry_run,
+            accepted: 0,
+            rejected: 0,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Records an accepted row, `id` is only known if the row was actually written
+    pub fn accept(&mut self, index: usize, id: Option<Uuid>) {
+        self.accepted += 1;
+        self.rows.push(HttpImportRowResult {
+            index,
+            accepted: true,
+            id,
+            error: None,
+        });
+    }
+
+    pub fn reject(&mut self, index: usize, error: AppError) {
+        self.rejected += 1;
+        self.rows.push(HttpImportRowResult {
+            index,
+            accepted: false,
+            id: None,
+            error: Some(error.into()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn parse(content_type: &'static str, body: &str) -> Result<Vec<HttpImportRow>, ImportError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        HttpImportRow::parse(&headers, body)
+    }
+
+    #[test]
+    fn parse_csv() {
+        let rows = parse(
+            "text/csv; charset=utf-8",
+            "id,Location,name\r\n1,\"Calgary, AB\",Sleeping Bag\r\n2,Banff\r\n",
+        )
+        .unwrap();
+        println!("{rows:#?}");
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name.as_deref(), Some("Sleeping Bag"));
+        assert_eq!(rows[0].location.as_deref(), Some("Calgary, AB"));
+        assert_eq!(rows[1].name, None);
+        assert_eq!(rows[1].location.as_deref(), Some("Banff"));
+    }
+
+    #[test]
+    fn parse_csv_missing_column() {
+        assert_eq!(
+            parse("text/csv", "name\r\nSleeping Bag\r\n").unwrap_err(),
+            ImportError::MissingColumn { column: "location" }
+        );
+    }
+
+    #[test]
+    fn parse_json() {
+        let rows = parse(
+            "application/json",
+            r#"[{"name":"Sleeping Bag","location":"Calgary, AB"},{"name":"Tent"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].location, None);
+    }
+
+    #[test]
+    fn parse_unsupported() {
+        assert_eq!(
+            parse("application/xml", "").unwrap_err(),
+            ImportError::UnsupportedMediaType {
+                content_type: "application/xml".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let row = HttpImportRow {
+            name: Some("Sleeping Bag".to_owned()),
+            location: Some("Calgary, AB".to_owned()),
+        };
+        assert!(row.validate().is_ok());
+
+        let row = HttpImportRow {
+            name: Some(String::new()),
+            location: None,
+        };
+        assert_eq!(
+            row.validate().err().unwrap().code,
+            "CreateItemParamsBuilderError::LocationNotSet"
+        );
+
+        let row = HttpImportRow {
+            name: Some(String::new()),
+            location: Some("a".repeat(256)),
+        };
+        let error = row.validate().err().unwrap();
+        assert_eq!(error.code, "CreateItemError::InvalidParams");
+        assert_eq!(error.violations.len(), 2);
+    }
+}
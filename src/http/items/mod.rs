@@ -1,7 +1,12 @@
-pub use handlers::{create_item, delete_item, get_item, list_items, update_item};
+pub use dtos::{HttpCreateItemParams, HttpItem, HttpUpdateItemParams};
+pub use events::{HttpItemEvent, ItemEvents};
+pub use handlers::{ItemApi, ItemRouter};
 
 use super::state;
 
 mod dtos;
 mod errors;
+mod events;
+mod formats;
 mod handlers;
+mod import;
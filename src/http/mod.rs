@@ -1,11 +1,32 @@
 pub use authentication::{auth_callback, login, logout};
-pub use common::health;
-pub use items::{create_item, delete_item, get_item, list_items, update_item};
+pub use common::{
+    health,
+    problem_instance,
+    request_id,
+    request_span,
+    AppError,
+    HttpPaginationParams,
+};
+pub use cors::CorsPolicy;
+pub use docs::DocsRouter;
+pub use idempotency::idempotency;
+pub use items::{HttpItem, ItemEvents};
+pub use rate_limit::{rate_limit, RateLimitQuotas};
 pub use state::AppState;
-pub use users::UserRouter;
+pub use users::{notify_user_webhooks, HttpUser, UserChange};
+pub use versions::{deprecated, Deprecation, V1Router, V1_PREFIX};
 
+mod admin;
 mod authentication;
 mod common;
+mod cors;
+mod docs;
+mod graphql;
+mod idempotency;
 mod items;
+mod rate_limit;
 mod state;
+mod trash;
 mod users;
+mod versions;
+mod webhooks;
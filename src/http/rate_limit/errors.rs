@@ -0,0 +1,43 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+
+use crate::{dao::ErrorVariant, http::common::AppError};
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RateLimitError {
+    #[error("Too many requests, retry in {retry_after} seconds")]
+    Exceeded { retry_after: u64 },
+}
+
+impl ErrorVariant for RateLimitError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Exceeded { .. } => "RateLimitError::Exceeded",
+        }
+    }
+}
+
+impl From<RateLimitError> for AppError {
+    fn from(value: RateLimitError) -> Self {
+        let status_code = match value {
+            RateLimitError::Exceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for RateLimitError {
+    fn into_response(self) -> Response {
+        AppError::from(self).into_response()
+    }
+}
@@ -0,0 +1,356 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{
+        header::{CONTENT_TYPE, RETRY_AFTER},
+        HeaderMap,
+        HeaderName,
+        HeaderValue,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::error;
+use utoipa::{
+    openapi::{path::Operation, ContentBuilder, Header, OpenApi, Ref, ResponseBuilder},
+    Modify,
+    PartialSchema,
+    ToSchema,
+};
+
+use self::errors::RateLimitError;
+use super::{
+    authentication::client_key,
+    common::{HttpProblem, PROBLEM_CONTENT_TYPE},
+    state::AppState,
+};
+use crate::{
+    grpc::{self, GRPC_CONTENT_TYPE},
+    rate_limit::{Quota, RateLimitDecision},
+};
+
+mod errors;
+
+pub const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+pub const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+pub const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Safe routes, which are limited by write quota, since they exchange credentials
+const CREDENTIAL_ROUTES: [&str; 2] = ["/login", "/auth/callback"];
+/// Every gRPC call is a POST, so reads are told apart by method name
+const GRPC_READ_METHODS: [&str; 2] = ["Get", "List"];
+
+/// Token buckets given to every client, reads and writes are limited separately
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimitQuotas {
+    pub read: Quota,
+    pub write: Quota,
+}
+
+/// Limits requests of every client with token buckets, answering 429 once they are empty.
+/// Logged in users are told apart by session, anyone else by IP address. gRPC calls are answered
+/// with `RESOURCE_EXHAUSTED` status instead, since gRPC clients don't read problem documents
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let grpc = is_grpc(request.headers());
+    let (kind, quota) = if is_read(&request, grpc) {
+        ("read", state.rate_limit_quotas.read)
+    } else {
+        ("write", state.rate_limit_quotas.write)
+    };
+    let (mut parts, body) = request.into_parts();
+    let client = client_key(state.session_store.as_ref(), &mut parts).await;
+    let request = Request::from_parts(parts, body);
+    let key = format!("{kind}:{client}");
+
+    let decision = match state.rate_limit.take(&key, quota).await {
+        Ok(decision) => decision,
+        Err(err) => {
+            // Limits are a safeguard, so API keeps serving while their store is unavailable
+            error!("{:#?}", err.to_string());
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        let error = RateLimitError::Exceeded {
+            retry_after: seconds(decision.retry_after),
+        };
+        if grpc {
+            grpc::status(error).into_http().map(Body::new)
+        } else {
+            error.into_response()
+        }
+    };
+    response.headers_mut().extend(headers(quota, decision));
+
+    response
+}
+
+fn is_grpc(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|x| x.starts_with(GRPC_CONTENT_TYPE))
+}
+
+fn is_read(request: &Request, grpc: bool) -> bool {
+    let path = request.uri().path();
+    if grpc {
+        let method = path.rsplit('/').next().unwrap_or_default();
+        return GRPC_READ_METHODS.iter().any(|x| method.starts_with(x));
+    }
+
+    request.method().is_safe() && !CREDENTIAL_ROUTES.contains(&path)
+}
+
+fn headers(quota: Quota, decision: RateLimitDecision) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(RATELIMIT_LIMIT, HeaderValue::from(quota.burst.get()));
+    headers.insert(RATELIMIT_REMAINING, HeaderValue::from(decision.remaining));
+    headers.insert(RATELIMIT_RESET, HeaderValue::from(seconds(decision.reset)));
+    if !decision.allowed {
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from(seconds(decision.retry_after)),
+        );
+    }
+
+    headers
+}
+
+/// Headers carry whole seconds, so waiting for less would be too early
+fn seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Describes 429 response of every rate limited operation in API document
+pub struct RateLimited;
+
+const TOO_MANY_REQUESTS: &str = "TooManyRequests";
+
+impl Modify for RateLimited {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let response = ResponseBuilder::new()
+            .description("Too Many Requests")
+            .header(
+                "Retry-After",
+                Header::builder()
+                    .schema(u64::schema())
+                    .description(Some("Seconds until the request may be retried"))
+                    .build(),
+            )
+            .header(
+                "RateLimit-Limit",
+                Header::builder()
+                    .schema(u64::schema())
+                    .description(Some("Size of the token bucket"))
+                    .build(),
+            )
+            .header(
+                "RateLimit-Remaining",
+                Header::builder()
+                    .schema(u64::schema())
+                    .description(Some("Tokens left in the bucket"))
+                    .build(),
+            )
+            .header(
+                "RateLimit-Reset",
+                Header::builder()
+                    .schema(u64::schema())
+                    .description(Some("Seconds until the bucket is full again"))
+                    .build(),
+            )
+            .content(
+                PROBLEM_CONTENT_TYPE,
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name(HttpProblem::name())))
+                    .build(),
+            )
+            .build();
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .responses
+            .insert(TOO_MANY_REQUESTS.to_owned(), response.into());
+
+        for item in openapi.paths.paths.values_mut() {
+            let operations: [&mut Option<Operation>; 8] = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.options,
+                &mut item.head,
+                &mut item.patch,
+                &mut item.trace,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation.responses.responses.insert(
+                    "429".to_owned(),
+                    Ref::from_response_name(TOO_MANY_REQUESTS).into(),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        num::NonZeroU32,
+    };
+
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{Method, StatusCode},
+        middleware,
+        routing::{get, post},
+        Router,
+    };
+    use http_body_util::BodyExt;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn router() -> Router {
+        let state = AppState {
+            rate_limit_quotas: RateLimitQuotas {
+                read: Quota::per_minute(NonZeroU32::new(1).unwrap(), NonZeroU32::new(2).unwrap()),
+                write: Quota::per_minute(NonZeroU32::new(1).unwrap(), NonZeroU32::new(1).unwrap()),
+            },
+            ..AppState::default()
+        };
+
+        Router::new()
+            .route("/items", get(|| async {}).post(|| async {}))
+            .route(
+                "/sleeping_bag_locator.v1.ItemService/:method",
+                post(|| async {}),
+            )
+            .route("/auth/callback", get(|| async {}))
+            .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+            .with_state(state)
+    }
+
+    fn request(method: Method, uri: &str, ip: Ipv4Addr) -> Request {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(IpAddr::V4(ip), 4242)));
+
+        request
+    }
+
+    #[tokio::test]
+    async fn exceeded() {
+        let router = router();
+
+        let response = router
+            .clone()
+            .oneshot(request(Method::POST, "/items", Ipv4Addr::LOCALHOST))
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(headers.get(RATELIMIT_LIMIT).unwrap(), "1");
+        assert_eq!(headers.get(RATELIMIT_REMAINING).unwrap(), "0");
+        assert_eq!(headers.get(RATELIMIT_RESET).unwrap(), "60");
+        assert!(!headers.contains_key(RETRY_AFTER));
+
+        let response = router
+            .oneshot(request(Method::POST, "/items", Ipv4Addr::LOCALHOST))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "60");
+        assert_eq!(response.headers().get(RATELIMIT_REMAINING).unwrap(), "0");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let problem = from_slice::<HttpProblem>(&body).unwrap();
+
+        assert_eq!(problem.code, "RateLimitError::Exceeded");
+    }
+
+    #[tokio::test]
+    async fn separate_buckets() {
+        let router = router();
+
+        for (method, uri, ip, status) in [
+            (Method::POST, "/items", Ipv4Addr::LOCALHOST, StatusCode::OK),
+            // Callback shares write bucket
+            (
+                Method::GET,
+                "/auth/callback",
+                Ipv4Addr::LOCALHOST,
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (Method::GET, "/items", Ipv4Addr::LOCALHOST, StatusCode::OK),
+            (Method::GET, "/items", Ipv4Addr::LOCALHOST, StatusCode::OK),
+            (
+                Method::GET,
+                "/items",
+                Ipv4Addr::LOCALHOST,
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (Method::POST, "/items", Ipv4Addr::BROADCAST, StatusCode::OK),
+        ] {
+            let response = router
+                .clone()
+                .oneshot(request(method.clone(), uri, ip))
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), status, "{method} {uri} from {ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn grpc() {
+        let router = router();
+        let grpc_request = |method: &str| {
+            let mut request = request(
+                Method::POST,
+                &format!("/sleeping_bag_locator.v1.ItemService/{method}"),
+                Ipv4Addr::LOCALHOST,
+            );
+            request
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+            request
+        };
+
+        for (method, status) in [
+            ("GetItem", None),
+            ("ListItems", None),
+            ("ListItems", Some("8")),
+            ("CreateItem", None),
+            ("UpdateItem", Some("8")),
+        ] {
+            let response = router.clone().oneshot(grpc_request(method)).await.unwrap();
+
+            // Rejected calls are answered with trailers only, as gRPC clients expect
+            assert_eq!(response.status(), StatusCode::OK, "{method}");
+            assert_eq!(
+                response
+                    .headers()
+                    .get("grpc-status")
+                    .map(|x| x.to_str().unwrap()),
+                status,
+                "{method}"
+            );
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use async_session::SessionStore;
 use oauth2::{
@@ -14,7 +14,14 @@ use oauth2::{
     StandardTokenResponse,
 };
 
-use crate::dao::{ItemsDao, UsersDao};
+use crate::{
+    backup::BackupStatusHandle,
+    dao::{ItemsDao, UsersDao},
+    http::{items::ItemEvents, rate_limit::RateLimitQuotas},
+    idempotency::IdempotencyStore,
+    rate_limit::RateLimitStore,
+    webhooks::Webhooks,
+};
 
 type OauthClient = Client<
     StandardErrorResponse<BasicErrorResponseType>,
@@ -34,5 +41,16 @@ pub struct AppState {
     pub items: Arc<dyn ItemsDao + Send + Sync>,
     pub users: Arc<dyn UsersDao + Send + Sync>,
     pub session_store: Arc<dyn SessionStore + Send + Sync>,
+    pub session_cookie_secure: bool,
     pub oauth: OauthClient,
+    pub admin_token: Option<String>,
+    pub items_batch_max_size: NonZeroUsize,
+    pub item_events: ItemEvents,
+    pub webhooks: Webhooks,
+    pub idempotency: Arc<dyn IdempotencyStore + Send + Sync>,
+    pub idempotency_ttl: Duration,
+    pub idempotency_lock_ttl: Duration,
+    pub rate_limit: Arc<dyn RateLimitStore + Send + Sync>,
+    pub rate_limit_quotas: RateLimitQuotas,
+    pub backup_status: Option<BackupStatusHandle>,
 }
@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::dao::TrashedItem;
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[schema(as = TrashedItem)]
+pub struct HttpTrashedItem {
+    #[schema(example = "d06cd939-f13b-4524-83a6-f025639235e9")]
+    id: Uuid,
+    #[schema(example = "Sleeping Bag")]
+    name: String,
+    #[schema(example = "Calgary, AB")]
+    location: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    deleted_at: NaiveDateTime,
+}
+
+#[cfg(test)]
+impl HttpTrashedItem {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl From<TrashedItem> for HttpTrashedItem {
+    fn from(value: TrashedItem) -> Self {
+        let item = value.item();
+
+        HttpTrashedItem {
+            id: item.id(),
+            name: item.name().to_owned(),
+            location: item.location().to_owned(),
+            created_at: item.created_at(),
+            updated_at: item.updated_at(),
+            deleted_at: value.deleted_at(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use axum::http::StatusCode;
+
+use crate::{
+    dao::{ErrorVariant, ListTrashError, PurgeItemError, RecoverItemError},
+    http::common::AppError,
+};
+
+impl From<ListTrashError> for AppError {
+    fn from(value: ListTrashError) -> Self {
+        let status_code = match value {
+            ListTrashError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<RecoverItemError> for AppError {
+    fn from(value: RecoverItemError) -> Self {
+        let status_code = match value {
+            RecoverItemError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
+            RecoverItemError::AlreadyExists { id: _ } => StatusCode::CONFLICT,
+            RecoverItemError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<PurgeItemError> for AppError {
+    fn from(value: PurgeItemError) -> Self {
+        let status_code = match value {
+            PurgeItemError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
+            PurgeItemError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
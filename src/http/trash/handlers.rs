@@ -0,0 +1,247 @@
+use axum::{
+    debug_handler,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use super::{
+    common::{
+        AppError,
+        HttpPaginationParams,
+        HttpProblem,
+        Json,
+        Path,
+        Query,
+        PROBLEM_CONTENT_TYPE,
+    },
+    dtos::HttpTrashedItem,
+    state::AppState,
+};
+use crate::{
+    dao::Pagination,
+    http::{idempotency::IdempotencyKey, items::HttpItem},
+};
+
+#[derive(Default)]
+pub struct TrashRouter {}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_trash, purge_item, recover_item))]
+pub struct TrashApi;
+
+/// Deleted items, which are purged after retention window
+#[utoipa::path(
+    get,
+    path = "",
+    params(HttpPaginationParams),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            body = Vec<HttpTrashedItem>,
+            headers(("pagination-page" = usize), ("pagination-limit" = usize)),
+        ),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn list_trash(
+    Query(pagination_params): Query<HttpPaginationParams>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let pagination: Pagination = pagination_params.try_into()?;
+    let response_headers: HeaderMap = pagination.clone().try_into()?;
+    let result: Vec<HttpTrashedItem> = state
+        .items
+        .list_trash(pagination)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, response_headers, Json(result)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{item_id}/restore",
+    params(IdempotencyKey, ("item_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "OK", body = HttpItem),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 409,
+            description = "Conflict",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn recover_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let entity = state.items.recover(id).await?;
+    // Recovered item reappears in listings, so it's announced as created
+    state.item_events.created(&entity);
+    let result: HttpItem = entity.into();
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
+/// Purge item from trash permanently
+#[utoipa::path(
+    delete,
+    path = "/{item_id}",
+    params(("item_id" = Uuid, Path)),
+    responses(
+        (status = 204, description = "Purged"),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn purge_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    state.items.purge(id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+impl From<TrashRouter> for Router<AppState> {
+    fn from(_: TrashRouter) -> Self {
+        Router::new()
+            .route("/", get(list_trash))
+            .route("/:id", delete(purge_item))
+            .route("/:id/restore", post(recover_item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{body::Body, http::Request};
+    use fake::{Fake, Faker};
+    use http_body_util::BodyExt;
+    use reqwest::Method;
+    use serde_json::from_slice;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::dao::{Item, ItemsDao, ItemsHashMapDao, Precondition};
+
+    async fn state_with_trashed() -> (AppState, Item) {
+        let items = ItemsHashMapDao::new();
+        let entity = items.create(Faker.fake()).await.unwrap();
+        items.delete(entity.id(), Precondition::None).await.unwrap();
+
+        let state = AppState {
+            items: Arc::new(items),
+            ..AppState::default()
+        };
+
+        (state, entity)
+    }
+
+    #[tokio::test]
+    async fn list_ok() {
+        let router: Router<AppState> = TrashRouter::default().into();
+        let (state, entity) = state_with_trashed().await;
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+
+        let response = from_slice::<Vec<HttpTrashedItem>>(
+            &raw_response.into_body().collect().await.unwrap().to_bytes(),
+        )
+        .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0].id(), entity.id());
+    }
+
+    #[tokio::test]
+    async fn recover_ok() {
+        let router: Router<AppState> = TrashRouter::default().into();
+        let (state, entity) = state_with_trashed().await;
+
+        let raw_response = router
+            .with_state(state.clone())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/{}/restore", entity.id()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+        assert_eq!(state.items.get(entity.id()).await.unwrap(), entity);
+    }
+
+    #[tokio::test]
+    async fn purge_ok() {
+        let router: Router<AppState> = TrashRouter::default().into();
+        let (state, entity) = state_with_trashed().await;
+
+        let raw_response = router
+            .clone()
+            .with_state(state.clone())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(format!("/{}", entity.id()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::NO_CONTENT);
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/{}/restore", entity.id()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,18 +1,24 @@
+use async_graphql::InputObject;
 #[cfg(test)]
 use fake::{faker::lorem::en::Word, faker::name::en::Name, Dummy};
 use serde::Deserialize;
 #[cfg(test)]
 use serde::Serialize;
+use utoipa::ToSchema;
 
 use super::{dao::CreateUserParams, entity::HttpUserAuthType};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject, ToSchema)]
 #[cfg_attr(test, derive(Dummy, Clone, PartialEq, Eq, Serialize))]
+#[graphql(name = "CreateUserInput")]
+#[schema(as = CreateUserBody)]
 pub struct HttpCreateUserParams {
     #[cfg_attr(test, dummy(faker = "Name()"))]
+    #[schema(example = "John Doe", min_length = 1, max_length = 128)]
     name: String,
     auth_type: HttpUserAuthType,
     #[cfg_attr(test, dummy(faker = "Word()"))]
+    #[schema(example = "awesome-github-id", min_length = 1, max_length = 128)]
     external_id: String,
 }
 
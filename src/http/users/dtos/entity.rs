@@ -1,14 +1,23 @@
+use async_graphql::{Context, Enum, Object, Result as GraphQLResult};
 use chrono::NaiveDateTime;
 #[cfg(test)]
 use fake::{Dummy, Faker, Rng};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::dao::{User, UserAuthType};
+use super::dao::{PaginationBuilder, User, UserAuthType};
+use crate::http::{
+    common::HttpPaginationParams,
+    graphql::graphql_error,
+    items::HttpItem,
+    state::AppState,
+};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Enum, ToSchema)]
 #[serde(rename_all = "lowercase")]
-#[cfg_attr(test, derive(PartialEq, Eq))]
+#[graphql(name = "UserAuthType")]
+#[schema(as = UserAuthType)]
 pub enum HttpUserAuthType {
     Github,
 }
@@ -36,12 +45,16 @@ impl From<HttpUserAuthType> for UserAuthType {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[cfg_attr(test, derive(Deserialize, PartialEq, Eq))]
+#[schema(as = User)]
 pub struct HttpUser {
+    #[schema(example = "0d58e49b-11b0-4991-86d8-9418637e8cd1")]
     id: Uuid,
+    #[schema(example = "John Doe")]
     name: String,
     auth_type: HttpUserAuthType,
+    #[schema(example = "awesome-github-id")]
     external_id: String,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
@@ -91,6 +104,68 @@ impl HttpUser {
     }
 }
 
+// Deriving `SimpleObject` would clash with the getters above
+#[Object(name = "User")]
+impl HttpUser {
+    #[graphql(name = "id")]
+    async fn resolve_id(&self) -> Uuid {
+        self.id
+    }
+
+    #[graphql(name = "name")]
+    async fn resolve_name(&self) -> &str {
+        &self.name
+    }
+
+    #[graphql(name = "authType")]
+    async fn resolve_auth_type(&self) -> HttpUserAuthType {
+        self.auth_type
+    }
+
+    #[graphql(name = "externalId")]
+    async fn resolve_external_id(&self) -> &str {
+        &self.external_id
+    }
+
+    #[graphql(name = "createdAt")]
+    async fn resolve_created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    #[graphql(name = "updatedAt")]
+    async fn resolve_updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    /// Items owned by the user, paginated the same way as `items` query
+    #[graphql(
+        name = "items",
+        complexity = "limit
+            .unwrap_or(PaginationBuilder::DEFAULT_PAGINATION_LIMIT)
+            .saturating_mul(child_complexity)"
+    )]
+    async fn resolve_items(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<usize>,
+        limit: Option<usize>,
+    ) -> GraphQLResult<Vec<HttpItem>> {
+        let pagination = HttpPaginationParams { page, limit }
+            .try_into()
+            .map_err(graphql_error)?;
+
+        Ok(ctx
+            .data_unchecked::<AppState>()
+            .items
+            .list_owned(self.id, pagination)
+            .await
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
 impl From<User> for HttpUser {
     fn from(value: User) -> Self {
         HttpUser {
@@ -0,0 +1,25 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::HttpUser;
+
+/// Payload of user webhook events, shaped the same way as item events
+#[derive(Debug, Serialize)]
+pub struct HttpUserEvent<'a> {
+    id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<&'a HttpUser>,
+}
+
+impl<'a> HttpUserEvent<'a> {
+    pub fn changed(user: &'a HttpUser) -> Self {
+        Self {
+            id: user.id(),
+            user: Some(user),
+        }
+    }
+
+    pub fn deleted(id: Uuid) -> Self {
+        Self { id, user: None }
+    }
+}
@@ -1,9 +1,11 @@
 pub use create::HttpCreateUserParams;
 pub use entity::HttpUser;
-pub use update::HttpUpdateUserParams;
+pub use event::HttpUserEvent;
+pub use update::{HttpPatchUserParams, HttpUpdateUserParams};
 
-use super::dao;
+use super::{common, dao};
 
 mod create;
 mod entity;
+mod event;
 mod update;
@@ -1,15 +1,23 @@
+use async_graphql::InputObject;
 #[cfg(test)]
 use fake::{faker::name::en::Name, Dummy};
 use serde::Deserialize;
 #[cfg(test)]
 use serde::Serialize;
+use utoipa::ToSchema;
 
-use super::dao::UpdateUserParams;
+use super::{
+    common::{MergePatchError, Patch},
+    dao::{UpdateUserParams, User},
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, InputObject, ToSchema)]
 #[cfg_attr(test, derive(Dummy, Serialize))]
+#[graphql(name = "UpdateUserInput")]
+#[schema(as = UpdateUserBody)]
 pub struct HttpUpdateUserParams {
     #[cfg_attr(test, dummy(faker = "Name()"))]
+    #[schema(example = "John Doe", min_length = 1, max_length = 128)]
     name: String,
 }
 
@@ -28,3 +36,23 @@ impl From<HttpUpdateUserParams> for UpdateUserParams {
         UpdateUserParams::new(val.name)
     }
 }
+
+/// JSON Merge Patch, absent members are kept and `null` removes them
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(as = PatchUserBody)]
+pub struct HttpPatchUserParams {
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "John Doe")]
+    name: Patch<String>,
+}
+
+impl HttpPatchUserParams {
+    pub fn apply(self, entity: &User) -> Result<UpdateUserParams, MergePatchError> {
+        let name = self
+            .name
+            .apply(entity.name().to_owned())
+            .ok_or(MergePatchError::RequiredMemberRemoved { member: "name" })?;
+
+        Ok(UpdateUserParams::new(name))
+    }
+}
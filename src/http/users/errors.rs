@@ -2,20 +2,47 @@ use axum::http::StatusCode;
 
 use super::{
     common::AppError,
-    dao::{CreateUserError, DeleteUserError, GetUserError, UpdateUserError, UsersHealthError},
+    dao::{
+        CreateUserError,
+        DeleteUserError,
+        ErrorVariant,
+        GetUserError,
+        ListUsersError,
+        UpdateUserError,
+        UsersHealthError,
+    },
 };
 
 impl From<CreateUserError> for AppError {
     fn from(value: CreateUserError) -> Self {
+        let (status_code, violations) = match &value {
+            CreateUserError::InvalidParams { violations } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, violations.clone())
+            }
+            CreateUserError::AlreadyExists { id: _ } => (StatusCode::CONFLICT, Vec::new()),
+            CreateUserError::UnexpectedError => (StatusCode::INTERNAL_SERVER_ERROR, Vec::new()),
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations,
+        }
+    }
+}
+
+impl From<ListUsersError> for AppError {
+    fn from(value: ListUsersError) -> Self {
         let status_code = match value {
-            CreateUserError::InvalidParams => StatusCode::UNPROCESSABLE_ENTITY,
-            CreateUserError::AlreadyExists { id: _ } => StatusCode::CONFLICT,
-            CreateUserError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+            ListUsersError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -24,12 +51,15 @@ impl From<DeleteUserError> for AppError {
     fn from(value: DeleteUserError) -> Self {
         let status_code = match value {
             DeleteUserError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
+            DeleteUserError::PreconditionFailed { id: _ } => StatusCode::PRECONDITION_FAILED,
             DeleteUserError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -43,7 +73,9 @@ impl From<GetUserError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
@@ -56,22 +88,31 @@ impl From<UsersHealthError> for AppError {
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations: Vec::new(),
         }
     }
 }
 
 impl From<UpdateUserError> for AppError {
     fn from(value: UpdateUserError) -> Self {
-        let status_code = match value {
-            UpdateUserError::InvalidParams => StatusCode::UNPROCESSABLE_ENTITY,
-            UpdateUserError::NoSuchEntity { id: _ } => StatusCode::NOT_FOUND,
-            UpdateUserError::UnexpectedError => StatusCode::INTERNAL_SERVER_ERROR,
+        let (status_code, violations) = match &value {
+            UpdateUserError::InvalidParams { violations } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, violations.clone())
+            }
+            UpdateUserError::NoSuchEntity { id: _ } => (StatusCode::NOT_FOUND, Vec::new()),
+            UpdateUserError::PreconditionFailed { id: _ } => {
+                (StatusCode::PRECONDITION_FAILED, Vec::new())
+            }
+            UpdateUserError::UnexpectedError => (StatusCode::INTERNAL_SERVER_ERROR, Vec::new()),
         };
 
         Self {
             status_code,
+            code: value.variant(),
             details: value.to_string(),
+            violations,
         }
     }
 }
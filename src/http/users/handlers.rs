@@ -1,75 +1,264 @@
 use axum::{
     debug_handler,
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{get, post},
-    Json,
     Router,
 };
+use utoipa::OpenApi;
 use uuid::Uuid;
 
 use super::{
-    common::AppError,
-    dtos::{HttpCreateUserParams, HttpUpdateUserParams, HttpUser},
+    common::{
+        AppError,
+        ConditionalGet,
+        ETag,
+        HttpProblem,
+        IfMatch,
+        Json,
+        LastModified,
+        Path,
+        PROBLEM_CONTENT_TYPE,
+    },
+    dao::{Precondition, UpdateUserError},
+    dtos::{HttpCreateUserParams, HttpPatchUserParams, HttpUpdateUserParams, HttpUser},
+    notify::{notify_user_webhooks, UserChange},
     state::AppState,
 };
+use crate::http::idempotency::IdempotencyKey;
 
 #[derive(Default)]
 pub struct UserRouter {}
 
+#[derive(OpenApi)]
+#[openapi(paths(create_user, get_user, update_user, patch_user, delete_user))]
+pub struct UserApi;
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(IdempotencyKey),
+    request_body = HttpCreateUserParams,
+    responses(
+        (status = 201, description = "Created", body = HttpUser),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 409,
+            description = "Conflict",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn create_user(
     State(state): State<AppState>,
     Json(params): Json<HttpCreateUserParams>,
 ) -> Result<impl IntoResponse, AppError> {
     let result: HttpUser = state.users.create(params.into()).await?.into();
+    notify_user_webhooks(&state.webhooks, UserChange::Created(&result));
 
     Ok((StatusCode::CREATED, Json(result)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{user_id}",
+    params(("user_id" = Uuid, Path), ConditionalGet),
+    responses(
+        (
+            status = 200,
+            description = "OK",
+            body = HttpUser,
+            headers(("ETag" = String), ("Last-Modified" = String)),
+        ),
+        (
+            status = 304,
+            description = "Not Modified",
+            headers(("ETag" = String), ("Last-Modified" = String)),
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn get_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, AppError> {
-    let result: HttpUser = state.users.get(id).await?.into();
+    conditions: ConditionalGet,
+) -> Result<Response, AppError> {
+    let entity = state.users.get(id).await?;
+    let (etag, last_modified) = (ETag(entity.updated_at()), LastModified(entity.updated_at()));
+
+    if conditions.is_not_modified(entity.updated_at()) {
+        return Ok((StatusCode::NOT_MODIFIED, etag, last_modified, ()).into_response());
+    }
 
-    Ok((StatusCode::OK, Json(result)))
+    let result: HttpUser = entity.into();
+
+    Ok((StatusCode::OK, etag, last_modified, Json(result)).into_response())
 }
 
+#[utoipa::path(
+    put,
+    path = "/{user_id}",
+    params(("user_id" = Uuid, Path), IfMatch),
+    request_body = HttpUpdateUserParams,
+    responses(
+        (status = 200, description = "OK", body = HttpUser, headers(("ETag" = String))),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn update_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
     Json(params): Json<HttpUpdateUserParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let result: HttpUser = state.users.update(id, params.into()).await?.into();
+    let entity = state.users.update(id, params.into(), precondition).await?;
+    let etag = ETag(entity.updated_at());
+    let result: HttpUser = entity.into();
+    notify_user_webhooks(&state.webhooks, UserChange::Updated(&result));
+
+    Ok((StatusCode::OK, etag, Json(result)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{user_id}",
+    params(("user_id" = Uuid, Path), IfMatch),
+    request_body(content = HttpPatchUserParams, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "OK", body = HttpUser, headers(("ETag" = String))),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
+#[debug_handler]
+pub async fn patch_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
+    Json(patch): Json<HttpPatchUserParams>,
+) -> Result<impl IntoResponse, AppError> {
+    // Same read-modify-write as for items, see `patch_item`
+    let entity = loop {
+        let current = state.users.get(id).await?;
+        if !precondition.holds(current.updated_at()) {
+            return Err(UpdateUserError::PreconditionFailed { id }.into());
+        }
 
-    Ok((StatusCode::OK, Json(result)))
+        let params = patch.clone().apply(&current)?;
+        let version = Precondition::UpdatedAt(current.updated_at());
+
+        match state.users.update(id, params, version).await {
+            Err(UpdateUserError::PreconditionFailed { .. })
+                if precondition == Precondition::None => {}
+            result => break result?,
+        }
+    };
+    let etag = ETag(entity.updated_at());
+    let result: HttpUser = entity.into();
+    notify_user_webhooks(&state.webhooks, UserChange::Updated(&result));
+
+    Ok((StatusCode::OK, etag, Json(result)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{user_id}",
+    params(("user_id" = Uuid, Path), IfMatch),
+    responses(
+        (status = 204, description = "Deleted"),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 412,
+            description = "Precondition Failed",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+)]
 #[debug_handler]
 pub async fn delete_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    IfMatch(precondition): IfMatch,
 ) -> Result<impl IntoResponse, AppError> {
-    state.users.delete(id).await?;
+    state.users.delete(id, precondition).await?;
+    notify_user_webhooks(&state.webhooks, UserChange::Deleted(id));
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 impl From<UserRouter> for Router<AppState> {
     fn from(_: UserRouter) -> Self {
-        Router::new()
-            .route("/", post(create_user))
-            .route("/:id", get(get_user).put(update_user).delete(delete_user))
+        Router::new().route("/", post(create_user)).route(
+            "/:id",
+            get(get_user)
+                .put(update_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{
+        num::{NonZeroU32, NonZeroUsize},
+        sync::Arc,
+        time::Duration,
+    };
 
     use async_session::{
         serde_json::{self, from_slice},
@@ -79,13 +268,23 @@ mod tests {
     use fake::{Fake, Faker};
     use http_body_util::BodyExt;
     use oauth2::{basic::BasicClient, AuthUrl, ClientId, ClientSecret, TokenUrl};
-    use reqwest::{header::CONTENT_TYPE, Method, Url};
+    use reqwest::{
+        header::{CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LAST_MODIFIED},
+        Method,
+        Url,
+    };
     use rstest::rstest;
     use serde_json::to_string;
     use tower::ServiceExt;
 
     use super::*;
-    use crate::dao::{CreateUserParams, ItemsMockedDao, UsersDao, UsersHashMapDao};
+    use crate::{
+        dao::{CreateUserParams, ItemsMockedDao, UsersDao, UsersHashMapDao},
+        http::{items::ItemEvents, rate_limit::RateLimitQuotas},
+        idempotency::IdempotencyMemoryStore,
+        rate_limit::{Quota, RateLimitMemoryStore},
+        webhooks::{RetryPolicy, Webhooks},
+    };
 
     impl Default for AppState {
         fn default() -> Self {
@@ -95,10 +294,38 @@ mod tests {
                 items: Arc::new(ItemsMockedDao {}),
                 users: Arc::new(UsersHashMapDao::new()),
                 session_store: Arc::new(MemoryStore::new()),
+                session_cookie_secure: true,
                 oauth: BasicClient::new(ClientId::new(String::new()))
                     .set_client_secret(ClientSecret::new(String::new()))
                     .set_auth_uri(AuthUrl::new(localhost.to_string()).unwrap())
                     .set_token_uri(TokenUrl::new(localhost.to_string()).unwrap()),
+                admin_token: None,
+                items_batch_max_size: NonZeroUsize::new(100).unwrap(),
+                item_events: ItemEvents::new(NonZeroUsize::new(100).unwrap()),
+                webhooks: Webhooks::new(
+                    RetryPolicy {
+                        max_attempts: NonZeroU32::new(2).unwrap(),
+                        initial_backoff: Duration::from_millis(10),
+                        max_backoff: Duration::from_millis(10),
+                    },
+                    NonZeroUsize::new(100).unwrap(),
+                    true,
+                ),
+                idempotency: Arc::new(IdempotencyMemoryStore::new()),
+                idempotency_ttl: Duration::from_secs(60),
+                idempotency_lock_ttl: Duration::from_secs(60),
+                rate_limit: Arc::new(RateLimitMemoryStore::new()),
+                rate_limit_quotas: RateLimitQuotas {
+                    read: Quota::per_minute(
+                        NonZeroU32::new(1).unwrap(),
+                        NonZeroU32::new(1000).unwrap(),
+                    ),
+                    write: Quota::per_minute(
+                        NonZeroU32::new(1).unwrap(),
+                        NonZeroU32::new(1000).unwrap(),
+                    ),
+                },
+                backup_status: None,
             }
         }
     }
@@ -169,6 +396,50 @@ mod tests {
         assert_eq!(raw_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[tokio::test]
+    async fn create_all_violations_reported() {
+        let router: Router<AppState> = UserRouter::default().into();
+
+        let mut params = serde_json::to_value(Faker.fake::<HttpCreateUserParams>()).unwrap();
+        params["name"] = serde_json::Value::String(String::new());
+        params["external_id"] = serde_json::Value::String(String::new());
+        println!("{params:#?}");
+
+        let raw_response = router
+            .with_state(AppState::default())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(to_string(&params).unwrap())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = from_slice::<serde_json::Value>(
+            &raw_response.into_body().collect().await.unwrap().to_bytes(),
+        )
+        .unwrap();
+        println!("{response:#?}");
+
+        assert_eq!(
+            response["errors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|x| (
+                    x["field"].as_str().unwrap(),
+                    x["constraint"].as_str().unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![("name", "not_empty"), ("external_id", "not_empty")]
+        );
+    }
+
     #[rstest]
     #[case::empty_name("name", "")]
     #[case::long_name("name", (129..256).fake())]
@@ -231,6 +502,10 @@ mod tests {
             .unwrap();
 
         assert_eq!(raw_response.status(), StatusCode::OK);
+        assert_eq!(
+            raw_response.headers().get(ETAG).unwrap(),
+            &ETag(entity.updated_at()).to_string()
+        );
 
         let response =
             from_slice::<HttpUser>(&raw_response.into_body().collect().await.unwrap().to_bytes())
@@ -243,6 +518,40 @@ mod tests {
         assert_eq!(response.created_at(), response.updated_at());
     }
 
+    #[tokio::test]
+    async fn get_not_modified() {
+        let router: Router<AppState> = UserRouter::default().into();
+        let predefined_dao = UsersHashMapDao::new();
+        let entity = predefined_dao.create(Faker.fake()).await.unwrap();
+        let state = AppState {
+            users: Arc::new(predefined_dao),
+            ..Default::default()
+        };
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/{}", entity.id()))
+                    .header(IF_NONE_MATCH, ETag(entity.updated_at()).to_string())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::NOT_MODIFIED);
+        assert!(raw_response.headers().contains_key(LAST_MODIFIED));
+        assert!(raw_response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn get_non_existent() {
         let router: Router<AppState> = UserRouter::default().into();
@@ -304,6 +613,136 @@ mod tests {
         assert!(response.created_at().le(&response.updated_at()));
     }
 
+    #[tokio::test]
+    async fn update_and_delete_if_match() {
+        let router: Router<AppState> = UserRouter::default().into();
+        let predefined_dao = UsersHashMapDao::new();
+        let entity = predefined_dao.create(Faker.fake()).await.unwrap();
+        let outdated = ETag(entity.updated_at()).to_string();
+        let state = AppState {
+            users: Arc::new(predefined_dao),
+            ..Default::default()
+        };
+
+        let request = |method: Method, etag: &str| {
+            Request::builder()
+                .method(method)
+                .uri(format!("/{}", entity.id()))
+                .header(CONTENT_TYPE, "application/json")
+                .header(IF_MATCH, etag)
+                .body(Body::from(
+                    to_string(&Faker.fake::<HttpUpdateUserParams>()).unwrap(),
+                ))
+                .unwrap()
+        };
+
+        let router = router.with_state(state);
+        let raw_response = router
+            .clone()
+            .oneshot(request(Method::PUT, &outdated))
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::OK);
+        let current = raw_response.headers().get(ETAG).unwrap().to_owned();
+        assert_ne!(current, outdated);
+
+        for method in [Method::PUT, Method::DELETE] {
+            let raw_response = router
+                .clone()
+                .oneshot(request(method, &outdated))
+                .await
+                .unwrap();
+
+            assert_eq!(raw_response.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
+        let raw_response = router
+            .oneshot(request(Method::DELETE, current.to_str().unwrap()))
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[rstest]
+    #[case::keep("{}", StatusCode::OK)]
+    #[case::set("{\"name\":\"Jane Doe\"}", StatusCode::OK)]
+    #[case::remove("{\"name\":null}", StatusCode::UNPROCESSABLE_ENTITY)]
+    #[case::empty("{\"name\":\"\"}", StatusCode::UNPROCESSABLE_ENTITY)]
+    #[tokio::test]
+    async fn patch(#[case] body: &'static str, #[case] status: StatusCode) {
+        let router: Router<AppState> = UserRouter::default().into();
+        let predefined_dao = UsersHashMapDao::new();
+        let entity = predefined_dao.create(Faker.fake()).await.unwrap();
+        let state = AppState {
+            users: Arc::new(predefined_dao),
+            ..Default::default()
+        };
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri(format!("/{}", entity.id()))
+                    .header(CONTENT_TYPE, "application/merge-patch+json")
+                    .body(body.to_owned())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), status);
+        if status != StatusCode::OK {
+            return;
+        }
+
+        let response =
+            from_slice::<HttpUser>(&raw_response.into_body().collect().await.unwrap().to_bytes())
+                .unwrap();
+        println!("{response:#?}");
+
+        let expected = from_slice::<serde_json::Value>(body.as_bytes()).unwrap()["name"]
+            .as_str()
+            .map_or(entity.name().to_owned(), ToOwned::to_owned);
+        assert_eq!(response.name(), expected);
+        assert_eq!(entity.external_id(), response.external_id());
+        assert!(response.updated_at() > entity.updated_at());
+    }
+
+    #[tokio::test]
+    async fn patch_outdated_if_match() {
+        let router: Router<AppState> = UserRouter::default().into();
+        let predefined_dao = UsersHashMapDao::new();
+        let entity = predefined_dao.create(Faker.fake()).await.unwrap();
+        let outdated = ETag(entity.updated_at()).to_string();
+        predefined_dao
+            .update(entity.id(), Faker.fake(), Precondition::None)
+            .await
+            .unwrap();
+        let state = AppState {
+            users: Arc::new(predefined_dao),
+            ..Default::default()
+        };
+
+        let raw_response = router
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri(format!("/{}", entity.id()))
+                    .header(CONTENT_TYPE, "application/merge-patch+json")
+                    .header(IF_MATCH, outdated)
+                    .body("{}".to_owned())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(raw_response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
     #[rstest]
     #[case::no_name("name")]
     #[tokio::test]
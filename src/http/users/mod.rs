@@ -1,4 +1,6 @@
-pub use handlers::UserRouter;
+pub use dtos::{HttpCreateUserParams, HttpUpdateUserParams, HttpUser};
+pub use handlers::{UserApi, UserRouter};
+pub use notify::{notify_user_webhooks, UserChange};
 
 use super::{common, state};
 use crate::dao;
@@ -6,3 +8,4 @@ use crate::dao;
 mod dtos;
 mod errors;
 mod handlers;
+mod notify;
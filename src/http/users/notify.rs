@@ -0,0 +1,36 @@
+use uuid::Uuid;
+
+use super::dtos::{HttpUser, HttpUserEvent};
+use crate::webhooks::{WebhookEventType, Webhooks};
+
+/// Change of a user, which webhooks are notified of
+#[derive(Clone, Copy)]
+pub enum UserChange<'a> {
+    Created(&'a HttpUser),
+    Updated(&'a HttpUser),
+    Deleted(Uuid),
+}
+
+/// Notifies webhooks of a user change. Shared by REST, GraphQL and gRPC handlers, so events don't
+/// depend on the API the change came from
+pub fn notify_user_webhooks(webhooks: &Webhooks, change: UserChange<'_>) {
+    let (id, event_type, event) = match change {
+        UserChange::Created(user) => (
+            user.id(),
+            WebhookEventType::UserCreated,
+            HttpUserEvent::changed(user),
+        ),
+        UserChange::Updated(user) => (
+            user.id(),
+            WebhookEventType::UserUpdated,
+            HttpUserEvent::changed(user),
+        ),
+        UserChange::Deleted(id) => (
+            id,
+            WebhookEventType::UserDeleted,
+            HttpUserEvent::deleted(id),
+        ),
+    };
+
+    webhooks.notify(id, event_type, &event);
+}
@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::LINK, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+use super::{common::HTTP_DATE_FORMAT, v1::V1_PREFIX};
+
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Schedule of routes, which are kept only for compatibility
+#[derive(Clone, Copy, Debug)]
+pub struct Deprecation {
+    pub deprecated_at: DateTime<Utc>,
+    pub sunset_at: DateTime<Utc>,
+}
+
+/// Marks responses of unversioned aliases as deprecated (RFC 9745) and sunsetting (RFC 8594),
+/// pointing clients to the same route of the first API version
+pub async fn deprecated(
+    State(deprecation): State<Deprecation>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let successor = format!(
+        "<{V1_PREFIX}{}>; rel=\"successor-version\"",
+        request.uri().path()
+    );
+    let mut response = next.run(request).await;
+
+    // Values are formatted from dates, so they are always valid header values
+    let headers = response.headers_mut();
+    headers.insert(
+        DEPRECATION,
+        HeaderValue::from_str(&format!("@{}", deprecation.deprecated_at.timestamp())).unwrap(),
+    );
+    headers.insert(
+        SUNSET,
+        HeaderValue::from_str(&deprecation.sunset_at.format(HTTP_DATE_FORMAT).to_string()).unwrap(),
+    );
+    if let Ok(successor) = HeaderValue::from_str(&successor) {
+        headers.append(LINK, successor);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Router};
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn deprecation_headers() {
+        let deprecation = Deprecation {
+            deprecated_at: Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap(),
+            sunset_at: Utc.with_ymd_and_hms(2027, 4, 19, 0, 0, 0).unwrap(),
+        };
+        let router = Router::new()
+            .route("/items/:id", get(|| async {}))
+            .layer(middleware::from_fn_with_state(deprecation, deprecated));
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/items/foo?bar=baz")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        println!("{response:#?}");
+
+        let headers = response.headers();
+        assert_eq!(headers.get(DEPRECATION).unwrap(), "@1792368000");
+        assert_eq!(
+            headers.get(SUNSET).unwrap(),
+            "Mon, 19 Apr 2027 00:00:00 GMT"
+        );
+        assert_eq!(
+            headers.get(LINK).unwrap(),
+            "</v1/items/foo>; rel=\"successor-version\""
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! Every API version is a router of its own, mounted under `/v{N}`.
+//! A new version reuses routers and handlers of the previous one and replaces only those,
+//! whose DTOs change in a breaking way, so clients of older versions keep working
+pub use deprecation::{deprecated, Deprecation};
+pub use v1::{V1Api, V1Router, V1_PREFIX};
+
+use super::{admin, common, graphql, items, rate_limit, state, trash, users, webhooks};
+
+mod deprecation;
+mod v1;
@@ -0,0 +1,45 @@
+use axum::Router;
+use utoipa::OpenApi;
+
+use super::{
+    admin::{AdminApi, AdminRouter},
+    graphql::{GraphQLApi, GraphQLRouter},
+    items::{ItemApi, ItemRouter},
+    rate_limit::RateLimited,
+    state::AppState,
+    trash::{TrashApi, TrashRouter},
+    users::{UserApi, UserRouter},
+    webhooks::{WebhookApi, WebhookRouter},
+};
+
+pub const V1_PREFIX: &str = "/v1";
+
+#[derive(Default)]
+pub struct V1Router {}
+
+impl From<V1Router> for Router<AppState> {
+    fn from(_: V1Router) -> Self {
+        Router::new()
+            .nest("/items", ItemRouter::default().into())
+            .nest("/trash", TrashRouter::default().into())
+            .nest("/users", UserRouter::default().into())
+            .nest("/admin", AdminRouter::default().into())
+            .nest("/webhooks", WebhookRouter::default().into())
+            .nest("/graphql", GraphQLRouter::default().into())
+    }
+}
+
+/// API document of `V1Router`, nested the same way
+#[derive(OpenApi)]
+#[openapi(
+    nest(
+        (path = "/items", api = ItemApi, tags = ["items"]),
+        (path = "/trash", api = TrashApi, tags = ["trash"]),
+        (path = "/users", api = UserApi, tags = ["users"]),
+        (path = "/admin", api = AdminApi, tags = ["admin"]),
+        (path = "/webhooks", api = WebhookApi, tags = ["webhooks"]),
+        (path = "/graphql", api = GraphQLApi, tags = ["graphql"]),
+    ),
+    modifiers(&RateLimited),
+)]
+pub struct V1Api;
@@ -0,0 +1,87 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::webhooks::{Delivery, Webhook, WebhookEventType};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(as = CreateWebhookBody)]
+pub struct HttpCreateWebhookParams {
+    #[schema(format = "uri", example = "https://example.com/hooks/items")]
+    pub url: String,
+    #[schema(min_items = 1)]
+    pub events: Vec<WebhookEventType>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = Webhook)]
+pub struct HttpWebhook {
+    id: Uuid,
+    #[schema(format = "uri", example = "https://example.com/hooks/items")]
+    url: String,
+    events: Vec<WebhookEventType>,
+    /// Key of `webhook-signature` header, only returned on registration. Deliveries are sent
+    /// with `webhook-id`, `webhook-timestamp` and `webhook-signature` headers, the latter being
+    /// `sha256=` followed by hex encoded HMAC-SHA256 of `{timestamp}.{body}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl HttpWebhook {
+    /// Representation returned on registration, the only one revealing signing secret
+    pub fn with_secret(value: Webhook) -> Self {
+        let secret = value.secret.clone();
+
+        Self {
+            secret: Some(secret),
+            ..value.into()
+        }
+    }
+}
+
+impl From<Webhook> for HttpWebhook {
+    fn from(value: Webhook) -> Self {
+        HttpWebhook {
+            id: value.id,
+            url: value.url.to_string(),
+            events: value.events,
+            secret: None,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Single attempt to deliver an event, failed ones are retried with exponential backoff
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = WebhookDelivery)]
+pub struct HttpDelivery {
+    id: Uuid,
+    /// Same for all attempts of the event, sent as `webhook-id` header
+    event_id: Uuid,
+    event_type: WebhookEventType,
+    #[schema(example = 1)]
+    attempt: u32,
+    /// Response status, absent if no response was received
+    #[schema(example = 200)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    attempted_at: NaiveDateTime,
+}
+
+impl From<Delivery> for HttpDelivery {
+    fn from(value: Delivery) -> Self {
+        HttpDelivery {
+            id: value.id,
+            event_id: value.event_id,
+            event_type: value.event_type,
+            attempt: value.attempt,
+            status: value.status,
+            error: value.error,
+            attempted_at: value.attempted_at,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use axum::http::StatusCode;
+
+use super::common::AppError;
+use crate::{
+    dao::ErrorVariant,
+    webhooks::{RegisterWebhookError, WebhookError},
+};
+
+impl From<WebhookError> for AppError {
+    fn from(value: WebhookError) -> Self {
+        let status_code = match value {
+            WebhookError::NoSuchWebhook { id: _ } => StatusCode::NOT_FOUND,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl From<RegisterWebhookError> for AppError {
+    fn from(value: RegisterWebhookError) -> Self {
+        let status_code = match value {
+            RegisterWebhookError::InvalidUrl { url: _ }
+            | RegisterWebhookError::UnsupportedScheme { scheme: _ }
+            | RegisterWebhookError::ForbiddenTarget { host: _ }
+            | RegisterWebhookError::NoEvents => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+
+        Self {
+            status_code,
+            code: value.variant(),
+            details: value.to_string(),
+            violations: Vec::new(),
+        }
+    }
+}
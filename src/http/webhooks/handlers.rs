@@ -0,0 +1,423 @@
+use axum::{
+    debug_handler,
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use super::{
+    common::{AppError, HttpProblem, Json, Path, PROBLEM_CONTENT_TYPE},
+    dtos::{HttpCreateWebhookParams, HttpDelivery, HttpWebhook},
+    state::AppState,
+};
+use crate::http::{
+    authentication::{SessionCookie, SessionUser},
+    idempotency::IdempotencyKey,
+};
+
+#[derive(Default)]
+pub struct WebhookRouter {}
+
+/// Webhooks belong to the logged in user, who has registered them, and are hidden from others
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_webhook,
+        list_webhooks,
+        get_webhook,
+        delete_webhook,
+        list_deliveries,
+    ),
+    modifiers(&SessionCookie)
+)]
+pub struct WebhookApi;
+
+/// Register webhook, which is notified of changes of the user's items and of the user
+#[utoipa::path(
+    post,
+    path = "",
+    params(IdempotencyKey),
+    request_body = HttpCreateWebhookParams,
+    responses(
+        (status = 201, description = "Created", body = HttpWebhook),
+        (
+            status = 422,
+            description = "Unprocessable Entity",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn create_webhook(
+    SessionUser(owner): SessionUser,
+    State(state): State<AppState>,
+    Json(params): Json<HttpCreateWebhookParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook = state.webhooks.register(owner, &params.url, params.events)?;
+
+    Ok((StatusCode::CREATED, Json(HttpWebhook::with_secret(webhook))))
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    responses(
+        (status = 200, description = "OK", body = Vec<HttpWebhook>),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn list_webhooks(
+    SessionUser(owner): SessionUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let result: Vec<HttpWebhook> = state
+        .webhooks
+        .list(owner)
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    (StatusCode::OK, Json(result))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_id}",
+    params(("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "OK", body = HttpWebhook),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn get_webhook(
+    SessionUser(owner): SessionUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let result: HttpWebhook = state.webhooks.get(owner, id)?.into();
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{webhook_id}",
+    params(("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 204, description = "Deleted"),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn delete_webhook(
+    SessionUser(owner): SessionUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    state.webhooks.unregister(owner, id)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_id}/deliveries",
+    params(("webhook_id" = Uuid, Path)),
+    responses(
+        (
+            status = 200,
+            description = "Latest delivery attempts, most recent first",
+            body = Vec<HttpDelivery>,
+        ),
+        (
+            status = 404,
+            description = "Not Found",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 401,
+            description = "Unauthorized",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+        (
+            status = 403,
+            description = "Forbidden, logged in account isn't registered as a user",
+            body = HttpProblem,
+            content_type = PROBLEM_CONTENT_TYPE,
+        ),
+    ),
+    security(("SessionCookie" = [])),
+)]
+#[debug_handler]
+pub async fn list_deliveries(
+    SessionUser(owner): SessionUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let result: Vec<HttpDelivery> = state
+        .webhooks
+        .deliveries(owner, id)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
+impl From<WebhookRouter> for Router<AppState> {
+    fn from(_: WebhookRouter) -> Self {
+        Router::new()
+            .route("/", post(create_webhook).get(list_webhooks))
+            .route("/:id", get(get_webhook).delete(delete_webhook))
+            .route("/:id/deliveries", get(list_deliveries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use http_body_util::BodyExt;
+    use reqwest::header::{CONTENT_TYPE, COOKIE};
+    use rstest::rstest;
+    use serde_json::{from_slice, json, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::http::authentication::session_cookie;
+
+    async fn call(router: Router, request: Request<Body>) -> (StatusCode, Value) {
+        let raw_response = router.oneshot(request).await.unwrap();
+        let status = raw_response.status();
+        let body = raw_response.into_body().collect().await.unwrap().to_bytes();
+        let response = if body.is_empty() {
+            Value::Null
+        } else {
+            from_slice::<Value>(&body).unwrap()
+        };
+        println!("{response:#?}");
+
+        (status, response)
+    }
+
+    /// Router with two logged in users, whose `Cookie` header values are returned too
+    async fn router() -> (Router, String, String) {
+        let state = AppState::default();
+        let (_, owner) = session_cookie(&state, 1).await;
+        let (_, other) = session_cookie(&state, 2).await;
+        let router: Router<AppState> = WebhookRouter::default().into();
+
+        (router.with_state(state), owner, other)
+    }
+
+    fn create(cookie: &str, body: &Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(COOKIE, cookie)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn get(cookie: &str, uri: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .header(COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn register_and_list() {
+        let (router, owner, _) = router().await;
+
+        let (status, created) = call(
+            router.clone(),
+            create(
+                &owner,
+                &json!({"url": "http://localhost/hook", "events": ["item.updated", "item.deleted"]}),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created["secret"].is_string());
+
+        let (status, listed) = call(router.clone(), get(&owner, "/")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed[0]["id"], created["id"]);
+        assert_eq!(listed[0]["events"], json!(["item.updated", "item.deleted"]));
+        assert!(listed[0].get("secret").is_none());
+
+        let (status, deliveries) = call(
+            router,
+            get(
+                &owner,
+                &format!("/{}/deliveries", created["id"].as_str().unwrap()),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(deliveries, json!([]));
+    }
+
+    #[tokio::test]
+    async fn hidden_from_other_users() {
+        let (router, owner, other) = router().await;
+        let (_, created) = call(
+            router.clone(),
+            create(
+                &owner,
+                &json!({"url": "http://localhost/hook", "events": ["item.updated"]}),
+            ),
+        )
+        .await;
+        let uri = format!("/{}", created["id"].as_str().unwrap());
+
+        let (status, listed) = call(router.clone(), get(&other, "/")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed, json!([]));
+
+        let (status, _) = call(router.clone(), get(&other, &uri)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = call(
+            router.clone(),
+            Request::builder()
+                .method("DELETE")
+                .uri(&uri)
+                .header(COOKIE, &other)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = call(router, get(&owner, &uri)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[rstest]
+    #[case::list("GET", "/")]
+    #[case::create("POST", "/")]
+    #[case::deliveries("GET", "/00000000-0000-0000-0000-000000000000/deliveries")]
+    #[tokio::test]
+    async fn anonymous(#[case] method: &str, #[case] uri: &str) {
+        let (router, _, _) = router().await;
+
+        let (status, response) = call(
+            router,
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({"url": "http://localhost/hook", "events": ["item.updated"]}).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(response["code"], "SessionUserError::Unauthenticated");
+    }
+
+    #[tokio::test]
+    async fn register_unknown_event() {
+        let (router, owner, _) = router().await;
+
+        let (status, _) = call(
+            router,
+            create(
+                &owner,
+                &json!({"url": "http://localhost/hook", "events": ["item.moved"]}),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn deliveries_not_found() {
+        let (router, owner, _) = router().await;
+
+        let (status, response) = call(
+            router,
+            get(&owner, &format!("/{}/deliveries", Uuid::new_v4())),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(response["code"], "WebhookError::NoSuchWebhook");
+    }
+}
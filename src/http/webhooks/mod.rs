@@ -0,0 +1,7 @@
+pub use handlers::{WebhookApi, WebhookRouter};
+
+use super::{common, state};
+
+mod dtos;
+mod errors;
+mod handlers;
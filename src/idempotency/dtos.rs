@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum IdempotencyRecord {
+    /// Request with this key is being processed right now
+    InProgress { fingerprint: String },
+    Completed {
+        fingerprint: String,
+        response: StoredResponse,
+    },
+}
+
+impl IdempotencyRecord {
+    pub fn fingerprint(&self) -> &str {
+        match self {
+            IdempotencyRecord::InProgress { fingerprint }
+            | IdempotencyRecord::Completed { fingerprint, .. } => fingerprint,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use thiserror::Error;
+
+use crate::dao::ErrorVariant;
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum IdempotencyStoreError {
+    #[error("Cannot access idempotency store. This error was a direct following of: {internal}")]
+    Storage { internal: String },
+    #[error(
+        "Cannot (de)serialize idempotency record. This error was a direct following of: {internal}"
+    )]
+    Serialization { internal: String },
+}
+
+impl ErrorVariant for IdempotencyStoreError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Storage { .. } => "IdempotencyStoreError::Storage",
+            Self::Serialization { .. } => "IdempotencyStoreError::Serialization",
+        }
+    }
+}
+
+impl From<serde_json::Error> for IdempotencyStoreError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serialization {
+            internal: value.to_string(),
+        }
+    }
+}
+
+impl From<redis::RedisError> for IdempotencyStoreError {
+    fn from(value: redis::RedisError) -> Self {
+        Self::Storage {
+            internal: value.to_string(),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use axum::async_trait;
+
+use crate::idempotency::{
+    IdempotencyRecord,
+    IdempotencyStore,
+    IdempotencyStoreError,
+    StoredResponse,
+};
+
+/// How often expired records are dropped, they are skipped by lookups meanwhile
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Records {
+    /// Records along with moments, when they expire
+    by_key: HashMap<String, (Instant, IdempotencyRecord)>,
+    evicted_at: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct IdempotencyMemoryStore(Mutex<Records>);
+
+impl IdempotencyMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Scanning every record on each request would hold the lock for long under load,
+    // so expired ones are dropped at most once per interval, which keeps the map within TTL
+    fn records(&self) -> MutexGuard<'_, Records> {
+        let mut records = self.0.lock().unwrap();
+        let now = Instant::now();
+        if records
+            .evicted_at
+            .map_or(true, |x| now.duration_since(x) >= EVICTION_INTERVAL)
+        {
+            records
+                .by_key
+                .retain(|_, (expires_at, _)| *expires_at > now);
+            records.evicted_at = Some(now);
+        }
+
+        records
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for IdempotencyMemoryStore {
+    async fn begin(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+    ) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+        let mut records = self.records();
+
+        if let Some((_, record)) = records
+            .by_key
+            .get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+        {
+            return Ok(Some(record.clone()));
+        }
+
+        records.by_key.insert(
+            key.to_owned(),
+            (
+                Instant::now() + ttl,
+                IdempotencyRecord::InProgress {
+                    fingerprint: fingerprint.to_owned(),
+                },
+            ),
+        );
+
+        Ok(None)
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), IdempotencyStoreError> {
+        self.records().by_key.insert(
+            key.to_owned(),
+            (
+                Instant::now() + ttl,
+                IdempotencyRecord::Completed {
+                    fingerprint: fingerprint.to_owned(),
+                    response,
+                },
+            ),
+        );
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError> {
+        self.records().by_key.remove(key);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn response() -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            headers: vec![("content-type".to_owned(), "application/json".to_owned())],
+            body: b"{}".to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn begin_and_complete() {
+        let store = IdempotencyMemoryStore::new();
+
+        assert_eq!(store.begin("key", "foo", TTL).await, Ok(None));
+        assert_eq!(
+            store.begin("key", "foo", TTL).await,
+            Ok(Some(IdempotencyRecord::InProgress {
+                fingerprint: "foo".to_owned()
+            }))
+        );
+
+        store.complete("key", "foo", response(), TTL).await.unwrap();
+
+        assert_eq!(
+            store.begin("key", "bar", TTL).await,
+            Ok(Some(IdempotencyRecord::Completed {
+                fingerprint: "foo".to_owned(),
+                response: response()
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn release() {
+        let store = IdempotencyMemoryStore::new();
+
+        store.begin("key", "foo", TTL).await.unwrap();
+        store.release("key").await.unwrap();
+
+        assert_eq!(store.begin("key", "foo", TTL).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn expired() {
+        let store = IdempotencyMemoryStore::new();
+
+        store
+            .complete("key", "foo", response(), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(store.begin("key", "foo", TTL).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn evicted_periodically() {
+        let store = IdempotencyMemoryStore::new();
+        store.begin("foo", "foo", TTL).await.unwrap();
+        store
+            .complete("bar", "bar", response(), Duration::ZERO)
+            .await
+            .unwrap();
+
+        // Expired record is kept until the interval passes, but it's already skipped by lookups
+        assert!(store.records().by_key.contains_key("bar"));
+        assert_eq!(store.begin("bar", "bar", TTL).await, Ok(None));
+
+        store
+            .complete("baz", "baz", response(), Duration::ZERO)
+            .await
+            .unwrap();
+        // Same as the interval having passed
+        store.records().evicted_at = None;
+
+        let records = store.records();
+        assert!(!records.by_key.contains_key("baz"));
+        assert!(records.by_key.contains_key("foo"));
+    }
+}
@@ -0,0 +1,5 @@
+pub use memory::IdempotencyMemoryStore;
+pub use redis::IdempotencyRedisStore;
+
+mod memory;
+mod redis;
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use axum::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+
+use crate::idempotency::{
+    IdempotencyRecord,
+    IdempotencyStore,
+    IdempotencyStoreError,
+    StoredResponse,
+};
+
+const KEY_PREFIX: &str = "idempotency:";
+
+#[derive(Clone)]
+pub struct IdempotencyRedisStore(ConnectionManager);
+
+impl IdempotencyRedisStore {
+    pub async fn new(dsn: &str) -> Result<Self, IdempotencyStoreError> {
+        let client = redis::Client::open(dsn)?;
+
+        Ok(Self(ConnectionManager::new(client).await?))
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+        options: SetOptions,
+    ) -> Result<bool, IdempotencyStoreError> {
+        let value = serde_json::to_string(record)?;
+        let reply: Option<String> = self
+            .0
+            .clone()
+            .set_options(format!("{KEY_PREFIX}{key}"), value, options)
+            .await?;
+
+        Ok(reply.is_some())
+    }
+}
+
+fn expiry(ttl: Duration) -> SetExpiry {
+    // Redis rejects zero expiration, so TTL is rounded up to a whole millisecond
+    SetExpiry::PX(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX).max(1))
+}
+
+#[async_trait]
+impl IdempotencyStore for IdempotencyRedisStore {
+    async fn begin(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+    ) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+        let reservation = IdempotencyRecord::InProgress {
+            fingerprint: fingerprint.to_owned(),
+        };
+
+        loop {
+            let options = SetOptions::default()
+                .conditional_set(ExistenceCheck::NX)
+                .with_expiration(expiry(ttl));
+            if self.set(key, &reservation, options).await? {
+                return Ok(None);
+            }
+
+            let value: Option<String> = self.0.clone().get(format!("{KEY_PREFIX}{key}")).await?;
+            // Otherwise the record has expired in between, so reservation is tried again
+            if let Some(value) = value {
+                return Ok(Some(serde_json::from_str(&value)?));
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), IdempotencyStoreError> {
+        let record = IdempotencyRecord::Completed {
+            fingerprint: fingerprint.to_owned(),
+            response,
+        };
+        self.set(
+            key,
+            &record,
+            SetOptions::default().with_expiration(expiry(ttl)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError> {
+        let _: () = self.0.clone().del(format!("{KEY_PREFIX}{key}")).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::async_trait;
+pub use dtos::{IdempotencyRecord, StoredResponse};
+pub use errors::IdempotencyStoreError;
+pub use impls::{IdempotencyMemoryStore, IdempotencyRedisStore};
+
+mod dtos;
+mod errors;
+mod impls;
+
+#[async_trait]
+pub trait IdempotencyStore {
+    /// Reserves key for a request with given fingerprint, unless the key is already known.
+    /// In the latter case the stored record is returned and nothing is reserved
+    async fn begin(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+    ) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError>;
+    /// Stores response for a reserved key, so it could be replayed on retries
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), IdempotencyStoreError>;
+    /// Drops reservation, so the request could be retried from scratch
+    async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError>;
+}
+
+#[async_trait]
+impl<T> IdempotencyStore for Arc<T>
+where
+    T: IdempotencyStore + Send + Sync + ?Sized,
+{
+    async fn begin(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+    ) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+        self.as_ref().begin(key, fingerprint, ttl).await
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> Result<(), IdempotencyStoreError> {
+        self.as_ref()
+            .complete(key, fingerprint, response, ttl)
+            .await
+    }
+
+    async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError> {
+        self.as_ref().release(key).await
+    }
+}
@@ -1,32 +1,88 @@
-use std::sync::Arc;
+use std::{fs, future::Future, io, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use async_redis_session::RedisSessionStore;
-use async_session::MemoryStore;
-use axum::{routing::get, Router};
+use async_session::{MemoryStore, SessionStore};
+use axum::{middleware, routing::get, Router};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use backup::{AdminClient, BackupScheduler, BackupStatusHandle};
+use chrono::{TimeDelta, Utc};
 use clap::Parser;
-use config::{Config, ItemsDaoType, LogFormat, SessionStoreType, UsersDaoType};
-use dao::{ItemsHashMapDao, ItemsMockedDao, UsersHashMapDao, UsersMockedDao};
+use config::{
+    BackupRestoreMode,
+    Command,
+    Config,
+    IdempotencyStoreType,
+    ItemsDaoType,
+    LogFormat,
+    RateLimitStoreType,
+    SessionStoreType,
+    UsersDaoType,
+};
+use dao::{
+    CachedItemsDao,
+    CachedUsersDao,
+    DaoMetrics,
+    InstrumentedItemsDao,
+    InstrumentedUsersDao,
+    ItemsDao,
+    ItemsHashMapDao,
+    ItemsMockedDao,
+    RestoreMode,
+    UsersDao,
+    UsersHashMapDao,
+    UsersMockedDao,
+};
+use futures::FutureExt;
+use grpc::{ItemServiceServer, ItemsGrpc, UserServiceServer, UsersGrpc};
 use http::{
     auth_callback,
-    create_item,
-    delete_item,
-    get_item,
+    deprecated,
     health,
-    list_items,
+    idempotency,
     login,
     logout,
-    update_item,
+    problem_instance,
+    rate_limit,
+    request_id,
+    request_span,
     AppState,
-    UserRouter,
+    CorsPolicy,
+    Deprecation,
+    DocsRouter,
+    ItemEvents,
+    RateLimitQuotas,
+    V1Router,
+    V1_PREFIX,
 };
+use idempotency::{IdempotencyMemoryStore, IdempotencyRedisStore, IdempotencyStore};
 use oauth2::{basic::BasicClient, AuthUrl, ClientId, ClientSecret, TokenUrl};
-use tokio::net::TcpListener;
-use tower_http::trace::TraceLayer;
-use tracing::{error, info};
+use object_store::{aws::AmazonS3Builder, path::Path as ObjectPath};
+use rate_limit::{Quota, RateLimitMemoryStore, RateLimitRedisStore, RateLimitStore};
+use reqwest::Url;
+use tls::{CertificateFiles, HttpsRedirectRouter};
+#[cfg(unix)]
+use tokio::signal::unix::{self, SignalKind};
+use tokio::{
+    net::TcpListener,
+    signal,
+    task::JoinHandle,
+    time::{sleep, Instant},
+};
+use tonic::service::Routes;
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tracing::{error, info, warn};
+use webhooks::{RetryPolicy, Webhooks};
 
+mod backup;
 mod config;
+mod cors;
 mod dao;
+mod grpc;
 mod http;
+mod idempotency;
+mod rate_limit;
+mod tls;
+mod webhooks;
 
 const TRACING_STARTUP_TARGET: &str = "startup";
 
@@ -47,6 +103,32 @@ async fn main() {
         "Tracing subscriber started with log level {} and {:?} log format", args.logging.log_level, args.logging.log_format,
     );
 
+    match args.command.clone().unwrap_or(Command::Serve) {
+        Command::Serve => {
+            let dao_metrics = Arc::new(DaoMetrics::new());
+            let items = items_dao(&args, &dao_metrics);
+            let users = users_dao(&args, &dao_metrics);
+
+            serve(&args, items, users, dao_metrics).await;
+        }
+        // Data lives in the server process, so backups go through its admin endpoints
+        Command::Export { output, server_url } => {
+            export(&admin_client(&args, server_url), output).await;
+        }
+        Command::Restore {
+            input,
+            mode,
+            server_url,
+        } => restore(&admin_client(&args, server_url), input, mode).await,
+    }
+}
+
+async fn serve(
+    args: &Config,
+    items: Arc<dyn ItemsDao + Send + Sync>,
+    users: Arc<dyn UsersDao + Send + Sync>,
+    dao_metrics: Arc<DaoMetrics>,
+) {
     let bind_address = format!("{}:{}", args.runtime.bind_host, args.runtime.bind_port);
     let listener = TcpListener::bind(&bind_address)
         .await
@@ -62,82 +144,626 @@ async fn main() {
         "Created listener at {bind_address}"
     );
 
-    let oauth = BasicClient::new(ClientId::new(args.authentication.oauth_client_id))
-        .set_client_secret(ClientSecret::new(args.authentication.oauth_client_secret))
+    let deadline = Duration::from_secs(args.shutdown.shutdown_timeout_seconds);
+    let shutdown = shutdown_signal(deadline).shared();
+    let (backup_status, backup) = backup_scheduler(args, &items, &users, shutdown.clone()).unzip();
+    let state = app_state(args, items, users, backup_status).await;
+
+    let mut tasks = vec![spawn_trash_purge(args, &state.items, shutdown.clone())];
+    tasks.extend(spawn_dao_metrics_log(args, dao_metrics, shutdown.clone()));
+    tasks.extend(backup);
+    // Ends along with the feed, once events published before shutdown are passed on
+    tasks.push(tokio::spawn(
+        state.item_events.clone().forward(state.webhooks.clone()),
+    ));
+    // Subscriptions never end on their own, so they'd hold connections for the whole deadline
+    tasks.push(tokio::spawn({
+        let item_events = state.item_events.clone();
+        shutdown.clone().map(move |()| item_events.close())
+    }));
+
+    let deprecation = Deprecation {
+        deprecated_at: args.versioning.unversioned_routes_deprecated_at,
+        sunset_at: args.versioning.unversioned_routes_sunset_at,
+    };
+    let v1_router: Router<AppState> = V1Router::default().into();
+    // Unversioned routes are kept as aliases of /v1 for clients, which predate versioning
+    let unversioned_router: Router<AppState> = Router::from(V1Router::default())
+        .layer(middleware::from_fn_with_state(deprecation, deprecated));
+    let router = Router::new()
+        .nest(V1_PREFIX, v1_router)
+        .merge(unversioned_router)
+        .route("/login", get(login))
+        .route("/auth/callback", get(auth_callback))
+        .route("/logout", get(logout))
+        .layer(middleware::from_fn_with_state(state.clone(), idempotency))
+        // Outside of idempotency, so throttled requests neither reserve keys nor get replayed
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        // Docs and probes are never throttled
+        .merge(Router::from(DocsRouter::default()))
+        .route("/health", get(health))
+        .layer(middleware::from_fn(problem_instance))
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(middleware::from_fn(request_id))
+        .with_state(state.clone());
+    // Outermost, so preflight requests are answered before any other middleware runs
+    let router = with_cors(args, router);
+    info!(target : TRACING_STARTUP_TARGET, "Created router");
+
+    let tls = tls_config(args).await;
+    let grpc = spawn_grpc(args, &state, tls.clone(), shutdown.clone()).await;
+    let redirect = spawn_https_redirect(args, shutdown.clone()).await;
+
+    info!(target : TRACING_STARTUP_TARGET, "Starting server");
+    let http = serve_http(listener, router, tls, shutdown.clone());
+    let servers = async {
+        http.await
+            .inspect_err(|err| {
+                error!(
+                    target : TRACING_STARTUP_TARGET,
+                    "Failed to start server: {err}"
+                );
+            })
+            .unwrap();
+        // Other servers stop on the same signal, so they are awaited only for the rest of drain
+        if let Some(grpc) = grpc {
+            let _ = grpc.await;
+        }
+        if let Some(redirect) = redirect {
+            let _ = redirect.await;
+        }
+        // Background tasks stop on the same signal too, purge or backup in progress is finished
+        for task in tasks {
+            let _ = task.await;
+        }
+    };
+
+    tokio::select! {
+        () = servers => info!(target : TRACING_STARTUP_TARGET, "Drained all connections"),
+        () = shutdown.then(|()| sleep(deadline)) => warn!(
+            target : TRACING_STARTUP_TARGET,
+            "Dropping connections, which weren't drained in {deadline:?}"
+        ),
+    }
+    // Unless drain timed out, routers and tasks are gone, so this is the last reference to DAOs
+    // and stores, and every write to them is complete. Redis
+    // connections of idempotency and rate limits are closed on drop, while RedisSessionStore
+    // opens one per command and has none left open
+    drop(state);
+    info!(target : TRACING_STARTUP_TARGET, "Server stopped");
+}
+
+/// Serves router over TLS if it's configured, over plain HTTP otherwise. HTTP/2 is negotiated
+/// either way, which gRPC relies on
+async fn serve_http(
+    listener: TcpListener,
+    router: Router,
+    tls: Option<RustlsConfig>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    // Peer address is the fallback key of rate limits
+    let service = router.into_make_service_with_connect_info::<SocketAddr>();
+    let Some(tls) = tls else {
+        return axum::serve(listener, service)
+            .with_graceful_shutdown(shutdown)
+            .await;
+    };
+
+    let handle = Handle::new();
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            shutdown.await;
+            // Deadline is up to the caller, as for plain HTTP
+            handle.graceful_shutdown(None);
+        }
+    });
+    axum_server::from_tcp_rustls(listener.into_std()?, tls)
+        .handle(handle)
+        .serve(service)
+        .await
+}
+
+async fn tls_config(args: &Config) -> Option<RustlsConfig> {
+    let files = CertificateFiles {
+        certificate: args.tls.tls_certificate_path.clone()?,
+        key: args.tls.tls_key_path.clone()?,
+    };
+
+    let config = files
+        .load()
+        .await
+        .inspect_err(|err| {
+            error!(target : TRACING_STARTUP_TARGET, "{err}");
+        })
+        .unwrap();
+    files
+        .clone()
+        .watch(config.clone())
+        .inspect_err(|err| {
+            error!(target : TRACING_STARTUP_TARGET, "{err}");
+        })
+        .unwrap();
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Serving HTTPS with certificate {:?}, which is reloaded on change", files.certificate
+    );
+
+    Some(config)
+}
+
+async fn spawn_https_redirect(
+    args: &Config,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Option<JoinHandle<()>> {
+    let bind_address = SocketAddr::new(args.runtime.bind_host, args.tls.tls_redirect_port?);
+    let listener = TcpListener::bind(bind_address)
+        .await
+        .inspect_err(|err| {
+            error!(
+                target : TRACING_STARTUP_TARGET,
+                "Cannot bind HTTPS redirect to {bind_address}: {err}"
+            );
+        })
+        .unwrap();
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Created HTTPS redirect listener at {bind_address}"
+    );
+
+    let router = Router::from(HttpsRedirectRouter {
+        https_port: args.runtime.bind_port,
+    });
+    Some(tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, router)
+            .with_graceful_shutdown(shutdown)
+            .await
+        {
+            error!(
+                target : TRACING_STARTUP_TARGET,
+                "HTTPS redirect failed: {err}"
+            );
+        }
+    }))
+}
+
+/// Resolves on the first SIGTERM or SIGINT, servers stop accepting connections once it does.
+/// There is no SIGTERM outside of Unix, so only Ctrl+C is awaited there
+async fn shutdown_signal(deadline: Duration) {
+    #[cfg(unix)]
+    let terminate = async {
+        unix::signal(SignalKind::terminate())
+            .inspect_err(|err| {
+                error!(
+                    target : TRACING_STARTUP_TARGET,
+                    "Cannot listen for SIGTERM: {err}"
+                );
+            })
+            .unwrap()
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let name = tokio::select! {
+        () = terminate => "SIGTERM",
+        _ = signal::ctrl_c() => "SIGINT",
+    };
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Received {name}, draining connections for up to {deadline:?}"
+    );
+}
+
+async fn app_state(
+    args: &Config,
+    items: Arc<dyn ItemsDao + Send + Sync>,
+    users: Arc<dyn UsersDao + Send + Sync>,
+    backup_status: Option<BackupStatusHandle>,
+) -> AppState {
+    let oauth = BasicClient::new(ClientId::new(args.authentication.oauth_client_id.clone()))
+        .set_client_secret(ClientSecret::new(
+            args.authentication.oauth_client_secret.clone(),
+        ))
         .set_auth_uri(AuthUrl::new("https://github.com/login/oauth/authorize".to_owned()).unwrap())
         .set_token_uri(
             TokenUrl::new("https://github.com/login/oauth/access_token".to_owned()).unwrap(),
         );
 
-    let state = AppState {
-        items: match args.items.items_dao_type {
-            ItemsDaoType::Mocked => {
-                info!(target : TRACING_STARTUP_TARGET, "Using ItemsMockedDao");
-                Arc::new(ItemsMockedDao {})
+    AppState {
+        backup_status,
+        items,
+        users,
+        session_store: session_store(args),
+        session_cookie_secure: args.authentication.session_cookie_secure,
+        oauth,
+        admin_token: args.admin.admin_token.clone(),
+        items_batch_max_size: args.batch.items_batch_max_size,
+        item_events: ItemEvents::new(args.events.item_events_replay_size),
+        webhooks: Webhooks::new(
+            RetryPolicy {
+                max_attempts: args.webhooks.webhook_max_attempts,
+                initial_backoff: Duration::from_millis(args.webhooks.webhook_initial_backoff_ms),
+                max_backoff: Duration::from_millis(args.webhooks.webhook_max_backoff_ms),
+            },
+            args.webhooks.webhook_delivery_log_size,
+            args.webhooks.webhook_allow_private_targets,
+        ),
+        idempotency: idempotency_store(args).await,
+        idempotency_ttl: Duration::from_secs(args.idempotency.idempotency_ttl_seconds),
+        idempotency_lock_ttl: Duration::from_secs(args.idempotency.idempotency_lock_ttl_seconds),
+        rate_limit: rate_limit_store(args).await,
+        rate_limit_quotas: RateLimitQuotas {
+            read: Quota::per_minute(
+                args.rate_limit.rate_limit_read_per_minute,
+                args.rate_limit.rate_limit_read_burst,
+            ),
+            write: Quota::per_minute(
+                args.rate_limit.rate_limit_write_per_minute,
+                args.rate_limit.rate_limit_write_burst,
+            ),
+        },
+    }
+}
+
+fn session_store(args: &Config) -> Arc<dyn SessionStore + Send + Sync> {
+    match args.session_store.session_store_type {
+        SessionStoreType::Memory => {
+            info!(target : TRACING_STARTUP_TARGET, "Using MemoryStore");
+            Arc::new(MemoryStore::new())
+        }
+        SessionStoreType::Redis => {
+            info!(target : TRACING_STARTUP_TARGET, "Using RedisSessionStore");
+            if args.session_store.session_store_dsn.is_empty() {
+                error!(target: TRACING_STARTUP_TARGET, "Cannot instantiate RedisSessionStore with empty DSN");
+                panic!()
             }
-            ItemsDaoType::HashMap => {
-                info!(target : TRACING_STARTUP_TARGET, "Using ItemsHashMapDao");
-                Arc::new(ItemsHashMapDao::new())
+            let session_store = RedisSessionStore::new(
+                args.session_store.session_store_dsn.clone(),
+            ).inspect_err(
+                |err|
+                error!(target: TRACING_STARTUP_TARGET, "Error while creating RedisSessionStore: {err:#?}")
+            ).unwrap();
+            info!(target : TRACING_STARTUP_TARGET, "Created RedisSessionStore with {:#?}", args.session_store.session_store_dsn);
+            Arc::new(session_store)
+        }
+    }
+}
+
+async fn idempotency_store(args: &Config) -> Arc<dyn IdempotencyStore + Send + Sync> {
+    match args.idempotency.idempotency_store_type {
+        IdempotencyStoreType::Memory => {
+            info!(target : TRACING_STARTUP_TARGET, "Using IdempotencyMemoryStore");
+            Arc::new(IdempotencyMemoryStore::new())
+        }
+        IdempotencyStoreType::Redis => {
+            info!(target : TRACING_STARTUP_TARGET, "Using IdempotencyRedisStore");
+            if args.idempotency.idempotency_store_dsn.is_empty() {
+                error!(target: TRACING_STARTUP_TARGET, "Cannot instantiate IdempotencyRedisStore with empty DSN");
+                panic!()
             }
-        },
-        users: match args.users.users_dao_type {
-            UsersDaoType::Mocked => {
-                info!(target : TRACING_STARTUP_TARGET, "Using ItemsMockedDao");
-                Arc::new(UsersMockedDao {})
+            let store = IdempotencyRedisStore::new(&args.idempotency.idempotency_store_dsn)
+                .await
+                .inspect_err(|err| {
+                    error!(
+                        target: TRACING_STARTUP_TARGET,
+                        "Error while creating IdempotencyRedisStore: {err:#?}"
+                    );
+                })
+                .unwrap();
+            Arc::new(store)
+        }
+    }
+}
+
+fn with_cors(args: &Config, router: Router) -> Router {
+    if args.cors.cors_allowed_origins.is_empty() {
+        info!(target : TRACING_STARTUP_TARGET, "CORS is disabled");
+        return router;
+    }
+
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Allowing cross-origin requests from {:?}",
+        args.cors.cors_allowed_origins
+    );
+    router.layer(CorsLayer::from(CorsPolicy {
+        origins: args.cors.cors_allowed_origins.clone(),
+        methods: args.cors.cors_allowed_methods.clone(),
+        headers: args.cors.cors_allowed_headers.clone(),
+        exposed_headers: args.cors.cors_exposed_headers.clone(),
+        credentials: args.cors.cors_allow_credentials,
+        max_age: Duration::from_secs(args.cors.cors_max_age_seconds),
+    }))
+}
+
+async fn rate_limit_store(args: &Config) -> Arc<dyn RateLimitStore + Send + Sync> {
+    match args.rate_limit.rate_limit_store_type {
+        RateLimitStoreType::Memory => {
+            info!(target : TRACING_STARTUP_TARGET, "Using RateLimitMemoryStore");
+            Arc::new(RateLimitMemoryStore::new())
+        }
+        RateLimitStoreType::Redis => {
+            info!(target : TRACING_STARTUP_TARGET, "Using RateLimitRedisStore");
+            if args.rate_limit.rate_limit_store_dsn.is_empty() {
+                error!(target: TRACING_STARTUP_TARGET, "Cannot instantiate RateLimitRedisStore with empty DSN");
+                panic!()
             }
-            UsersDaoType::HashMap => {
-                info!(target : TRACING_STARTUP_TARGET, "Using ItemsHashMapDao");
-                Arc::new(UsersHashMapDao::new())
+            let store = RateLimitRedisStore::new(&args.rate_limit.rate_limit_store_dsn)
+                .await
+                .inspect_err(|err| {
+                    error!(
+                        target: TRACING_STARTUP_TARGET,
+                        "Error while creating RateLimitRedisStore: {err:#?}"
+                    );
+                })
+                .unwrap();
+            Arc::new(store)
+        }
+    }
+}
+
+/// Serves gRPC with the same TLS config, rate limits and request ids as HTTP listener
+async fn spawn_grpc(
+    args: &Config,
+    state: &AppState,
+    tls: Option<RustlsConfig>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Option<JoinHandle<()>> {
+    let bind_address = SocketAddr::new(args.runtime.bind_host, args.grpc.grpc_port?);
+    let listener = TcpListener::bind(bind_address)
+        .await
+        .inspect_err(|err| {
+            error!(
+                target : TRACING_STARTUP_TARGET,
+                "Cannot bind gRPC server to {bind_address}: {err}"
+            );
+        })
+        .unwrap();
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Created gRPC listener at {bind_address}"
+    );
+
+    let router = Routes::new(ItemServiceServer::new(ItemsGrpc::new(state.clone())))
+        .add_service(UserServiceServer::new(UsersGrpc::new(state.clone())))
+        .into_axum_router()
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        .layer(TraceLayer::new_for_grpc().make_span_with(request_span))
+        .layer(middleware::from_fn(request_id));
+    Some(tokio::spawn(async move {
+        if let Err(err) = serve_http(listener, router, tls, shutdown).await {
+            error!(
+                target : TRACING_STARTUP_TARGET,
+                "gRPC server failed: {err}"
+            );
+        }
+    }))
+}
+
+fn spawn_trash_purge(
+    args: &Config,
+    items: &Arc<dyn ItemsDao + Send + Sync>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> JoinHandle<()> {
+    let items = items.clone();
+    let retention = TimeDelta::days(args.trash.trash_retention_days);
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(args.trash.trash_purge_interval_seconds));
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Purging trash older than {retention} every {:?}", interval.period()
+    );
+
+    tokio::spawn(async move {
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                () = &mut shutdown => return,
             }
-        },
-        session_store: match args.session_store.session_store_type {
-            SessionStoreType::Memory => {
-                info!(target : TRACING_STARTUP_TARGET, "Using MemoryStore");
-                Arc::new(MemoryStore::new())
+            match items.purge_trash(Utc::now().naive_utc() - retention).await {
+                Ok(0) => {}
+                Ok(count) => info!("Purged {count} expired items from trash"),
+                Err(err) => error!("Cannot purge trash: {err}"),
             }
-            SessionStoreType::Redis => {
-                info!(target : TRACING_STARTUP_TARGET, "Using RedisSessionStore");
-                if args.session_store.session_store_dsn.is_empty() {
-                    error!(target: TRACING_STARTUP_TARGET, "Cannot instantiate RedisSessionStore with empty DSN");
-                    panic!()
+        }
+    })
+}
+
+fn spawn_dao_metrics_log(
+    args: &Config,
+    metrics: Arc<DaoMetrics>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Option<JoinHandle<()>> {
+    if !args.dao_instrumentation.dao_instrumentation_enabled {
+        return None;
+    }
+
+    let period = Duration::from_secs(
+        args.dao_instrumentation
+            .dao_instrumentation_log_interval_seconds,
+    );
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Logging DAO call counters every {period:?}"
+    );
+
+    Some(tokio::spawn(async move {
+        // First tick completes immediately, when there is nothing to log yet
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => metrics.log(),
+                // Calls since the last tick would be lost otherwise
+                () = &mut shutdown => {
+                    metrics.log();
+                    return;
                 }
-                let session_store = RedisSessionStore::new(
-                    args.session_store.session_store_dsn.clone(),
-                ).inspect_err(
-                    |err|
-                    error!(target: TRACING_STARTUP_TARGET, "Error while creating RedisSessionStore: {err:#?}")
-                ).unwrap();
-                info!(target : TRACING_STARTUP_TARGET, "Created RedisSessionStore with {:#?}", args.session_store.session_store_dsn);
-                Arc::new(session_store)
             }
-        },
-        oauth,
-    };
+        }
+    }))
+}
 
-    let user_router = UserRouter::default();
-    let router = Router::new()
-        .layer(TraceLayer::new_for_http())
-        .route("/items", get(list_items).post(create_item))
-        .route(
-            "/items/:id",
-            get(get_item).put(update_item).delete(delete_item),
-        )
-        .nest("/users", user_router.into())
-        .route("/login", get(login))
-        .route("/auth/callback", get(auth_callback))
-        .route("/logout", get(logout))
-        .route("/health", get(health))
-        .with_state(state);
-    info!(target : TRACING_STARTUP_TARGET, "Created router");
+fn backup_scheduler(
+    args: &Config,
+    items: &Arc<dyn ItemsDao + Send + Sync>,
+    users: &Arc<dyn UsersDao + Send + Sync>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Option<(BackupStatusHandle, JoinHandle<()>)> {
+    let schedule = args.backup_schedule.backup_schedule.clone()?;
 
-    info!(target : TRACING_STARTUP_TARGET, "Starting server");
-    axum::serve(listener, router)
-        .await
+    let store = AmazonS3Builder::new()
+        .with_endpoint(&args.backup_schedule.backup_s3_endpoint)
+        .with_allow_http(args.backup_schedule.backup_s3_allow_http)
+        .with_region(&args.backup_schedule.backup_s3_region)
+        .with_bucket_name(&args.backup_schedule.backup_s3_bucket)
+        .with_access_key_id(&args.backup_schedule.backup_s3_access_key_id)
+        .with_secret_access_key(&args.backup_schedule.backup_s3_secret_access_key)
+        .build()
         .inspect_err(|err| {
             error!(
                 target : TRACING_STARTUP_TARGET,
-                "Failed to start server: {err}"
+                "Cannot create S3 client for backups: {err}"
             );
         })
         .unwrap();
+
+    let scheduler = BackupScheduler::new(
+        items.clone(),
+        users.clone(),
+        Arc::new(store),
+        ObjectPath::from(args.backup_schedule.backup_s3_prefix.as_str()),
+        schedule,
+        args.backup_schedule.backup_retention_count,
+    );
+    let status = scheduler.status();
+    info!(
+        target : TRACING_STARTUP_TARGET,
+        "Scheduled backups to bucket {:?} at {:?}",
+        args.backup_schedule.backup_s3_bucket,
+        args.backup_schedule.backup_s3_endpoint,
+    );
+    let task = tokio::spawn(scheduler.run(shutdown));
+
+    Some((status, task))
+}
+
+fn admin_client(args: &Config, server_url: Url) -> AdminClient {
+    let token = args
+        .admin
+        .admin_token
+        .clone()
+        .ok_or("Admin token is required to call admin endpoints")
+        .inspect_err(|err| error!("{err}"))
+        .unwrap();
+
+    AdminClient::new(server_url, token)
+}
+
+async fn export(client: &AdminClient, output: Option<PathBuf>) {
+    let archive = client
+        .export()
+        .await
+        .inspect_err(|err| error!("Cannot export backup: {err}"))
+        .unwrap();
+
+    match output {
+        Some(path) => {
+            fs::write(&path, archive)
+                .inspect_err(|err| error!("Cannot write backup to {path:?}: {err}"))
+                .unwrap();
+            info!("Backup exported to {path:?}");
+        }
+        None => print!("{archive}"),
+    }
+}
+
+async fn restore(client: &AdminClient, input: Option<PathBuf>, mode: BackupRestoreMode) {
+    let archive = match input {
+        Some(path) => fs::read_to_string(&path)
+            .inspect_err(|err| error!("Cannot read backup from {path:?}: {err}"))
+            .unwrap(),
+        None => io::read_to_string(io::stdin())
+            .inspect_err(|err| error!("Cannot read backup from stdin: {err}"))
+            .unwrap(),
+    };
+    let mode = match mode {
+        BackupRestoreMode::Merge => RestoreMode::Merge,
+        BackupRestoreMode::Replace => RestoreMode::Replace,
+    };
+
+    let report = client
+        .restore(archive, mode)
+        .await
+        .inspect_err(|err| error!("Cannot restore backup: {err}"))
+        .unwrap();
+    info!(
+        "Restored {} items, {} trashed items and {} users in {mode:?} mode",
+        report.items, report.trash, report.users
+    );
+}
+
+fn items_dao(args: &Config, metrics: &Arc<DaoMetrics>) -> Arc<dyn ItemsDao + Send + Sync> {
+    let mut dao: Arc<dyn ItemsDao + Send + Sync> = match args.items.items_dao_type {
+        ItemsDaoType::Mocked => {
+            info!(target : TRACING_STARTUP_TARGET, "Using ItemsMockedDao");
+            Arc::new(ItemsMockedDao {})
+        }
+        ItemsDaoType::HashMap => {
+            info!(target : TRACING_STARTUP_TARGET, "Using ItemsHashMapDao");
+            Arc::new(ItemsHashMapDao::new())
+        }
+    };
+
+    if args.dao_cache.dao_cache_enabled {
+        let capacity = args.dao_cache.dao_cache_capacity;
+        let ttl = Duration::from_secs(args.dao_cache.dao_cache_ttl_seconds);
+        info!(
+            target : TRACING_STARTUP_TARGET,
+            "Using CachedItemsDao with capacity {capacity} and TTL {ttl:?}"
+        );
+        dao = Arc::new(CachedItemsDao::new(dao, capacity, ttl));
+    }
+
+    // Outside of the cache, so latency is measured as callers observe it, cache hits included
+    if args.dao_instrumentation.dao_instrumentation_enabled {
+        info!(target : TRACING_STARTUP_TARGET, "Using InstrumentedItemsDao");
+        dao = Arc::new(InstrumentedItemsDao::new(dao, metrics.clone()));
+    }
+
+    dao
+}
+
+fn users_dao(args: &Config, metrics: &Arc<DaoMetrics>) -> Arc<dyn UsersDao + Send + Sync> {
+    let mut dao: Arc<dyn UsersDao + Send + Sync> = match args.users.users_dao_type {
+        UsersDaoType::Mocked => {
+            info!(target : TRACING_STARTUP_TARGET, "Using UsersMockedDao");
+            Arc::new(UsersMockedDao {})
+        }
+        UsersDaoType::HashMap => {
+            info!(target : TRACING_STARTUP_TARGET, "Using UsersHashMapDao");
+            Arc::new(UsersHashMapDao::new())
+        }
+    };
+
+    if args.dao_cache.dao_cache_enabled {
+        let capacity = args.dao_cache.dao_cache_capacity;
+        let ttl = Duration::from_secs(args.dao_cache.dao_cache_ttl_seconds);
+        info!(
+            target : TRACING_STARTUP_TARGET,
+            "Using CachedUsersDao with capacity {capacity} and TTL {ttl:?}"
+        );
+        dao = Arc::new(CachedUsersDao::new(dao, capacity, ttl));
+    }
+
+    // Outside of the cache, so latency is measured as callers observe it, cache hits included
+    if args.dao_instrumentation.dao_instrumentation_enabled {
+        info!(target : TRACING_STARTUP_TARGET, "Using InstrumentedUsersDao");
+        dao = Arc::new(InstrumentedUsersDao::new(dao, metrics.clone()));
+    }
+
+    dao
 }
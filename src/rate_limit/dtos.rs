@@ -0,0 +1,111 @@
+use std::{num::NonZeroU32, time::Duration};
+
+/// Token bucket, which holds up to `burst` tokens and regains one every `interval`.
+///
+/// Buckets are tracked as the time left until they are full again (GCRA), so stores keep
+/// a single timestamp per bucket and never have to refill it in background
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    pub burst: NonZeroU32,
+    pub interval: Duration,
+}
+
+impl Quota {
+    pub fn per_minute(requests: NonZeroU32, burst: NonZeroU32) -> Self {
+        Self {
+            burst,
+            interval: Duration::from_secs(60) / requests.get(),
+        }
+    }
+
+    /// Time it takes for an empty bucket to become full
+    pub fn capacity(&self) -> Duration {
+        self.interval * self.burst.get()
+    }
+
+    /// Takes a token from the bucket, which becomes full in `reset`
+    pub fn take(&self, reset: Duration) -> RateLimitDecision {
+        let taken = reset + self.interval;
+
+        match taken.checked_sub(self.capacity()) {
+            Some(retry_after) if !retry_after.is_zero() => RateLimitDecision {
+                allowed: false,
+                remaining: 0,
+                reset,
+                retry_after,
+            },
+            _ => RateLimitDecision {
+                allowed: true,
+                remaining: self.remaining(taken),
+                reset: taken,
+                retry_after: Duration::ZERO,
+            },
+        }
+    }
+
+    /// Tokens left in the bucket, which becomes full in `reset`
+    pub fn remaining(&self, reset: Duration) -> u32 {
+        let available = self.capacity().saturating_sub(reset);
+
+        u32::try_from(available.as_nanos() / self.interval.as_nanos().max(1)).unwrap_or(u32::MAX)
+    }
+}
+
+/// Outcome of taking a token from a bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Tokens left in the bucket
+    pub remaining: u32,
+    /// Time until the bucket is full again
+    pub reset: Duration,
+    /// Time until the next token is available, zero if request was allowed
+    pub retry_after: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota() -> Quota {
+        Quota::per_minute(NonZeroU32::new(60).unwrap(), NonZeroU32::new(3).unwrap())
+    }
+
+    #[test]
+    fn take_until_empty() {
+        let quota = quota();
+        let mut reset = Duration::ZERO;
+
+        for remaining in [2, 1, 0] {
+            let decision = quota.take(reset);
+
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+            reset = decision.reset;
+        }
+
+        assert_eq!(
+            quota.take(reset),
+            RateLimitDecision {
+                allowed: false,
+                remaining: 0,
+                reset: Duration::from_secs(3),
+                retry_after: Duration::from_secs(1),
+            }
+        );
+    }
+
+    #[test]
+    fn take_partially_refilled() {
+        let decision = quota().take(Duration::from_millis(1500));
+
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.reset, Duration::from_millis(2500));
+
+        let decision = quota().take(Duration::from_millis(2500));
+
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after, Duration::from_millis(500));
+    }
+}
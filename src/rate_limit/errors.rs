@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use crate::dao::ErrorVariant;
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RateLimitStoreError {
+    #[error("Cannot access rate limit store. This error was a direct following of: {internal}")]
+    Storage { internal: String },
+}
+
+impl ErrorVariant for RateLimitStoreError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Storage { .. } => "RateLimitStoreError::Storage",
+        }
+    }
+}
+
+impl From<redis::RedisError> for RateLimitStoreError {
+    fn from(value: redis::RedisError) -> Self {
+        Self::Storage {
+            internal: value.to_string(),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use axum::async_trait;
+
+use crate::rate_limit::{Quota, RateLimitDecision, RateLimitStore, RateLimitStoreError};
+
+/// How often full buckets are dropped, they are the same as ones never accessed
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Buckets {
+    /// Moments, when buckets become full again
+    full_at: HashMap<String, Instant>,
+    evicted_at: Option<Instant>,
+}
+
+/// Keeps moments, when buckets become full again
+#[derive(Default)]
+pub struct RateLimitMemoryStore(Mutex<Buckets>);
+
+impl RateLimitMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Scanning every bucket on each request would hold the lock for long under load,
+    // so full ones are dropped at most once per interval and read as full meanwhile
+    fn buckets(&self) -> MutexGuard<'_, Buckets> {
+        let mut buckets = self.0.lock().unwrap();
+        let now = Instant::now();
+        if buckets
+            .evicted_at
+            .map_or(true, |x| now.duration_since(x) >= EVICTION_INTERVAL)
+        {
+            buckets.full_at.retain(|_, full_at| *full_at > now);
+            buckets.evicted_at = Some(now);
+        }
+
+        buckets
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RateLimitMemoryStore {
+    async fn take(
+        &self,
+        key: &str,
+        quota: Quota,
+    ) -> Result<RateLimitDecision, RateLimitStoreError> {
+        let mut buckets = self.buckets();
+        let now = Instant::now();
+        let reset = buckets
+            .full_at
+            .get(key)
+            .map(|full_at| full_at.saturating_duration_since(now))
+            .unwrap_or_default();
+
+        let decision = quota.take(reset);
+        if decision.allowed {
+            buckets.full_at.insert(key.to_owned(), now + decision.reset);
+        }
+
+        Ok(decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn take() {
+        let store = RateLimitMemoryStore::new();
+        let quota = Quota::per_minute(NonZeroU32::new(1).unwrap(), NonZeroU32::new(2).unwrap());
+
+        assert!(store.take("foo", quota).await.unwrap().allowed);
+        assert!(store.take("foo", quota).await.unwrap().allowed);
+
+        let decision = store.take("foo", quota).await.unwrap();
+
+        assert!(!decision.allowed);
+        assert!(decision.retry_after > Duration::from_secs(59));
+        assert!(store.take("bar", quota).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn evicted_periodically() {
+        let store = RateLimitMemoryStore::new();
+        let quota = Quota::per_minute(NonZeroU32::new(1).unwrap(), NonZeroU32::new(1).unwrap());
+        store.take("foo", quota).await.unwrap();
+        {
+            let mut buckets = store.buckets();
+            let full_at = buckets.full_at.get_mut("foo").unwrap();
+            *full_at = full_at.checked_sub(Duration::from_secs(60)).unwrap();
+        }
+
+        // Full bucket is kept until the interval passes, but it's already read as full
+        assert!(store.take("bar", quota).await.unwrap().allowed);
+        assert!(store.buckets().full_at.contains_key("foo"));
+        assert_eq!(store.take("foo", quota).await.unwrap().remaining, 0);
+
+        store
+            .buckets()
+            .full_at
+            .insert("baz".to_owned(), Instant::now());
+        // Same as the interval having passed
+        store.buckets().evicted_at = None;
+
+        assert!(!store.buckets().full_at.contains_key("baz"));
+    }
+}
@@ -0,0 +1,5 @@
+pub use memory::RateLimitMemoryStore;
+pub use redis::RateLimitRedisStore;
+
+mod memory;
+mod redis;
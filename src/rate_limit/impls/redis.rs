@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use axum::async_trait;
+use redis::{aio::ConnectionManager, Script};
+
+use crate::rate_limit::{Quota, RateLimitDecision, RateLimitStore, RateLimitStoreError};
+
+const KEY_PREFIX: &str = "rate_limit:";
+// Same algorithm as `Quota::take` in milliseconds. It runs atomically and on Redis clock,
+// so instances sharing the store never race or disagree on time
+const TAKE_SCRIPT: &str = r"
+local burst = tonumber(ARGV[1])
+local interval = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local reset = math.max((tonumber(redis.call('GET', KEYS[1])) or now) - now, 0)
+local taken = reset + interval
+if taken > burst * interval then
+    return {0, reset, taken - burst * interval}
+end
+redis.call('SET', KEYS[1], now + taken, 'PX', taken)
+return {1, taken, 0}
+";
+
+#[derive(Clone)]
+pub struct RateLimitRedisStore(ConnectionManager, Script);
+
+impl RateLimitRedisStore {
+    pub async fn new(dsn: &str) -> Result<Self, RateLimitStoreError> {
+        let client = redis::Client::open(dsn)?;
+
+        Ok(Self(
+            ConnectionManager::new(client).await?,
+            Script::new(TAKE_SCRIPT),
+        ))
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RateLimitRedisStore {
+    async fn take(
+        &self,
+        key: &str,
+        quota: Quota,
+    ) -> Result<RateLimitDecision, RateLimitStoreError> {
+        // Redis clock has millisecond precision, so faster refill is rounded down to it
+        let interval = u64::try_from(quota.interval.as_millis())
+            .unwrap_or(u64::MAX)
+            .max(1);
+        let (allowed, reset, retry_after): (bool, u64, u64) = self
+            .1
+            .key(format!("{KEY_PREFIX}{key}"))
+            .arg(quota.burst.get())
+            .arg(interval)
+            .invoke_async(&mut self.0.clone())
+            .await?;
+        let reset = Duration::from_millis(reset);
+
+        Ok(RateLimitDecision {
+            allowed,
+            remaining: quota.remaining(reset),
+            reset,
+            retry_after: Duration::from_millis(retry_after),
+        })
+    }
+}
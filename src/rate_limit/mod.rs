@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+pub use dtos::{Quota, RateLimitDecision};
+pub use errors::RateLimitStoreError;
+pub use impls::{RateLimitMemoryStore, RateLimitRedisStore};
+
+mod dtos;
+mod errors;
+mod impls;
+
+#[async_trait]
+pub trait RateLimitStore {
+    /// Takes a token from the bucket with given key, which is full on the first access.
+    /// Nothing is taken if the bucket is empty
+    async fn take(&self, key: &str, quota: Quota)
+        -> Result<RateLimitDecision, RateLimitStoreError>;
+}
+
+#[async_trait]
+impl<T> RateLimitStore for Arc<T>
+where
+    T: RateLimitStore + Send + Sync + ?Sized,
+{
+    async fn take(
+        &self,
+        key: &str,
+        quota: Quota,
+    ) -> Result<RateLimitDecision, RateLimitStoreError> {
+        self.as_ref().take(key, quota).await
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::BTreeSet, io, path::PathBuf, time::Duration};
+
+use axum_server::tls_rustls::RustlsConfig;
+use notify::{Event, RecursiveMode, Watcher};
+use rustls::crypto::ring;
+use tokio::{sync::mpsc, task::JoinHandle, time::sleep};
+use tracing::{error, info};
+
+use super::errors::TlsError;
+
+// Renewals usually write certificate and key one after another, so reload waits for both
+const RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+/// PEM files of certificate chain and its private key
+#[derive(Clone, Debug)]
+pub struct CertificateFiles {
+    pub certificate: PathBuf,
+    pub key: PathBuf,
+}
+
+impl CertificateFiles {
+    pub async fn load(&self) -> Result<RustlsConfig, TlsError> {
+        // Provider is installed once per process, so error only means it's already there
+        let _ = ring::default_provider().install_default();
+
+        RustlsConfig::from_pem_file(&self.certificate, &self.key)
+            .await
+            .map_err(|err| self.loading_error(&err))
+    }
+
+    /// Reloads certificate into `config` whenever its files change, e.g. once it's renewed.
+    /// Directories are watched instead of files, since renewals often replace them or swap
+    /// symlinks. Config keeps the previous certificate, if the new one fails to load
+    pub fn watch(self, config: RustlsConfig) -> Result<JoinHandle<()>, TlsError> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            // Reads are reported as well, including our own reloads
+            if event.is_ok_and(|x| !x.kind.is_access()) {
+                let _ = sender.send(());
+            }
+        })?;
+        for directory in self.directories() {
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok(tokio::spawn(async move {
+            // Files are watched as long as watcher lives
+            let _watcher = watcher;
+
+            while receiver.recv().await.is_some() {
+                sleep(RELOAD_DELAY).await;
+                while receiver.try_recv().is_ok() {}
+
+                match config
+                    .reload_from_pem_file(&self.certificate, &self.key)
+                    .await
+                {
+                    Ok(()) => info!("Reloaded TLS certificate from {:?}", self.certificate),
+                    Err(err) => error!("{}", self.loading_error(&err)),
+                }
+            }
+        }))
+    }
+
+    fn directories(&self) -> BTreeSet<PathBuf> {
+        [&self.certificate, &self.key]
+            .into_iter()
+            .map(|path| match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+                _ => PathBuf::from("."),
+            })
+            .collect()
+    }
+
+    fn loading_error(&self, err: &io::Error) -> TlsError {
+        TlsError::Loading {
+            certificate: self.certificate.clone(),
+            key: self.key.clone(),
+            internal: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, sync::Arc};
+
+    use tokio::time::timeout;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::dao::ErrorVariant;
+
+    // Self-signed for localhost, used by tests only
+    const CERTIFICATE: &str = include_str!("testdata/certificate.pem");
+    const KEY: &str = include_str!("testdata/key.pem");
+
+    fn files() -> CertificateFiles {
+        let directory = env::temp_dir().join(Uuid::new_v4().to_string());
+        fs::create_dir(&directory).unwrap();
+
+        let files = CertificateFiles {
+            certificate: directory.join("certificate.pem"),
+            key: directory.join("key.pem"),
+        };
+        fs::write(&files.certificate, CERTIFICATE).unwrap();
+        fs::write(&files.key, KEY).unwrap();
+
+        files
+    }
+
+    #[tokio::test]
+    async fn load() {
+        assert!(files().load().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn load_missing() {
+        let files = CertificateFiles {
+            key: PathBuf::from("missing.pem"),
+            ..files()
+        };
+
+        assert_eq!(
+            files.load().await.unwrap_err().variant(),
+            "TlsError::Loading"
+        );
+    }
+
+    #[tokio::test]
+    async fn reload_on_change() {
+        let files = files();
+        let config = files.load().await.unwrap();
+        let loaded = config.get_inner();
+        let watch = files.clone().watch(config.clone()).unwrap();
+
+        // Broken certificate is not loaded, while the next fixed one is
+        fs::write(&files.certificate, "").unwrap();
+        sleep(RELOAD_DELAY * 2).await;
+
+        assert!(Arc::ptr_eq(&loaded, &config.get_inner()));
+
+        fs::write(&files.certificate, CERTIFICATE).unwrap();
+        timeout(Duration::from_secs(5), async {
+            while Arc::ptr_eq(&loaded, &config.get_inner()) {
+                sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        watch.abort();
+    }
+}
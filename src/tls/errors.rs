@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::dao::ErrorVariant;
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum TlsError {
+    #[error(
+        "Cannot load TLS certificate from {certificate:?} and {key:?}. \
+         This error was a direct following of: {internal}"
+    )]
+    Loading {
+        certificate: PathBuf,
+        key: PathBuf,
+        internal: String,
+    },
+    #[error(
+        "Cannot watch TLS certificate files. This error was a direct following of: {internal}"
+    )]
+    Watching { internal: String },
+}
+
+impl ErrorVariant for TlsError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::Loading { .. } => "TlsError::Loading",
+            Self::Watching { .. } => "TlsError::Watching",
+        }
+    }
+}
+
+impl From<notify::Error> for TlsError {
+    fn from(value: notify::Error) -> Self {
+        Self::Watching {
+            internal: value.to_string(),
+        }
+    }
+}
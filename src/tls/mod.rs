@@ -0,0 +1,6 @@
+pub use certificate::CertificateFiles;
+pub use redirect::HttpsRedirectRouter;
+
+mod certificate;
+mod errors;
+mod redirect;
@@ -0,0 +1,130 @@
+use axum::{
+    extract::State,
+    http::{header::HOST, uri::Authority, HeaderMap, StatusCode, Uri},
+    response::Redirect,
+    Router,
+};
+
+const HTTPS_PORT: u16 = 443;
+
+/// Redirects every plain HTTP request to the same URL on HTTPS port
+pub struct HttpsRedirectRouter {
+    pub https_port: u16,
+}
+
+impl From<HttpsRedirectRouter> for Router {
+    fn from(value: HttpsRedirectRouter) -> Self {
+        Router::new()
+            .fallback(redirect)
+            .with_state(value.https_port)
+    }
+}
+
+// `Host` extractor would trust `X-Forwarded-Host`, which anyone can send to get redirected to
+// an arbitrary site, so only the host of the request itself is used
+async fn redirect(
+    State(https_port): State<u16>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Result<Redirect, StatusCode> {
+    let authority = match headers.get(HOST) {
+        Some(host) => Authority::try_from(host.as_bytes()).map_err(|_| StatusCode::BAD_REQUEST)?,
+        // HTTP/2 requests carry it in URI instead
+        None => uri.authority().cloned().ok_or(StatusCode::BAD_REQUEST)?,
+    };
+    let path = uri.path_and_query().map_or("/", |x| x.as_str());
+
+    // Permanent redirect keeps method and body, unlike 301
+    Ok(Redirect::permanent(&if https_port == HTTPS_PORT {
+        format!("https://{}{path}", authority.host())
+    } else {
+        format!("https://{}:{https_port}{path}", authority.host())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header::LOCATION, Request},
+    };
+    use rstest::rstest;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        "example.com",
+        "/v1/items?page=2",
+        443,
+        "https://example.com/v1/items?page=2"
+    )]
+    #[case("example.com:8080", "/", 8443, "https://example.com:8443/")]
+    #[case("[::1]:80", "/health", 443, "https://[::1]/health")]
+    #[tokio::test]
+    async fn redirect(
+        #[case] host: &str,
+        #[case] uri: &str,
+        #[case] https_port: u16,
+        #[case] expected: &str,
+    ) {
+        let router = Router::from(HttpsRedirectRouter { https_port });
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("host", host)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers().get(LOCATION).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn malformed_host() {
+        let router = Router::from(HttpsRedirectRouter { https_port: 443 });
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("host", "exa mple.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn forwarded_host_ignored() {
+        let router = Router::from(HttpsRedirectRouter { https_port: 443 });
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("host", "example.com")
+                    .header("x-forwarded-host", "evil.com")
+                    .header("forwarded", "host=evil.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/"
+        );
+    }
+}
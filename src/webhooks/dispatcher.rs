@@ -0,0 +1,556 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use chrono::Utc;
+use reqwest::{header::CONTENT_TYPE, redirect, Client, Url};
+use serde::Serialize;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::{
+    dtos::{Delivery, Webhook, WebhookEvent, WebhookEventType},
+    errors::{RegisterWebhookError, WebhookError},
+    signature::sign,
+    target::{is_public, PublicResolver},
+};
+
+pub const WEBHOOK_ID_HEADER: &str = "webhook-id";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "webhook-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "webhook-signature";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: NonZeroU32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay after a failed attempt, doubled with every attempt
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    webhooks: HashMap<Uuid, Webhook>,
+    deliveries: HashMap<Uuid, VecDeque<Delivery>>,
+}
+
+/// Registry of webhooks, which delivers events to them in background
+#[derive(Clone)]
+pub struct Webhooks {
+    registry: Arc<RwLock<Registry>>,
+    client: Client,
+    retry: RetryPolicy,
+    log_size: usize,
+    allow_private_targets: bool,
+}
+
+impl Webhooks {
+    /// Unless `allow_private_targets` is set, webhooks may only target public addresses
+    pub fn new(retry: RetryPolicy, log_size: NonZeroUsize, allow_private_targets: bool) -> Self {
+        // Redirects aren't followed, since their targets would escape the checks
+        let mut client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(redirect::Policy::none());
+        if !allow_private_targets {
+            client = client.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        Self {
+            registry: Arc::default(),
+            client: client.build().unwrap(),
+            retry,
+            log_size: log_size.get(),
+            allow_private_targets,
+        }
+    }
+
+    /// Registers webhook of the user, who is the only one to see and manage it afterwards
+    pub fn register(
+        &self,
+        owner: Uuid,
+        url: &str,
+        events: Vec<WebhookEventType>,
+    ) -> Result<Webhook, RegisterWebhookError> {
+        let url = Url::parse(url).map_err(|_| RegisterWebhookError::InvalidUrl {
+            url: url.to_owned(),
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(RegisterWebhookError::UnsupportedScheme {
+                scheme: url.scheme().to_owned(),
+            });
+        }
+        // Names are checked once resolved on delivery, address literals bypass resolver though
+        let address = url
+            .host_str()
+            .map(|x| x.trim_start_matches('[').trim_end_matches(']'))
+            .and_then(|x| x.parse::<IpAddr>().ok());
+        if !self.allow_private_targets && address.is_some_and(|x| !is_public(x)) {
+            return Err(RegisterWebhookError::ForbiddenTarget {
+                host: url.host_str().unwrap_or_default().to_owned(),
+            });
+        }
+        if events.is_empty() {
+            return Err(RegisterWebhookError::NoEvents);
+        }
+
+        let webhook = Webhook {
+            id: Uuid::new_v4(),
+            owner,
+            url,
+            events,
+            // Random part of v4 UUIDs comes from a secure source, 244 bits in total
+            secret: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+            created_at: Utc::now().naive_utc(),
+        };
+        let mut registry = self.registry.write().unwrap();
+        registry.webhooks.insert(webhook.id, webhook.clone());
+        registry.deliveries.insert(webhook.id, VecDeque::new());
+
+        Ok(webhook)
+    }
+
+    pub fn list(&self, owner: Uuid) -> Vec<Webhook> {
+        let mut result: Vec<Webhook> = self
+            .registry
+            .read()
+            .unwrap()
+            .webhooks
+            .values()
+            .filter(|x| x.owner == owner)
+            .cloned()
+            .collect();
+        result.sort_by_key(|x| (x.created_at, x.id));
+
+        result
+    }
+
+    /// Webhooks of other users are reported missing, so their ids can't be probed
+    pub fn get(&self, owner: Uuid, id: Uuid) -> Result<Webhook, WebhookError> {
+        self.registry
+            .read()
+            .unwrap()
+            .webhooks
+            .get(&id)
+            .filter(|x| x.owner == owner)
+            .cloned()
+            .ok_or(WebhookError::NoSuchWebhook { id })
+    }
+
+    pub fn unregister(&self, owner: Uuid, id: Uuid) -> Result<(), WebhookError> {
+        let mut registry = self.registry.write().unwrap();
+        if !registry.webhooks.get(&id).is_some_and(|x| x.owner == owner) {
+            return Err(WebhookError::NoSuchWebhook { id });
+        }
+        registry.webhooks.remove(&id);
+        registry.deliveries.remove(&id);
+
+        Ok(())
+    }
+
+    /// Latest delivery attempts, most recent first
+    pub fn deliveries(&self, owner: Uuid, id: Uuid) -> Result<Vec<Delivery>, WebhookError> {
+        let registry = self.registry.read().unwrap();
+        if !registry.webhooks.get(&id).is_some_and(|x| x.owner == owner) {
+            return Err(WebhookError::NoSuchWebhook { id });
+        }
+
+        Ok(registry
+            .deliveries
+            .get(&id)
+            .map(|x| x.iter().rev().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Schedules delivery of event to every webhook of the owner of affected entity, which is
+    /// subscribed to its type. Users own themselves, so their events are delivered to them only
+    pub fn notify(&self, owner: Uuid, event_type: WebhookEventType, data: &impl Serialize) {
+        let event = match serde_json::to_value(data) {
+            Ok(data) => WebhookEvent {
+                id: Uuid::new_v4(),
+                event_type,
+                created_at: Utc::now().naive_utc(),
+                data,
+            },
+            Err(err) => {
+                error!("Cannot serialize {event_type:?} webhook event: {err}");
+                return;
+            }
+        };
+        let Ok(body) = serde_json::to_string(&event) else {
+            error!("Cannot serialize {event_type:?} webhook event");
+            return;
+        };
+        let body = Arc::new(body);
+
+        let subscribed: Vec<Webhook> = self
+            .registry
+            .read()
+            .unwrap()
+            .webhooks
+            .values()
+            .filter(|x| x.owner == owner && x.events.contains(&event_type))
+            .cloned()
+            .collect();
+        for webhook in subscribed {
+            tokio::spawn(self.clone().deliver(webhook, event.clone(), body.clone()));
+        }
+    }
+
+    async fn deliver(self, webhook: Webhook, event: WebhookEvent, body: Arc<String>) {
+        for attempt in 1..=self.retry.max_attempts.get() {
+            let timestamp = Utc::now().timestamp();
+            let result = self
+                .client
+                .post(webhook.url.clone())
+                .header(CONTENT_TYPE, "application/json")
+                .header(WEBHOOK_ID_HEADER, event.id.to_string())
+                .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+                .header(
+                    WEBHOOK_SIGNATURE_HEADER,
+                    sign(&webhook.secret, timestamp, &body),
+                )
+                .body(body.as_str().to_owned())
+                .send()
+                .await;
+
+            let delivery = Delivery {
+                id: Uuid::new_v4(),
+                event_id: event.id,
+                event_type: event.event_type,
+                attempt,
+                status: result.as_ref().ok().map(|x| x.status().as_u16()),
+                error: result.as_ref().err().map(ToString::to_string),
+                attempted_at: Utc::now().naive_utc(),
+            };
+            let succeeded = result.is_ok_and(|x| x.status().is_success());
+
+            // Webhook may be unregistered in between, then there is nobody to retry for
+            if !self.log(webhook.id, delivery) || succeeded {
+                return;
+            }
+
+            if attempt < self.retry.max_attempts.get() {
+                tokio::time::sleep(self.retry.backoff(attempt)).await;
+            }
+        }
+
+        warn!(
+            "Giving up delivery of event {} to webhook {}",
+            event.id, webhook.id
+        );
+    }
+
+    fn log(&self, id: Uuid, delivery: Delivery) -> bool {
+        let mut registry = self.registry.write().unwrap();
+        let Some(deliveries) = registry.deliveries.get_mut(&id) else {
+            return false;
+        };
+
+        if deliveries.len() == self.log_size {
+            deliveries.pop_front();
+        }
+        deliveries.push_back(delivery);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use axum::{
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use rstest::rstest;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::dao::ErrorVariant;
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+    const OWNER: Uuid = Uuid::from_u128(42);
+
+    /// Webhooks, which may target `allow_private_targets`, e.g. local receivers below
+    fn webhooks_with(allow_private_targets: bool) -> Webhooks {
+        Webhooks::new(
+            RetryPolicy {
+                max_attempts: NonZeroU32::new(3).unwrap(),
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(20),
+            },
+            NonZeroUsize::new(16).unwrap(),
+            allow_private_targets,
+        )
+    }
+
+    fn webhooks() -> Webhooks {
+        webhooks_with(true)
+    }
+
+    /// Local receiver, which fails the first `failures` requests with 500
+    async fn receiver(failures: usize) -> (String, Received) {
+        let received = Received::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = Router::new().route(
+            "/hook",
+            post({
+                let received = received.clone();
+                move |headers: HeaderMap, body: String| async move {
+                    received.lock().unwrap().push((headers, body));
+                    if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    } else {
+                        StatusCode::NO_CONTENT
+                    }
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        (url, received)
+    }
+
+    async fn wait_for_deliveries(webhooks: &Webhooks, id: Uuid, count: usize) -> Vec<Delivery> {
+        for _ in 0..100 {
+            let deliveries = webhooks.deliveries(OWNER, id).unwrap();
+            if deliveries.len() >= count {
+                return deliveries;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("Expected {count} deliveries to webhook {id}");
+    }
+
+    #[tokio::test]
+    async fn delivered_signed() {
+        let (url, received) = receiver(0).await;
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::ItemUpdated])
+            .unwrap();
+
+        webhooks.notify(OWNER, WebhookEventType::ItemUpdated, &json!({"id": 1}));
+        let deliveries = wait_for_deliveries(&webhooks, webhook.id, 1).await;
+        println!("{deliveries:#?}");
+
+        assert_eq!(deliveries[0].status, Some(204));
+        assert_eq!(deliveries[0].attempt, 1);
+
+        let (headers, body) = received.lock().unwrap()[0].clone();
+        let timestamp: i64 = headers[WEBHOOK_TIMESTAMP_HEADER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            headers[WEBHOOK_SIGNATURE_HEADER],
+            sign(&webhook.secret, timestamp, &body)
+        );
+        assert_eq!(
+            headers[WEBHOOK_ID_HEADER],
+            deliveries[0].event_id.to_string()
+        );
+
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["type"], "item.updated");
+        assert_eq!(body["data"], json!({"id": 1}));
+    }
+
+    #[tokio::test]
+    async fn retried() {
+        let (url, received) = receiver(1).await;
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::ItemDeleted])
+            .unwrap();
+
+        webhooks.notify(OWNER, WebhookEventType::ItemDeleted, &json!({}));
+        let deliveries = wait_for_deliveries(&webhooks, webhook.id, 2).await;
+        println!("{deliveries:#?}");
+
+        assert_eq!(
+            deliveries
+                .iter()
+                .map(|x| (x.attempt, x.status))
+                .collect::<Vec<_>>(),
+            [(2, Some(204)), (1, Some(500))]
+        );
+        assert_eq!(deliveries[0].event_id, deliveries[1].event_id);
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unreachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::UserCreated])
+            .unwrap();
+
+        webhooks.notify(OWNER, WebhookEventType::UserCreated, &json!({}));
+        let deliveries = wait_for_deliveries(&webhooks, webhook.id, 3).await;
+
+        assert!(deliveries
+            .iter()
+            .all(|x| x.status.is_none() && x.error.is_some()));
+    }
+
+    #[tokio::test]
+    async fn not_subscribed() {
+        let (url, received) = receiver(0).await;
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::ItemCreated])
+            .unwrap();
+
+        webhooks.notify(OWNER, WebhookEventType::ItemDeleted, &json!({}));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(webhooks.deliveries(OWNER, webhook.id).unwrap().is_empty());
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn other_owner() {
+        let (url, received) = receiver(0).await;
+        let (other_url, other_received) = receiver(0).await;
+        let webhooks = webhooks();
+        let other = Uuid::new_v4();
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::ItemCreated])
+            .unwrap();
+        let other_webhook = webhooks
+            .register(other, &other_url, vec![WebhookEventType::ItemCreated])
+            .unwrap();
+
+        // Events of an owner are delivered to webhooks of the owner only
+        webhooks.notify(OWNER, WebhookEventType::ItemCreated, &json!({}));
+        wait_for_deliveries(&webhooks, webhook.id, 1).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert!(webhooks
+            .deliveries(other, other_webhook.id)
+            .unwrap()
+            .is_empty());
+        assert!(other_received.lock().unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case::invalid_url("not a url", vec![WebhookEventType::ItemCreated], "RegisterWebhookError::InvalidUrl")]
+    #[case::scheme("ftp://localhost/", vec![WebhookEventType::ItemCreated], "RegisterWebhookError::UnsupportedScheme")]
+    #[case::no_events("http://localhost/", vec![], "RegisterWebhookError::NoEvents")]
+    #[case::loopback("http://127.0.0.1/", vec![WebhookEventType::ItemCreated], "RegisterWebhookError::ForbiddenTarget")]
+    #[case::metadata("http://169.254.169.254/latest/meta-data/", vec![WebhookEventType::ItemCreated], "RegisterWebhookError::ForbiddenTarget")]
+    #[case::loopback_v6("http://[::1]/", vec![WebhookEventType::ItemCreated], "RegisterWebhookError::ForbiddenTarget")]
+    fn register_invalid(
+        #[case] url: &str,
+        #[case] events: Vec<WebhookEventType>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            webhooks_with(false)
+                .register(OWNER, url, events)
+                .unwrap_err()
+                .variant(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn private_target_not_delivered() {
+        let (url, received) = receiver(0).await;
+        let url = url.replace("127.0.0.1", "localhost");
+        let webhooks = webhooks_with(false);
+        let webhook = webhooks
+            .register(OWNER, &url, vec![WebhookEventType::ItemCreated])
+            .unwrap();
+
+        webhooks.notify(OWNER, WebhookEventType::ItemCreated, &json!({}));
+        let deliveries = wait_for_deliveries(&webhooks, webhook.id, 3).await;
+        println!("{deliveries:#?}");
+
+        assert!(deliveries
+            .iter()
+            .all(|x| x.status.is_none() && x.error.is_some()));
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unregister() {
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(
+                OWNER,
+                "http://localhost/",
+                vec![WebhookEventType::ItemCreated],
+            )
+            .unwrap();
+
+        webhooks.unregister(OWNER, webhook.id).unwrap();
+
+        assert_eq!(
+            webhooks.get(OWNER, webhook.id).unwrap_err(),
+            WebhookError::NoSuchWebhook { id: webhook.id }
+        );
+        assert!(webhooks.list(OWNER).is_empty());
+    }
+
+    #[test]
+    fn owned() {
+        let webhooks = webhooks();
+        let webhook = webhooks
+            .register(
+                OWNER,
+                "http://localhost/",
+                vec![WebhookEventType::ItemCreated],
+            )
+            .unwrap();
+        let other = Uuid::new_v4();
+
+        assert!(webhooks.list(other).is_empty());
+        assert!(webhooks.get(other, webhook.id).is_err());
+        assert!(webhooks.deliveries(other, webhook.id).is_err());
+        assert!(webhooks.unregister(other, webhook.id).is_err());
+        assert_eq!(webhooks.list(OWNER).len(), 1);
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy {
+            max_attempts: NonZeroU32::new(10).unwrap(),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+
+        assert_eq!(
+            (1..=5).map(|x| policy.backoff(x)).collect::<Vec<_>>(),
+            [1, 2, 4, 5, 5].map(Duration::from_secs)
+        );
+    }
+}
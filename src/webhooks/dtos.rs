@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEventType {
+    #[serde(rename = "item.created")]
+    ItemCreated,
+    #[serde(rename = "item.updated")]
+    ItemUpdated,
+    #[serde(rename = "item.deleted")]
+    ItemDeleted,
+    #[serde(rename = "user.created")]
+    UserCreated,
+    #[serde(rename = "user.updated")]
+    UserUpdated,
+    #[serde(rename = "user.deleted")]
+    UserDeleted,
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: Uuid,
+    /// Id of the user, who has registered the webhook
+    pub owner: Uuid,
+    pub url: Url,
+    pub events: Vec<WebhookEventType>,
+    /// Key of HMAC-SHA256 signatures, only revealed on registration
+    pub secret: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Body of delivery request, `data` holds representation of affected entity
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub created_at: NaiveDateTime,
+    pub data: Value,
+}
+
+/// Single attempt to deliver an event
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub attempt: u32,
+    /// Response status, absent if no response was received
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub attempted_at: NaiveDateTime,
+}
@@ -0,0 +1,43 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::dao::ErrorVariant;
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum WebhookError {
+    #[error("Webhook with id '{id:?}' doesn't exist in our records")]
+    NoSuchWebhook { id: Uuid },
+}
+
+impl ErrorVariant for WebhookError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::NoSuchWebhook { .. } => "WebhookError::NoSuchWebhook",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RegisterWebhookError {
+    #[error("Webhook URL '{url}' is not a valid absolute URL")]
+    InvalidUrl { url: String },
+    #[error("Webhook URL scheme '{scheme}' is not supported, use 'http' or 'https'")]
+    UnsupportedScheme { scheme: String },
+    #[error("Webhook URL host '{host}' is not a public address")]
+    ForbiddenTarget { host: String },
+    #[error("Webhook must subscribe to at least one event type")]
+    NoEvents,
+}
+
+impl ErrorVariant for RegisterWebhookError {
+    fn variant(&self) -> &'static str {
+        match self {
+            Self::InvalidUrl { .. } => "RegisterWebhookError::InvalidUrl",
+            Self::UnsupportedScheme { .. } => "RegisterWebhookError::UnsupportedScheme",
+            Self::ForbiddenTarget { .. } => "RegisterWebhookError::ForbiddenTarget",
+            Self::NoEvents => "RegisterWebhookError::NoEvents",
+        }
+    }
+}
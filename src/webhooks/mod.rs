@@ -0,0 +1,9 @@
+pub use dispatcher::{RetryPolicy, Webhooks};
+pub use dtos::{Delivery, Webhook, WebhookEventType};
+pub use errors::{RegisterWebhookError, WebhookError};
+
+mod dispatcher;
+mod dtos;
+mod errors;
+mod signature;
+mod target;
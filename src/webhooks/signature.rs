@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const SIGNATURE_PREFIX: &str = "sha256=";
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    // HMAC accepts keys of any length, so it never fails
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(message);
+
+    mac.finalize().into_bytes().into()
+}
+
+/// Signature of delivery: timestamp is signed along with body, so a captured request can't be
+/// replayed later with a fresh timestamp
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let digest = hmac_sha256(secret.as_bytes(), format!("{timestamp}.{body}").as_bytes());
+
+    format!("{SIGNATURE_PREFIX}{}", hex(&digest))
+}
+
+fn hex(value: &[u8]) -> String {
+    value.iter().fold(String::new(), |mut result, x| {
+        let _ = write!(result, "{x:02x}");
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    // Test cases 2 and 6 of RFC 4231
+    #[rstest]
+    #[case::short_key(
+        b"Jefe",
+        b"what do ya want for nothing?",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    )]
+    #[case::long_key(
+        &[0xaa; 131],
+        b"Test Using Larger Than Block-Size Key - Hash Key First",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    )]
+    fn hmac(#[case] key: &[u8], #[case] message: &[u8], #[case] expected: &str) {
+        assert_eq!(hex(&hmac_sha256(key, message)), expected);
+    }
+
+    #[test]
+    fn signature() {
+        let signature = sign("secret", 1_700_000_000, "{}");
+
+        assert!(signature.starts_with(SIGNATURE_PREFIX));
+        assert_eq!(signature, sign("secret", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign("secret", 1_700_000_001, "{}"));
+        assert_ne!(signature, sign("other", 1_700_000_000, "{}"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Guards against webhooks being pointed at the server's own network, e.g. admin endpoints on
+//! loopback or credentials served by cloud metadata services
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::net::lookup_host;
+
+/// Whether address is routable on the public internet, so a webhook may target it
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, third, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        // Covers 169.254.169.254 metadata service of most clouds
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network" 0.0.0.0/8
+        || first == 0
+        // Shared address space 100.64.0.0/10, covers 100.100.100.200 metadata service
+        || (first == 100 && second & 0xc0 == 64)
+        // IETF protocol assignments 192.0.0.0/24, covers 192.0.0.192 metadata service
+        || (first == 192 && second == 0 && third == 0)
+        // Benchmarking 198.18.0.0/15
+        || (first == 198 && second & 0xfe == 18)
+        // Reserved 240.0.0.0/4
+        || first >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local fc00::/7, covers fd00:ec2::254 metadata service
+        || first & 0xfe00 == 0xfc00
+        // Link-local fe80::/10
+        || first & 0xffc0 == 0xfe80
+        // Documentation 2001:db8::/32
+        || (first == 0x2001 && second == 0x0db8))
+}
+
+/// Resolves names of webhook hosts, dropping addresses, which aren't public. Checking addresses
+/// at connection time, rather than on registration, keeps DNS rebinding from sneaking them in
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            // Port is replaced with the one of URL by the client
+            let addrs: Vec<SocketAddr> = lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|x| is_public(x.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} resolves to no public address", name.as_str()),
+                )
+                .into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::public_v4("93.184.215.14", true)]
+    #[case::public_v6("2606:2800:21f:cb07:6820:80da:af6b:8b2c", true)]
+    #[case::loopback("127.0.0.1", false)]
+    #[case::private("10.1.2.3", false)]
+    #[case::private_172("172.16.0.1", false)]
+    #[case::private_192("192.168.1.1", false)]
+    #[case::metadata("169.254.169.254", false)]
+    #[case::metadata_alibaba("100.100.100.200", false)]
+    #[case::unspecified("0.0.0.0", false)]
+    #[case::loopback_v6("::1", false)]
+    #[case::mapped_loopback("::ffff:127.0.0.1", false)]
+    #[case::unique_local("fd00:ec2::254", false)]
+    #[case::link_local_v6("fe80::1", false)]
+    fn public(#[case] ip: &str, #[case] expected: bool) {
+        assert_eq!(is_public(IpAddr::from_str(ip).unwrap()), expected);
+    }
+
+    #[tokio::test]
+    async fn resolver_rejects_loopback() {
+        let result = PublicResolver
+            .resolve(Name::from_str("localhost").unwrap())
+            .await;
+
+        assert!(result.is_err());
+    }
+}